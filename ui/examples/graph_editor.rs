@@ -1,10 +1,22 @@
-use egui::{CentralPanel, Color32, ComboBox, Id, Pos2, SidePanel, Slider, Ui};
+use egui::{CentralPanel, Color32, ComboBox, DragValue, Id, Key, Modifiers, SidePanel, Slider, Ui};
 use egui_extras::{Size, StripBuilder};
+use shine_core::smallbox::smallbox;
 use shine_ui::node_graph::{
-    Connection, ConnectionData, ContextMenu, ContextMenuData, Graph, GraphEdit, Input, InputId, InputPortData, Node,
-    NodeData, Output, OutputId, OutputPortData, PortStyle, PortStyles, Validator,
+    Action, ActionContext, ActionId, ActionMenuItem, Actions, BoxedInputPortData, BoxedNodeData, BoxedOutputPortData,
+    ConextMenuBuilder, Connection, ConnectionData, ConnectionId, ContextMenu, ContextMenuContext, ContextMenuData,
+    ContextMenuProvider, ContextMenuTarget, Graph, GraphEdit, GraphEvaluator, GraphExport, GraphTheme, HasProperties,
+    Input, InputId, InputOutputId, InputPortData, InteractionMode, KeyChord, Node, NodeBadge, NodeData, NodeId,
+    NodeInspector, NodeShape, NodeStyle, NodeStyles, NodeTask, Output, OutputId, OutputPortData, PortShape, PortStyle,
+    PortStyles, PortValueSource, ProblemsList, Properties, Property, PropertyPanel, PropertyValue, Selection,
+    SharedTemplateLibrary, TaskContext, TemplateMenu, Validator, ValuePreview,
+};
+use std::{
+    any::TypeId,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    thread,
+    time::Duration,
 };
-use std::any::TypeId;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum SideTool {
@@ -24,7 +36,8 @@ enum MyContextMenuData {
 }
 
 impl ContextMenuData for MyContextMenuData {
-    fn on_select(&self, graph: &mut Graph, location: Pos2) {
+    fn on_select(&self, graph: &mut Graph, context: &ContextMenuContext) {
+        let location = context.location;
         match self {
             MyContextMenuData::AddMinimalNode => {
                 graph.add_node(Node::new("minimal", location, vec![], vec![]));
@@ -54,9 +67,11 @@ impl ContextMenuData for MyContextMenuData {
                     "complex",
                     location,
                     vec![
-                        Input::new::<u8>("in1").with(SampleInput { value: 10. }),
-                        Input::new::<u16>("in2"),
-                        Input::new::<u32>("in3"),
+                        Input::new::<u8>("in1")
+                            .with(SampleInput { value: 10. })
+                            .with_connection_limits(0, Some(1)),
+                        Input::new::<u16>("in2").with_required(true),
+                        Input::new::<u32>("in3").with_default(42u32),
                     ],
                     vec![Output::new::<u8>("calculated")],
                 ));
@@ -66,6 +81,115 @@ impl ContextMenuData for MyContextMenuData {
             }
         }
     }
+
+    fn is_visible(&self, _graph: &Graph, context: &ContextMenuContext) -> bool {
+        context.target.is_canvas()
+    }
+
+    fn is_enabled(&self, graph: &Graph, _context: &ContextMenuContext) -> bool {
+        match self {
+            MyContextMenuData::ClearGraph => graph.nodes().next().is_some(),
+            _ => true,
+        }
+    }
+}
+
+enum MyTargetMenuData {
+    RemoveNodes(Vec<NodeId>),
+    RemoveConnection(ConnectionId),
+    ToggleCollapsed(NodeId),
+    ToggleHideUnconnected(NodeId),
+    ToggleLocked(NodeId),
+}
+
+impl ContextMenuData for MyTargetMenuData {
+    fn on_select(&self, graph: &mut Graph, _context: &ContextMenuContext) {
+        match self {
+            MyTargetMenuData::RemoveNodes(node_ids) => {
+                for node_id in node_ids {
+                    graph.remove_node(*node_id);
+                }
+            }
+            MyTargetMenuData::RemoveConnection(connection_id) => graph.remove_connection(*connection_id),
+            MyTargetMenuData::ToggleCollapsed(node_id) => {
+                if let Some(node) = graph.node_mut(*node_id) {
+                    node.collapsed = !node.collapsed;
+                }
+            }
+            MyTargetMenuData::ToggleHideUnconnected(node_id) => {
+                if let Some(node) = graph.node_mut(*node_id) {
+                    node.hide_unconnected_ports = !node.hide_unconnected_ports;
+                }
+            }
+            MyTargetMenuData::ToggleLocked(node_id) => {
+                if let Some(node) = graph.node_mut(*node_id) {
+                    node.locked = !node.locked;
+                }
+            }
+        }
+    }
+
+    fn is_enabled(&self, graph: &Graph, context: &ContextMenuContext) -> bool {
+        let interaction = context.interaction;
+        match self {
+            MyTargetMenuData::RemoveNodes(node_ids) => {
+                interaction == InteractionMode::Full
+                    && node_ids
+                        .iter()
+                        .all(|node_id| graph.node(*node_id).map(|node| !node.locked).unwrap_or(false))
+            }
+            MyTargetMenuData::RemoveConnection(_) => interaction.allows_wiring(),
+            MyTargetMenuData::ToggleCollapsed(node_id) => graph
+                .node(*node_id)
+                .map(|node| node.is_layout_enabled(interaction))
+                .unwrap_or(false),
+            MyTargetMenuData::ToggleHideUnconnected(_) => interaction.allows_layout(),
+            MyTargetMenuData::ToggleLocked(_) => interaction == InteractionMode::Full,
+        }
+    }
+
+    fn is_checked(&self, graph: &Graph, _context: &ContextMenuContext) -> Option<bool> {
+        match self {
+            MyTargetMenuData::ToggleCollapsed(node_id) => graph.node(*node_id).map(|node| node.collapsed),
+            MyTargetMenuData::ToggleHideUnconnected(node_id) => {
+                graph.node(*node_id).map(|node| node.hide_unconnected_ports)
+            }
+            MyTargetMenuData::ToggleLocked(node_id) => graph.node(*node_id).map(|node| node.locked),
+            _ => None,
+        }
+    }
+}
+
+/// Add the items depending on the clicked element.
+struct MyTargetMenu;
+
+impl ContextMenuProvider for MyTargetMenu {
+    fn build(&self, graph: &Graph, context: &ContextMenuContext, builder: &mut ConextMenuBuilder<'_>) {
+        match context.target {
+            ContextMenuTarget::Node(node_id) => {
+                let caption = graph.node(node_id).map(|node| node.caption.clone()).unwrap_or_default();
+                builder.add_item(
+                    format!("remove {}", caption),
+                    MyTargetMenuData::RemoveNodes(vec![node_id]),
+                );
+                builder.add_item("collapsed", MyTargetMenuData::ToggleCollapsed(node_id));
+                builder.add_item(
+                    "hide unconnected ports",
+                    MyTargetMenuData::ToggleHideUnconnected(node_id),
+                );
+                builder.add_item("locked", MyTargetMenuData::ToggleLocked(node_id));
+                if !context.selection.is_empty() {
+                    builder.add_separator();
+                    builder.add_item("delete selected", ActionMenuItem(ActionId::Delete));
+                    builder.add_item("duplicate selected", ActionMenuItem(ActionId::Duplicate));
+                }
+            }
+            ContextMenuTarget::Connection(connection_id) => {
+                builder.add_item("remove connection", MyTargetMenuData::RemoveConnection(connection_id));
+            }
+            ContextMenuTarget::Canvas | ContextMenuTarget::Port(_) => {}
+        }
+    }
 }
 
 pub struct SampleInput {
@@ -73,8 +197,41 @@ pub struct SampleInput {
 }
 
 impl InputPortData for SampleInput {
-    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) {
-        ui.add(Slider::new(&mut self.value, 0.0..=100.0).text("percent"));
+    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        ui.add(Slider::new(&mut self.value, 0.0..=100.0).text("percent"))
+            .changed()
+    }
+
+    fn duplicate(&self) -> Option<BoxedInputPortData> {
+        Some(smallbox!(SampleInput { value: self.value }))
+    }
+}
+
+impl HasProperties for SampleInput {
+    fn properties(&self) -> Option<&dyn Properties> {
+        Some(self)
+    }
+
+    fn properties_mut(&mut self) -> Option<&mut dyn Properties> {
+        Some(self)
+    }
+}
+
+impl Properties for SampleInput {
+    fn properties(&self) -> Vec<Property> {
+        vec![Property::new("value", self.value)
+            .with_label("percent")
+            .with_range(0., 100.)]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) -> bool {
+        match (name, value) {
+            ("value", PropertyValue::Float(value)) => {
+                self.value = value as f32;
+                true
+            }
+            _ => false,
+        }
     }
 }
 
@@ -82,9 +239,17 @@ pub struct SampleOutput {
     value: String,
 }
 
+impl HasProperties for SampleOutput {}
+
 impl OutputPortData for SampleOutput {
-    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) {
-        ui.text_edit_singleline(&mut self.value);
+    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        ui.text_edit_singleline(&mut self.value).changed()
+    }
+
+    fn duplicate(&self) -> Option<BoxedOutputPortData> {
+        Some(smallbox!(SampleOutput {
+            value: self.value.clone()
+        }))
     }
 }
 
@@ -93,8 +258,50 @@ pub struct SampleNodeData {
 }
 
 impl NodeData for SampleNodeData {
-    fn show(&mut self, ui: &mut Ui, _inputs: &mut Vec<Input>, _outputs: &mut Vec<Output>) {
-        ui.text_edit_singleline(&mut self.value);
+    fn duplicate(&self) -> Option<BoxedNodeData> {
+        Some(smallbox!(SampleNodeData {
+            value: self.value.clone()
+        }))
+    }
+
+    fn badges(&self) -> Vec<NodeBadge> {
+        if self.value.is_empty() {
+            vec![NodeBadge::Warning]
+        } else {
+            vec![]
+        }
+    }
+
+    fn content_hash(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.value.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+}
+
+impl HasProperties for SampleNodeData {
+    fn properties(&self) -> Option<&dyn Properties> {
+        Some(self)
+    }
+
+    fn properties_mut(&mut self) -> Option<&mut dyn Properties> {
+        Some(self)
+    }
+}
+
+impl Properties for SampleNodeData {
+    fn properties(&self) -> Vec<Property> {
+        vec![Property::new("value", self.value.as_str())]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) -> bool {
+        match (name, value) {
+            ("value", PropertyValue::Text(value)) => {
+                self.value = value;
+                true
+            }
+            _ => false,
+        }
     }
 }
 
@@ -102,9 +309,12 @@ pub struct SampleConnectionData {
     value: String,
 }
 
+impl HasProperties for SampleConnectionData {}
+
 impl ConnectionData for SampleConnectionData {
-    fn show(&mut self, ui: &mut Ui, _style: &PortStyle) {
+    fn show(&mut self, ui: &mut Ui, _style: &PortStyle) -> bool {
         ui.label(&self.value);
+        false
     }
 }
 
@@ -130,23 +340,119 @@ impl Validator for MyGraphValidator {
     }
 }
 
+/// Show the input values of the sample ports in the tooltips.
+pub struct MyPortValues;
+
+impl PortValueSource for MyPortValues {
+    fn port_value(&self, graph: &Graph, port_id: InputOutputId) -> Option<String> {
+        match port_id {
+            InputOutputId::Input(id) => {
+                let input = graph.node(id.node_id())?.inputs.get(id.port_id())?;
+                let data = input.data().downcast_ref::<SampleInput>()?;
+                Some(data.value.to_string())
+            }
+            InputOutputId::Output(_) => None,
+        }
+    }
+}
+
+/// A slow task emitting a constant, it reports its progress and stops early when cancelled.
+fn constant_task<T: 'static + Send + Sync>(value: T) -> Box<dyn NodeTask> {
+    Box::new(move |context: &mut TaskContext| {
+        for step in 0..10 {
+            if context.is_cancelled() {
+                return Ok(());
+            }
+            context.set_progress(step as f32 / 10.);
+            thread::sleep(Duration::from_millis(100));
+        }
+        context.set_output(0, value);
+        Ok(())
+    })
+}
+
+fn create_task(node: &Node) -> Option<Box<dyn NodeTask>> {
+    match node.kind.as_str() {
+        "u8" => Some(constant_task(8u8)),
+        "u16" => Some(constant_task(16u16)),
+        "u32" => Some(constant_task(32u32)),
+        "complex" => Some(Box::new(|context: &mut TaskContext| {
+            let in1 = context.input::<u8>(0).cloned().unwrap_or(0);
+            let in2 = context.input::<u16>(1).ok_or("in2 is missing")?;
+            let in3 = context.input::<u32>(2).cloned().unwrap_or(0);
+            let sum = in1 as u32 + *in2 as u32 + in3;
+            context.set_output(0, (sum % 256) as u8);
+            Ok(())
+        })),
+        _ => None,
+    }
+}
+
+pub struct ClearGraphAction;
+
+impl Action for ClearGraphAction {
+    fn execute(&self, graph: &mut Graph, _context: &ActionContext) {
+        graph.clear();
+    }
+}
+
 struct MyApp {
     tool: SideTool,
+    theme: GraphTheme,
+    /// interaction mode of the second view
+    interaction: InteractionMode,
     graph: Graph,
+    /// selection shared by the views
+    selection: Selection,
     context_menu: ContextMenu,
+    actions: Actions,
+    evaluator: GraphEvaluator,
+    templates: SharedTemplateLibrary,
 }
 
 impl Default for MyApp {
     fn default() -> Self {
         let mut style = PortStyles::default();
-        style.set::<u8>(PortStyle::new("u8").with_color(Color32::KHAKI));
-        style.set::<u16>(PortStyle::new("u16"));
-        style.set::<u32>(PortStyle::new("u32"));
+        style.set::<u8>(
+            PortStyle::new("u8")
+                .with_color(Color32::KHAKI)
+                .with_hollow_unconnected(true)
+                .with_preview(|value: &u8| ValuePreview::text(value)),
+        );
+        style.set::<u16>(
+            PortStyle::new("u16")
+                .with_shape(PortShape::Square)
+                .with_connection_count(true)
+                .with_display::<u16>()
+                .with_preview(|value: &u16| ValuePreview::Color(Color32::from_gray((*value * 8) as u8))),
+        );
+        style.set::<u32>(
+            PortStyle::new("u32")
+                .with_shape(PortShape::Diamond)
+                .with_display::<u32>()
+                .with_editor(|ui, value: &mut u32| ui.add(DragValue::new(value)))
+                .with_preview(|value: &u32| {
+                    ValuePreview::Sparkline((0..8).map(|i| ((value >> i) & 1) as f32).collect())
+                }),
+        );
+
+        let mut node_styles = NodeStyles::default();
+        node_styles.set("u8", NodeStyle::new().with_shape(NodeShape::Pill));
+        node_styles.set("u16", NodeStyle::new().with_header_color(Color32::from_rgb(40, 70, 40)));
+        node_styles.set("u32", NodeStyle::new().with_header_color(Color32::from_rgb(40, 70, 40)));
+        node_styles.set(
+            "complex",
+            NodeStyle::new()
+                .with_header_color(Color32::from_rgb(70, 40, 40))
+                .with_icon("⚙"),
+        );
 
         let mut graph = Graph::default();
         graph.set_validator(MyGraphValidator);
         graph.set_port_styles(style);
+        graph.set_node_styles(node_styles);
 
+        let templates = SharedTemplateLibrary::default();
         let context_menu = {
             let mut context_menu = ContextMenu::default();
             let mut builder = context_menu.builder();
@@ -160,29 +466,154 @@ impl Default for MyApp {
                 .add_group("logic")
                 .add_item("minimal", MyContextMenuData::AddMinimalNode)
                 .add_item("complex", MyContextMenuData::AddComplexNode);
+            builder.add_separator();
+            builder.add_item("select all", ActionMenuItem(ActionId::SelectAll));
+            builder.add_item("frame", ActionMenuItem(ActionId::Frame));
             builder.add_item("clear", MyContextMenuData::ClearGraph);
+            context_menu.add_provider(MyTargetMenu);
+            context_menu.add_provider(TemplateMenu::new(templates.clone()));
 
             context_menu
         };
 
+        let mut actions = Actions::default();
+        actions
+            .add(
+                ActionId::custom("clear"),
+                Some(KeyChord::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Delete)),
+                ClearGraphAction,
+            )
+            .unwrap();
+
+        // a theme file can be given as the first argument
+        let theme = match std::env::args().nth(1) {
+            Some(path) => GraphTheme::load(&path).unwrap_or_else(|err| {
+                log::error!("Failed to load theme from {}: {:?}", path, err);
+                GraphTheme::dark()
+            }),
+            None => GraphTheme::dark(),
+        };
+
         Self {
             tool: SideTool::Memory,
+            theme,
+            interaction: InteractionMode::ReadOnly,
             graph,
+            selection: Selection::default(),
             context_menu,
+            actions,
+            evaluator: GraphEvaluator::default(),
+            templates,
         }
     }
 }
 
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let validation = self.graph.validate();
+        self.evaluator.poll();
+        if self.evaluator.is_running() {
+            ctx.request_repaint();
+        }
+
         SidePanel::left("Settings").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Graph theme:");
+                if ui.button("dark").clicked() {
+                    self.theme = GraphTheme::dark();
+                }
+                if ui.button("light").clicked() {
+                    self.theme = GraphTheme::light();
+                }
+            });
+
+            if ui.button("export").clicked() {
+                let export = GraphExport::new(&self.graph).with_theme(&self.theme);
+                for (path, content) in [
+                    ("graph.dot", export.to_dot()),
+                    ("graph.mmd", export.to_mermaid()),
+                    ("graph.svg", export.to_svg()),
+                ] {
+                    if let Err(err) = std::fs::write(path, content) {
+                        log::error!("Failed to export {}: {:?}", path, err);
+                    }
+                }
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("evaluate").clicked() {
+                    self.evaluator.start(&self.graph, &create_task);
+                }
+                if ui.button("cancel").clicked() {
+                    self.evaluator.cancel();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("step").clicked() {
+                    self.evaluator.step();
+                }
+                if ui.button("resume").clicked() {
+                    self.evaluator.resume();
+                }
+                let mut recording = self.evaluator.trace().is_some();
+                if ui.checkbox(&mut recording, "record").changed() {
+                    self.evaluator.set_recording(recording);
+                }
+                if ui.button("save trace").clicked() {
+                    if let Some(trace) = self.evaluator.trace() {
+                        if let Err(err) = trace.save("trace.json") {
+                            log::error!("Failed to save trace: {:?}", err);
+                        }
+                    }
+                }
+            });
+            if let Some(node_id) = self.selection.to_vec().first() {
+                NodeInspector::new(&mut self.evaluator).show(ui, &self.graph, *node_id);
+                ui.separator();
+                PropertyPanel::new(&mut self.graph).show(ui, *node_id);
+            }
+
+            ComboBox::new("Interaction", "second view")
+                .selected_text(format!("{:?}", self.interaction))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.interaction, InteractionMode::Full, "Full");
+                    ui.selectable_value(&mut self.interaction, InteractionMode::ReadOnly, "Read only");
+                    ui.selectable_value(&mut self.interaction, InteractionMode::LayoutOnly, "Layout only");
+                    ui.selectable_value(&mut self.interaction, InteractionMode::WiringOnly, "Wiring only");
+                });
+
             ComboBox::new("Side panel", "")
-                .selected_text(format!("{:?}", &mut self.tool))
+                .selected_text(format!("{:?}", self.tool))
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.tool, SideTool::Memory, "Memory Tool");
                     ui.selectable_value(&mut self.tool, SideTool::Settings, "EGUI settings");
                 });
 
+            ui.separator();
+            ui.label("Problems:");
+            ProblemsList::new(&validation, Id::new("graph edit 1")).show(ui, &self.graph);
+            ui.separator();
+
+            ui.label("Templates:");
+            let mut removed = None;
+            for template in self.templates.read().unwrap().templates() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} ({} nodes, {} connections)",
+                        template.name,
+                        template.node_count(),
+                        template.connection_count()
+                    ));
+                    if ui.small_button("X").clicked() {
+                        removed = Some(template.name.clone());
+                    }
+                });
+            }
+            if let Some(name) = removed {
+                self.templates.write().unwrap().remove(&name);
+            }
+            ui.separator();
+
             match self.tool {
                 SideTool::Memory => ctx.memory_ui(ui),
                 SideTool::Settings => ctx.settings_ui(ui),
@@ -198,7 +629,13 @@ impl eframe::App for MyApp {
                     strip.cell(|ui| {
                         ui.painter()
                             .rect_filled(ui.available_rect_before_wrap(), 0.0, Color32::DARK_BLUE);
-                        GraphEdit::new(Id::new("graph edit 1"), &mut self.graph, &self.context_menu).show(ui);
+                        GraphEdit::new(Id::new("graph edit 1"), &mut self.graph, &self.context_menu)
+                            .with_actions(&self.actions)
+                            .with_port_values(&MyPortValues)
+                            .with_theme(&self.theme)
+                            .with_selection(&mut self.selection)
+                            .with_validation(&validation)
+                            .show(ui);
                     });
                     strip.cell(|ui| {
                         ui.painter()
@@ -207,7 +644,14 @@ impl eframe::App for MyApp {
                     strip.cell(|ui| {
                         ui.painter()
                             .rect_filled(ui.available_rect_before_wrap(), 0.0, Color32::DARK_RED);
-                        GraphEdit::new(Id::new("graph edit 2"), &mut self.graph, &self.context_menu).show(ui);
+                        GraphEdit::new(Id::new("graph edit 2"), &mut self.graph, &self.context_menu)
+                            .with_actions(&self.actions)
+                            .with_theme(&self.theme)
+                            .with_selection(&mut self.selection)
+                            .with_interaction(self.interaction)
+                            .with_validation(&validation)
+                            .with_evaluation(&self.evaluator)
+                            .show(ui);
                     });
                 });
         });
@@ -0,0 +1,247 @@
+use crate::node_graph::{Graph, NodeId};
+use egui::{Context, Key, KeyboardShortcut, Modifiers, Pos2};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error as ThisError;
+
+/// Identifies an operation of the editor that can be bound to a key chord.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ActionId {
+    /// Remove the selected nodes.
+    Delete,
+    /// Duplicate the selected nodes and the connections between them.
+    Duplicate,
+    /// Fit the view to the selected nodes, or to the whole graph if nothing is selected.
+    Frame,
+    /// Select all the nodes.
+    SelectAll,
+    /// The graph has no history, it has effect only if the host registers a handler.
+    Undo,
+    /// The graph has no grouping, it has effect only if the host registers a handler.
+    Group,
+    /// An action defined by the host application.
+    Custom(String),
+}
+
+impl ActionId {
+    pub fn custom<S: ToString>(name: S) -> Self {
+        ActionId::Custom(name.to_string())
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, ActionId::Custom(_))
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ActionId::Delete => "delete",
+            ActionId::Duplicate => "duplicate",
+            ActionId::Frame => "frame",
+            ActionId::SelectAll => "select all",
+            ActionId::Undo => "undo",
+            ActionId::Group => "group",
+            ActionId::Custom(name) => name,
+        }
+    }
+}
+
+/// A key with the required modifiers, e.g. `Ctrl+D`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyChord {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl KeyChord {
+    pub const fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }
+    }
+
+    pub const fn key(key: Key) -> Self {
+        Self::new(Modifiers::NONE, key)
+    }
+
+    pub const fn command(key: Key) -> Self {
+        Self::new(Modifiers::COMMAND, key)
+    }
+
+    pub fn to_shortcut(&self) -> KeyboardShortcut {
+        KeyboardShortcut::new(self.modifiers, self.key)
+    }
+
+    /// Format the chord in the style of the current platform.
+    pub fn format(&self, ctx: &Context) -> String {
+        ctx.format_shortcut(&self.to_shortcut())
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum KeyMapError {
+    #[error("{chord:?} is already bound to {action:?}")]
+    Conflict { chord: KeyChord, action: ActionId },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub action: ActionId,
+    pub chord: KeyChord,
+}
+
+/// Key chord of the actions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyMap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut keymap = KeyMap::empty();
+        let defaults = [
+            (ActionId::Delete, KeyChord::key(Key::Delete)),
+            (ActionId::Duplicate, KeyChord::command(Key::D)),
+            (ActionId::Frame, KeyChord::key(Key::F)),
+            (ActionId::SelectAll, KeyChord::command(Key::A)),
+            (ActionId::Undo, KeyChord::command(Key::Z)),
+            (ActionId::Group, KeyChord::command(Key::G)),
+        ];
+        for (action, chord) in defaults {
+            keymap.bind(action, chord).unwrap();
+        }
+        keymap
+    }
+}
+
+impl KeyMap {
+    /// Create a keymap without any binding.
+    pub fn empty() -> Self {
+        Self { bindings: Vec::new() }
+    }
+
+    pub fn bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    /// Bind the action to the chord replacing the previous chord of the action.
+    /// It is an error to bind a chord that is already used by another action.
+    pub fn bind(&mut self, action: ActionId, chord: KeyChord) -> Result<(), KeyMapError> {
+        if let Some(other) = self.action(&chord) {
+            if other != &action {
+                return Err(KeyMapError::Conflict {
+                    chord,
+                    action: other.clone(),
+                });
+            }
+        }
+
+        self.unbind(&action);
+        self.bindings.push(KeyBinding { action, chord });
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, action: &ActionId) {
+        self.bindings.retain(|binding| &binding.action != action);
+    }
+
+    pub fn chord(&self, action: &ActionId) -> Option<KeyChord> {
+        self.bindings
+            .iter()
+            .find(|binding| &binding.action == action)
+            .map(|binding| binding.chord)
+    }
+
+    pub fn action(&self, chord: &KeyChord) -> Option<&ActionId> {
+        self.bindings
+            .iter()
+            .find(|binding| &binding.chord == chord)
+            .map(|binding| &binding.action)
+    }
+
+    /// Return the chords that are bound to multiple actions. As `bind` prevents conflicts, it can happen only
+    /// for a deserialized keymap.
+    pub fn conflicts(&self) -> Vec<(KeyChord, Vec<ActionId>)> {
+        let mut conflicts: Vec<(KeyChord, Vec<ActionId>)> = Vec::new();
+        for (i, binding) in self.bindings.iter().enumerate() {
+            if conflicts.iter().any(|(chord, _)| chord == &binding.chord) {
+                continue;
+            }
+            let actions: Vec<ActionId> = self.bindings[i..]
+                .iter()
+                .filter(|other| other.chord == binding.chord)
+                .map(|other| other.action.clone())
+                .collect();
+            if actions.len() > 1 {
+                conflicts.push((binding.chord, actions));
+            }
+        }
+        conflicts
+    }
+
+    /// Format the chord of an action for display, ex. in a menu.
+    pub fn format(&self, action: &ActionId, ctx: &Context) -> Option<String> {
+        self.chord(action).map(|chord| chord.format(ctx))
+    }
+}
+
+/// Information about the editor for the execution of an action.
+#[derive(Clone, Debug)]
+pub struct ActionContext {
+    /// The selected nodes
+    pub selection: Vec<NodeId>,
+    /// Location of the pointer in graph (area) coordinates.
+    pub location: Option<Pos2>,
+}
+
+pub trait Action: 'static + Send + Sync {
+    fn execute(&self, graph: &mut Graph, context: &ActionContext);
+}
+
+/// The registry of the actions with their key bindings.
+///
+/// The built-in actions are executed by the editor unless a handler is registered for them.
+#[derive(Default)]
+pub struct Actions {
+    keymap: KeyMap,
+    handlers: HashMap<ActionId, Box<dyn Action>>,
+}
+
+impl Actions {
+    pub fn keymap(&self) -> &KeyMap {
+        &self.keymap
+    }
+
+    pub fn keymap_mut(&mut self) -> &mut KeyMap {
+        &mut self.keymap
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    /// Register the handler of an action and bind it to the given chord.
+    pub fn add<A: Action>(&mut self, id: ActionId, chord: Option<KeyChord>, action: A) -> Result<(), KeyMapError> {
+        if let Some(chord) = chord {
+            self.keymap.bind(id.clone(), chord)?;
+        }
+        self.handlers.insert(id, Box::new(action));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &ActionId) {
+        self.keymap.unbind(id);
+        self.handlers.remove(id);
+    }
+
+    pub fn handler(&self, id: &ActionId) -> Option<&dyn Action> {
+        self.handlers.get(id).map(|handler| &**handler)
+    }
+
+    /// Find the first action whose chord was pressed and consume the key event.
+    pub(in crate::node_graph) fn consume_pressed(&self, ctx: &Context) -> Option<ActionId> {
+        let mut input = ctx.input_mut();
+        self.keymap
+            .bindings()
+            .iter()
+            .find(|binding| input.consume_shortcut(&binding.chord.to_shortcut()))
+            .map(|binding| binding.action.clone())
+    }
+}
@@ -0,0 +1,217 @@
+use crate::node_graph::{
+    ConnectionId, Graph, Input, InputId, Node, NodeData, NodeId, Output, OutputId, TypedInputId, TypedOutputId,
+};
+use egui::Pos2;
+use std::any::Any;
+
+/// Fluent construction of graphs in code.
+///
+/// ```
+/// # use shine_ui::node_graph::Graph;
+/// let mut graph = Graph::default();
+/// let mut builder = graph.builder();
+/// let a = builder.node("constant").output::<f32>("x").build();
+/// let b = builder.node("add").input::<f32>("a").input::<f32>("b").output::<f32>("sum").build();
+/// builder.connect(a.output("x"), b.input("a")).unwrap();
+/// ```
+pub struct GraphBuilder<'g> {
+    graph: &'g mut Graph,
+}
+
+impl<'g> GraphBuilder<'g> {
+    pub fn new(graph: &'g mut Graph) -> Self {
+        Self { graph }
+    }
+
+    /// Start a new node with the given caption, the node is added to the graph by [`NodeBuilder::build`].
+    pub fn node<S: ToString>(&mut self, caption: S) -> NodeBuilder<'_> {
+        NodeBuilder {
+            graph: self.graph,
+            node: Node::new(caption, Pos2::ZERO, vec![], vec![]),
+        }
+    }
+
+    /// Connect two ports using [`Graph::try_create_connection`].
+    pub fn connect(&mut self, output_id: OutputId, input_id: InputId) -> Option<ConnectionId> {
+        let connection = self.graph.try_create_connection(input_id, output_id)?;
+        Some(self.graph.add_connection(connection))
+    }
+
+    /// Connect two ports of the same type, see [`Graph::connect`].
+    pub fn connect_typed<T: 'static>(
+        &mut self,
+        output_id: TypedOutputId<T>,
+        input_id: TypedInputId<T>,
+    ) -> Option<ConnectionId> {
+        self.graph.connect(output_id, input_id)
+    }
+
+    pub fn graph(&mut self) -> &mut Graph {
+        self.graph
+    }
+}
+
+pub struct NodeBuilder<'g> {
+    graph: &'g mut Graph,
+    node: Node,
+}
+
+impl<'g> NodeBuilder<'g> {
+    pub fn kind<S: ToString>(self, kind: S) -> Self {
+        Self {
+            node: self.node.with_kind(kind),
+            ..self
+        }
+    }
+
+    pub fn at<P: Into<Pos2>>(mut self, location: P) -> Self {
+        self.node.location = location.into();
+        self
+    }
+
+    /// Add an input port of type `T`.
+    pub fn input<T: Any>(self, name: &str) -> Self {
+        self.with_input(Input::new::<T>(name))
+    }
+
+    /// Add an input port configured by the caller.
+    pub fn with_input(mut self, input: Input) -> Self {
+        self.node.inputs.push(input);
+        self
+    }
+
+    /// Add an output port of type `T`.
+    pub fn output<T: Any>(self, name: &str) -> Self {
+        self.with_output(Output::new::<T>(name))
+    }
+
+    /// Add an output port configured by the caller.
+    pub fn with_output(mut self, output: Output) -> Self {
+        self.node.outputs.push(output);
+        self
+    }
+
+    pub fn with_data<N: NodeData>(self, data: N) -> Self {
+        Self {
+            node: self.node.with_data(data),
+            ..self
+        }
+    }
+
+    /// Add the node to the graph and return a handle to refer to its ports by name.
+    pub fn build(self) -> NodeHandle {
+        let node_id = self.graph.add_node(self.node);
+        let node = self.graph.node(node_id).unwrap();
+        NodeHandle {
+            node_id,
+            inputs: (0..node.inputs.len())
+                .filter_map(|port_id| Some((node.inputs[port_id].name.clone(), node.input_id(port_id)?)))
+                .collect(),
+            outputs: (0..node.outputs.len())
+                .filter_map(|port_id| Some((node.outputs[port_id].name.clone(), node.output_id(port_id)?)))
+                .collect(),
+        }
+    }
+}
+
+/// The ports of a node created by a [`NodeBuilder`].
+#[derive(Clone, Debug)]
+pub struct NodeHandle {
+    node_id: NodeId,
+    inputs: Vec<(String, InputId)>,
+    outputs: Vec<(String, OutputId)>,
+}
+
+impl NodeHandle {
+    pub fn id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn find_input(&self, name: &str) -> Option<InputId> {
+        self.inputs.iter().find(|(n, _)| n == name).map(|(_, id)| *id)
+    }
+
+    pub fn find_output(&self, name: &str) -> Option<OutputId> {
+        self.outputs.iter().find(|(n, _)| n == name).map(|(_, id)| *id)
+    }
+
+    /// Return the id of an input port.
+    ///
+    /// # Panics
+    /// If the node has no input with the given name.
+    pub fn input(&self, name: &str) -> InputId {
+        self.find_input(name)
+            .unwrap_or_else(|| panic!("Node has no input named {}", name))
+    }
+
+    /// Return the id of an output port.
+    ///
+    /// # Panics
+    /// If the node has no output with the given name.
+    pub fn output(&self, name: &str) -> OutputId {
+        self.find_output(name)
+            .unwrap_or_else(|| panic!("Node has no output named {}", name))
+    }
+
+    /// Return the typed id of an input port.
+    ///
+    /// # Panics
+    /// If the node has no input with the given name and type.
+    pub fn typed_input<T: 'static>(&self, name: &str) -> TypedInputId<T> {
+        TypedInputId::try_from_id(self.input(name)).unwrap_or_else(|| panic!("Input {} has a different type", name))
+    }
+
+    /// Return the typed id of an output port.
+    ///
+    /// # Panics
+    /// If the node has no output with the given name and type.
+    pub fn typed_output<T: 'static>(&self, name: &str) -> TypedOutputId<T> {
+        TypedOutputId::try_from_id(self.output(name)).unwrap_or_else(|| panic!("Output {} has a different type", name))
+    }
+}
+
+/// Declare the nodes and the connections of a graph. Each node is bound to a local variable holding its
+/// [`NodeHandle`]. A rejected connection panics, the macro is intended for tests and presets.
+///
+/// ```
+/// # use shine_ui::{build_graph, node_graph::Graph};
+/// let mut graph = Graph::default();
+/// build_graph! {
+///     graph;
+///     source = "source" at (100., 100.), inputs [], outputs [value: u8];
+///     sink = "sink" at (450., 300.), inputs [value: u8], outputs [];
+///     source.value -> sink.value;
+/// }
+/// assert_eq!(graph.connections().count(), 1);
+/// ```
+#[macro_export]
+macro_rules! build_graph {
+    ($graph: expr;) => {};
+    ($graph: expr;
+        $node: ident = $caption: literal at ($x: expr, $y: expr),
+        inputs [$( $input: ident : $input_type: ty ),*],
+        outputs [$( $output: ident : $output_type: ty ),*];
+        $( $rest: tt )*
+    ) => {
+        let $node = $crate::node_graph::GraphBuilder::new(&mut $graph)
+            .node($caption)
+            .at(($x, $y))
+            $( .input::<$input_type>(stringify!($input)) )*
+            $( .output::<$output_type>(stringify!($output)) )*
+            .build();
+        $crate::build_graph!($graph; $( $rest )*);
+    };
+    ($graph: expr;
+        $from: ident . $output: ident -> $to: ident . $input: ident;
+        $( $rest: tt )*
+    ) => {
+        $crate::node_graph::GraphBuilder::new(&mut $graph)
+            .connect($from.output(stringify!($output)), $to.input(stringify!($input)))
+            .expect(concat!(
+                "Connection ",
+                stringify!($from.$output -> $to.$input),
+                " was rejected"
+            ));
+        $crate::build_graph!($graph; $( $rest )*);
+    };
+}
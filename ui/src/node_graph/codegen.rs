@@ -0,0 +1,281 @@
+use crate::node_graph::{utils::topological_order, Graph, Input, InputId, Node, NodeId, OutputId, ValidationReport};
+use std::{
+    any::TypeId,
+    collections::{HashMap, HashSet},
+};
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum CodegenError {
+    #[error("Graph has validation errors")]
+    Invalid(ValidationReport),
+    #[error("Node {node_id:?} of kind {kind} is not supported by the backend")]
+    UnsupportedNode { node_id: NodeId, kind: String },
+}
+
+/// The variables of a node passed to [`CodegenBackend::emit_node`].
+pub struct SnippetContext<'a> {
+    node: &'a Node,
+    inputs: Vec<Vec<String>>,
+    outputs: Vec<String>,
+}
+
+impl<'a> SnippetContext<'a> {
+    pub fn node(&self) -> &'a Node {
+        self.node
+    }
+
+    /// Return the expression of an input port. It is the variable of the first connection, or the
+    /// [literal](CodegenBackend::emit_literal) of the default of the port if it is not connected.
+    pub fn input(&self, port_id: usize) -> Option<&str> {
+        self.inputs.get(port_id)?.first().map(|input| input.as_str())
+    }
+
+    /// Return the variables of all the connections of an input port.
+    pub fn inputs(&self, port_id: usize) -> &[String] {
+        self.inputs.get(port_id).map(|inputs| inputs.as_slice()).unwrap_or(&[])
+    }
+
+    /// Return the variable that shall hold the value of an output port.
+    pub fn output(&self, port_id: usize) -> Option<&str> {
+        self.outputs.get(port_id).map(|output| output.as_str())
+    }
+}
+
+/// The target language of a [`CodeGenerator`].
+pub trait CodegenBackend {
+    /// Return the code of a node assigning its outputs, `None` if the kind of the node is not supported.
+    fn emit_node(&self, context: &SnippetContext<'_>) -> Option<String>;
+
+    /// Return the literal of the default value of an unconnected input, `None` if it has no literal.
+    fn emit_literal(&self, _input: &Input) -> Option<String> {
+        None
+    }
+
+    /// Return the name of the variable of an output, `index` is unique in the generated code.
+    fn variable_name(&self, index: usize, _node: &Node, _port_id: usize) -> String {
+        format!("v{}", index)
+    }
+
+    /// Return if the node is a result of the code, ex. a shader output. The nodes not contributing to any result
+    /// are eliminated. By default the nodes without outputs are the results.
+    fn is_root(&self, node: &Node) -> bool {
+        node.outputs.is_empty()
+    }
+
+    /// Return if the node computes its outputs only from its inputs and its data, thus identical nodes can share
+    /// their code. The nodes with side effects shall return `false`.
+    fn is_pure(&self, node: &Node) -> bool {
+        !self.is_root(node)
+    }
+
+    /// The code before the first node.
+    fn prologue(&self, _graph: &Graph) -> String {
+        String::new()
+    }
+
+    /// The code after the last node.
+    fn epilogue(&self, _graph: &Graph) -> String {
+        String::new()
+    }
+}
+
+/// The result of a [`CodeGenerator`].
+#[derive(Clone, Debug, Default)]
+pub struct GeneratedCode {
+    pub source: String,
+    /// The nodes in the order their code was emitted.
+    pub nodes: Vec<NodeId>,
+    /// The variables of the outputs of the used nodes. The outputs of a shared node refer to the variables of the
+    /// node it is shared with.
+    pub variables: HashMap<OutputId, String>,
+}
+
+/// The identity of the computation of a node used to share the code of the identical nodes.
+#[derive(PartialEq, Eq, Hash)]
+struct ExpressionKey {
+    kind: String,
+    content_hash: Option<u64>,
+    inputs: Vec<Vec<String>>,
+    outputs: Vec<TypeId>,
+}
+
+/// Generate source text from a graph. The nodes are emitted in topological order by a [`CodegenBackend`].
+pub struct CodeGenerator<'a> {
+    backend: &'a dyn CodegenBackend,
+    eliminate_dead_nodes: bool,
+    share_expressions: bool,
+}
+
+impl<'a> CodeGenerator<'a> {
+    pub fn new(backend: &'a dyn CodegenBackend) -> Self {
+        Self {
+            backend,
+            eliminate_dead_nodes: true,
+            share_expressions: true,
+        }
+    }
+
+    /// Skip the nodes that are not contributing to a [root](CodegenBackend::is_root) node. Enabled by default.
+    pub fn with_dead_node_elimination(self, enable: bool) -> Self {
+        Self {
+            eliminate_dead_nodes: enable,
+            ..self
+        }
+    }
+
+    /// Emit the code of the identical [pure](CodegenBackend::is_pure) nodes only once. Two nodes are identical if
+    /// they have the same kind, the same inputs and the same data. The data is compared by its
+    /// [content hash](crate::node_graph::NodeData::content_hash), the nodes with data but without a content hash
+    /// are never shared. Enabled by default.
+    pub fn with_shared_expressions(self, enable: bool) -> Self {
+        Self {
+            share_expressions: enable,
+            ..self
+        }
+    }
+
+    /// Generate the code of a graph without validation errors.
+    pub fn generate(&self, graph: &Graph) -> Result<GeneratedCode, CodegenError> {
+        let report = graph.validate();
+        if report.has_errors() {
+            return Err(CodegenError::Invalid(report));
+        }
+
+        let mut connections = HashMap::<InputId, Vec<OutputId>>::new();
+        for connection in graph.connections() {
+            connections
+                .entry(connection.input_id())
+                .or_default()
+                .push(connection.output_id());
+        }
+
+        let live = if self.eliminate_dead_nodes {
+            Some(self.live_nodes(graph))
+        } else {
+            None
+        };
+
+        let mut code = GeneratedCode {
+            source: self.backend.prologue(graph),
+            ..Default::default()
+        };
+        let mut expressions = HashMap::<ExpressionKey, NodeId>::new();
+        let mut variable_count = 0;
+        for node_id in topological_order(graph) {
+            if let Some(live) = &live {
+                if !live.contains(&node_id) {
+                    continue;
+                }
+            }
+            let node = graph.node(node_id).unwrap();
+
+            let inputs: Vec<Vec<String>> = node
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(port_id, input)| {
+                    let sources: Vec<String> = node
+                        .input_id(port_id)
+                        .and_then(|input_id| connections.get(&input_id))
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|output_id| code.variables.get(output_id).cloned())
+                        .collect();
+                    if sources.is_empty() {
+                        self.backend.emit_literal(input).into_iter().collect()
+                    } else {
+                        sources
+                    }
+                })
+                .collect();
+
+            let key = if self.share_expressions && self.is_shareable(node) {
+                Some(ExpressionKey {
+                    kind: node.kind.clone(),
+                    content_hash: node.data().content_hash(),
+                    inputs: inputs.clone(),
+                    outputs: node.outputs.iter().map(|output| output.port_type_id()).collect(),
+                })
+            } else {
+                None
+            };
+            if let Some(shared_id) = key.as_ref().and_then(|key| expressions.get(key)) {
+                let shared = graph.node(*shared_id).unwrap();
+                for port_id in 0..node.outputs.len() {
+                    let output_id = node.output_id(port_id);
+                    let variable = shared
+                        .output_id(port_id)
+                        .and_then(|shared_id| code.variables.get(&shared_id))
+                        .cloned();
+                    if let (Some(output_id), Some(variable)) = (output_id, variable) {
+                        code.variables.insert(output_id, variable);
+                    }
+                }
+                continue;
+            }
+
+            let outputs: Vec<String> = (0..node.outputs.len())
+                .map(|port_id| {
+                    variable_count += 1;
+                    self.backend.variable_name(variable_count - 1, node, port_id)
+                })
+                .collect();
+            let context = SnippetContext { node, inputs, outputs };
+            let snippet = match self.backend.emit_node(&context) {
+                Some(snippet) => snippet,
+                None => {
+                    return Err(CodegenError::UnsupportedNode {
+                        node_id,
+                        kind: node.kind.clone(),
+                    })
+                }
+            };
+            code.source.push_str(&snippet);
+            if !snippet.is_empty() && !snippet.ends_with('\n') {
+                code.source.push('\n');
+            }
+            for (port_id, variable) in context.outputs.into_iter().enumerate() {
+                if let Some(output_id) = node.output_id(port_id) {
+                    code.variables.insert(output_id, variable);
+                }
+            }
+            code.nodes.push(node_id);
+            if let Some(key) = key {
+                expressions.insert(key, node_id);
+            }
+        }
+
+        code.source.push_str(&self.backend.epilogue(graph));
+        Ok(code)
+    }
+
+    /// Return the roots and the nodes they depend on.
+    fn live_nodes(&self, graph: &Graph) -> HashSet<NodeId> {
+        let mut sources = HashMap::<NodeId, Vec<NodeId>>::new();
+        for connection in graph.connections() {
+            sources
+                .entry(connection.input_node_id())
+                .or_default()
+                .push(connection.output_node_id());
+        }
+
+        let mut stack: Vec<NodeId> = graph
+            .nodes()
+            .filter(|node| self.backend.is_root(node))
+            .map(|node| node.id())
+            .collect();
+        let mut live = HashSet::new();
+        while let Some(node_id) = stack.pop() {
+            if live.insert(node_id) {
+                stack.extend(sources.get(&node_id).into_iter().flatten());
+            }
+        }
+        live
+    }
+
+    fn is_shareable(&self, node: &Node) -> bool {
+        let has_data = node.data().downcast_ref::<()>().is_none();
+        self.backend.is_pure(node) && (!has_data || node.data().content_hash().is_some())
+    }
+}
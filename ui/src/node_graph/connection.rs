@@ -1,5 +1,6 @@
 use crate::node_graph::{
-    utils::draw_connection, InputId, NodeId, OutputId, PortStyle, PortStyles, PortViewState, ZoomPanState,
+    utils::{connection_distance, draw_connection},
+    HasProperties, InputId, NodeId, OutputId, PortStyle, PortStyles, PortViewState, ZoomPanState,
 };
 use egui::{Area, Order, Rect, Stroke, Ui};
 use emath::Align2;
@@ -11,13 +12,16 @@ use shine_core::{
 
 new_key_type! { pub struct ConnectionId; }
 
-pub trait ConnectionData: 'static + Downcast + Send + Sync {
-    fn show(&mut self, ui: &mut Ui, style: &PortStyle);
+pub trait ConnectionData: 'static + Downcast + Send + Sync + HasProperties {
+    /// Show the editor of the data on the connection and return if it was changed.
+    fn show(&mut self, ui: &mut Ui, style: &PortStyle) -> bool;
 }
 impl_downcast!(ConnectionData);
 
 impl ConnectionData for () {
-    fn show(&mut self, _ui: &mut Ui, _style: &PortStyle) {}
+    fn show(&mut self, _ui: &mut Ui, _style: &PortStyle) -> bool {
+        false
+    }
 }
 
 type BoxedConnectionData = SmallBox<dyn ConnectionData, space::S32>;
@@ -72,6 +76,10 @@ impl Connection {
         &*self.data
     }
 
+    pub fn data_mut(&mut self) -> &mut dyn ConnectionData {
+        &mut *self.data
+    }
+
     pub fn data_as<T: ConnectionData>(&self) -> &T {
         let data = &*self.data;
         data.downcast_ref::<T>().unwrap()
@@ -86,52 +94,67 @@ impl Connection {
         &mut self,
         ui: &mut Ui,
         zoom_pan: &ZoomPanState,
-        port_visual: &PortViewState,
+        port_visual: &mut PortViewState,
         port_styles: &PortStyles,
-    ) {
+    ) -> bool {
+        let mut changed = false;
         let start = port_visual.get_screen_pos(self.input_id.into());
         let end = port_visual.get_screen_pos(self.output_id.into());
 
         if let (Some(start), Some(end)) = (start, end) {
             let type_id = self.input_id.port_type_id();
+            // the connections of the unstyled ports are not drawn, they are reported by the validation
             if let Some(style) = port_styles.find(type_id) {
+                let width = style.connection_width * zoom_pan.zoom;
                 draw_connection(
                     ui.painter(),
                     start,
                     end,
                     Stroke {
-                        color: style.color,
-                        width: style.connection_width * zoom_pan.zoom,
+                        color: style.get_wire_color(&zoom_pan.theme),
+                        width,
                     },
                 );
 
-                /*if self.data.is_visible() */
-                {
+                if let Some(pointer_pos) = ui.ctx().pointer_latest_pos() {
+                    let is_free = !port_visual.has_hovered()
+                        && port_visual.get_hovered_node().is_none()
+                        && port_visual.get_hovered_connection().is_none();
+                    if is_free
+                        && zoom_pan.screen_rect.contains(pointer_pos)
+                        && connection_distance(start, end, pointer_pos) < width + 3.
+                    {
+                        port_visual.set_hovered_connection(self.id);
+                    }
+                }
+
+                // the data is not shown when zoomed out
+                if !zoom_pan.is_lod() {
                     let rect = Rect::from_points(&[start, end]);
                     /*ui.painter().rect(
                         rect,
                         0.,
                         egui::Color32::TRANSPARENT,
-                        egui::Stroke::new(1., egui::Color32::YELLOW),
+                        egui::Stroke::new(1., zoom_pan.theme.debug_color),
                     );*/
                     let screen_center = ui.ctx().available_rect().center();
                     let rect_center = rect.center();
                     let offset = rect_center - screen_center;
                     let id = zoom_pan.child_id(self.id);
-                    Area::new(id)
+                    changed = Area::new(id)
                         .order(Order::Middle)
                         .anchor(Align2::CENTER_CENTER, offset)
-                        .enabled(port_visual.is_nodes_enabled())
+                        .enabled(port_visual.is_nodes_enabled() && port_visual.interaction().allows_content_edit())
                         .movable(false)
                         .show(ui.ctx(), |ui| {
                             ui.set_max_size(rect.size());
                             ui.set_clip_rect(zoom_pan.screen_rect);
-                            self.data.show(ui, style);
-                        });
+                            self.data.show(ui, style)
+                        })
+                        .inner;
                 }
-            } else {
-                log::warn!("Skipping connection {:?}, style for {:?} not found", self.id(), type_id);
             }
         }
+        changed
     }
 }
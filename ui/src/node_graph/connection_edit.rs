@@ -73,9 +73,9 @@ impl ConnectionEditState {
                     .find(type_id)
                     .expect("Connection shall be drown only with known types");
                 let color = if self.connection.borrow().is_some() {
-                    style.hover_color
+                    style.get_hover_color(&zoom_pan.theme)
                 } else {
-                    style.error_color
+                    style.get_error_color(&zoom_pan.theme)
                 };
                 draw_connection(
                     ui.painter(),
@@ -100,6 +100,12 @@ impl ConnectionEditState {
         let pointer_pos = ui.ctx().pointer_latest_pos().unwrap_or(Pos2::ZERO);
         let pointer_down = ui.input().pointer.any_down();
 
+        if !port_visual.is_ports_enabled() || !port_visual.interaction().allows_wiring() {
+            // wiring is not allowed, it was disabled during the edit
+            self.cancel();
+            return ConnectionResult::Completed(None);
+        }
+
         if self.start.is_none() {
             // start a new connection
             let port = port_visual.get_hovered().unwrap();
@@ -125,18 +131,10 @@ impl ConnectionEditState {
                 if update_validity {
                     let connection = match (self.start.unwrap(), self.end.unwrap()) {
                         (InputOutputId::Input(input_id), InputOutputId::Output(output_id)) => {
-                            if graph.find_connections(input_id, output_id).is_none() {
-                                graph.validator().try_create_connection(graph, input_id, output_id)
-                            } else {
-                                None
-                            }
+                            graph.try_create_connection(input_id, output_id)
                         }
                         (InputOutputId::Output(output_id), InputOutputId::Input(input_id)) => {
-                            if graph.find_connections(input_id, output_id).is_none() {
-                                graph.validator().try_create_connection(graph, input_id, output_id)
-                            } else {
-                                None
-                            }
+                            graph.try_create_connection(input_id, output_id)
                         }
                         _ => None,
                     };
@@ -1,18 +1,111 @@
-use crate::node_graph::{Graph, ZoomPanState};
-use egui::{pos2, Id, Pos2, Ui};
+use crate::node_graph::{ActionId, ConnectionId, Graph, InputOutputId, InteractionMode, KeyMap, NodeId, ZoomPanState};
+use egui::{pos2, Button, Context, Id, Pos2, Ui};
 use shine_core::{
     downcast_rs::{impl_downcast, Downcast},
     slotmap::{new_key_type, SlotMap},
     smallbox::{smallbox, space, SmallBox},
 };
+use std::sync::Arc;
 
 new_key_type! { pub struct ContextMenuId; }
 
+/// The element the context menu was opened on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextMenuTarget {
+    Canvas,
+    Node(NodeId),
+    Port(InputOutputId),
+    Connection(ConnectionId),
+}
+
+impl ContextMenuTarget {
+    pub fn is_canvas(&self) -> bool {
+        matches!(self, ContextMenuTarget::Canvas)
+    }
+
+    /// The node the target belongs to, if any.
+    pub fn node_id(&self) -> Option<NodeId> {
+        match self {
+            ContextMenuTarget::Node(node_id) => Some(*node_id),
+            ContextMenuTarget::Port(port_id) => Some(port_id.node_id()),
+            _ => None,
+        }
+    }
+}
+
+/// Information about the location where the context menu was opened.
+#[derive(Clone, Debug)]
+pub struct ContextMenuContext {
+    pub target: ContextMenuTarget,
+    /// Location of the right-click in graph (area) coordinates.
+    pub location: Pos2,
+    /// The selected nodes at the time the menu was opened.
+    pub selection: Vec<NodeId>,
+    /// The allowed modifications, items modifying the graph should check it in `is_enabled`.
+    pub interaction: InteractionMode,
+}
+
+impl Default for ContextMenuContext {
+    fn default() -> Self {
+        Self {
+            target: ContextMenuTarget::Canvas,
+            location: pos2(0., 0.),
+            selection: Vec::new(),
+            interaction: InteractionMode::Full,
+        }
+    }
+}
+
 pub trait ContextMenuData: 'static + Downcast + Send + Sync {
-    fn on_select(&self, graph: &mut Graph, location: Pos2);
+    fn on_select(&self, graph: &mut Graph, context: &ContextMenuContext);
+
+    /// Return if the item is shown for the given context.
+    fn is_visible(&self, _graph: &Graph, _context: &ContextMenuContext) -> bool {
+        true
+    }
+
+    /// Return if the item can be selected in the given context. Disabled items are shown grayed out.
+    fn is_enabled(&self, _graph: &Graph, _context: &ContextMenuContext) -> bool {
+        true
+    }
+
+    /// Return the checked state for checkbox items, `None` for plain items.
+    fn is_checked(&self, _graph: &Graph, _context: &ContextMenuContext) -> Option<bool> {
+        None
+    }
+
+    /// Shortcut label shown next to the item. If not given, the key binding of the action is shown.
+    fn shortcut(&self) -> Option<String> {
+        None
+    }
+
+    /// The action executed by the editor after `on_select`.
+    fn action(&self) -> Option<ActionId> {
+        None
+    }
 }
 impl_downcast!(ContextMenuData);
 
+/// Menu item to trigger an action of the editor.
+pub struct ActionMenuItem(pub ActionId);
+
+impl ContextMenuData for ActionMenuItem {
+    fn on_select(&self, _graph: &mut Graph, _context: &ContextMenuContext) {}
+
+    fn is_enabled(&self, _graph: &Graph, context: &ContextMenuContext) -> bool {
+        context.interaction.allows_action(&self.0)
+    }
+
+    fn action(&self) -> Option<ActionId> {
+        Some(self.0.clone())
+    }
+}
+
+/// Build the menu items dynamically each time the menu is opened.
+pub trait ContextMenuProvider: 'static + Send + Sync {
+    fn build(&self, graph: &Graph, context: &ContextMenuContext, builder: &mut ConextMenuBuilder<'_>);
+}
+
 type BoxedContextMenuData = SmallBox<dyn ContextMenuData, space::S32>;
 
 pub struct ContextMenuItem {
@@ -47,16 +140,26 @@ impl ContextMenuItem {
         let data = &mut *self.data;
         data.downcast_mut::<T>().unwrap()
     }
+
+    /// The shortcut label shown next to the item, the [shortcut](ContextMenuData::shortcut) of the data or the key
+    /// binding of its action.
+    pub fn shortcut_text(&self, keymap: &KeyMap, ctx: &Context) -> Option<String> {
+        self.data
+            .shortcut()
+            .or_else(|| self.data.action().and_then(|action| keymap.format(&action, ctx)))
+    }
 }
 
 enum ContextMenuKind {
     SubMenu { name: String, items: Vec<ContextMenuKind> },
     LeafItem(ContextMenuId),
+    Separator,
 }
 
 pub struct ContextMenu {
     items: SlotMap<ContextMenuId, ContextMenuItem>,
     root: ContextMenuKind,
+    providers: Vec<Box<dyn ContextMenuProvider>>,
 }
 
 impl Default for ContextMenu {
@@ -67,6 +170,7 @@ impl Default for ContextMenu {
                 name: "root".into(),
                 items: Vec::new(),
             },
+            providers: Vec::new(),
         }
     }
 }
@@ -79,12 +183,44 @@ impl ContextMenu {
         }
     }
 
+    /// Add a provider that extends the menu each time it is opened.
+    pub fn add_provider<P: ContextMenuProvider>(&mut self, provider: P) {
+        self.providers.push(Box::new(provider));
+    }
+
     pub fn clear(&mut self) {
         self.items.clear();
+        self.providers.clear();
         if let ContextMenuKind::SubMenu { items, .. } = &mut self.root {
             items.clear();
         }
     }
+
+    fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items of the menu, the groups and the separators are not listed.
+    pub fn items(&self) -> impl Iterator<Item = &ContextMenuItem> {
+        self.items.values()
+    }
+
+    fn root_items(&self) -> &[ContextMenuKind] {
+        if let ContextMenuKind::SubMenu { items, .. } = &self.root {
+            items
+        } else {
+            unreachable!()
+        }
+    }
+
+    /// Create the menu items of the providers for the given context, as it is done when the menu is opened.
+    pub fn build_dynamic(&self, graph: &Graph, context: &ContextMenuContext) -> ContextMenu {
+        let mut menu = ContextMenu::default();
+        for provider in &self.providers {
+            provider.build(graph, context, &mut menu.builder());
+        }
+        menu
+    }
 }
 
 pub struct ConextMenuBuilder<'m> {
@@ -105,6 +241,15 @@ impl<'m> ConextMenuBuilder<'m> {
         }
     }
 
+    pub fn add_separator(&mut self) -> &mut Self {
+        if let ContextMenuKind::SubMenu { items, .. } = &mut self.corrent {
+            items.push(ContextMenuKind::Separator);
+            self
+        } else {
+            unreachable!()
+        }
+    }
+
     pub fn add_group<'n, S: ToString>(&'n mut self, name: S) -> ConextMenuBuilder<'n>
     where
         'm: 'n,
@@ -125,17 +270,25 @@ impl<'m> ConextMenuBuilder<'m> {
     }
 }
 
+/// Environment of the menu rendering.
+struct MenuEnv<'a> {
+    keymap: &'a KeyMap,
+    triggered: Option<ActionId>,
+}
+
 #[derive(Clone)]
 pub(in crate::node_graph) struct ContextMenuState {
     filter: String,
-    start_location: Pos2,
+    context: ContextMenuContext,
+    dynamic: Arc<ContextMenu>,
 }
 
 impl Default for ContextMenuState {
     fn default() -> Self {
         ContextMenuState {
             filter: String::new(),
-            start_location: pos2(0., 0.),
+            context: ContextMenuContext::default(),
+            dynamic: Arc::new(ContextMenu::default()),
         }
     }
 }
@@ -149,27 +302,57 @@ impl ContextMenuState {
         ui.data().insert_temp(id, self);
     }
 
+    /// Location where the menu was opened in graph (area) coordinates.
+    pub fn location(&self) -> Pos2 {
+        self.context.location
+    }
+
+    fn show_item(&self, item: &ContextMenuItem, ui: &mut Ui, graph: &mut Graph, env: &mut MenuEnv) {
+        let data = item.data();
+        if !data.is_visible(graph, &self.context) {
+            return;
+        }
+
+        let enabled = data.is_enabled(graph, &self.context);
+        let text = match data.is_checked(graph, &self.context) {
+            Some(true) => format!("☑ {}", item.name),
+            Some(false) => format!("☐ {}", item.name),
+            None => item.name.clone(),
+        };
+        let mut button = Button::new(text);
+        if let Some(shortcut) = item.shortcut_text(env.keymap, ui.ctx()) {
+            button = button.shortcut_text(shortcut);
+        }
+
+        if ui.add_enabled(enabled, button).clicked() {
+            data.on_select(graph, &self.context);
+            env.triggered = data.action();
+            ui.close_menu();
+        }
+    }
+
     fn show_recursive(
         &self,
         menu_items: &SlotMap<ContextMenuId, ContextMenuItem>,
         current: &ContextMenuKind,
         ui: &mut Ui,
         graph: &mut Graph,
+        env: &mut MenuEnv,
     ) {
         match current {
             ContextMenuKind::SubMenu { name, items } => {
                 ui.menu_button(name, |ui| {
                     for sub_item in items {
-                        self.show_recursive(menu_items, sub_item, ui, graph);
+                        self.show_recursive(menu_items, sub_item, ui, graph, env);
                     }
                 });
             }
             ContextMenuKind::LeafItem(menu_id) => {
                 let item = menu_items.get(*menu_id).unwrap();
-                if ui.button(&item.name).clicked() {
-                    item.data.on_select(graph, self.start_location);
-                    ui.close_menu();
-                }
+                self.show_item(item, ui, graph, env);
+            }
+            ContextMenuKind::Separator => {
+                ui.separator();
             }
         }
     }
@@ -180,16 +363,28 @@ impl ContextMenuState {
         filter: &[&str],
         ui: &mut Ui,
         graph: &mut Graph,
+        env: &mut MenuEnv,
     ) {
         for item in menu_items.values() {
-            if filter.iter().any(|filter| item.name.starts_with(filter)) && ui.button(&item.name).clicked() {
-                item.data.on_select(graph, self.start_location);
-                ui.close_menu();
+            if filter.iter().any(|filter| item.name.starts_with(filter)) {
+                self.show_item(item, ui, graph, env);
             }
         }
     }
 
-    pub fn show(&mut self, ui: &mut Ui, zoom_pan: &ZoomPanState, content: &ContextMenu, graph: &mut Graph) {
+    /// Show the menu and return the action of the selected item.
+    #[allow(clippy::too_many_arguments)]
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        zoom_pan: &ZoomPanState,
+        content: &ContextMenu,
+        keymap: &KeyMap,
+        target: ContextMenuTarget,
+        selection: Vec<NodeId>,
+        interaction: InteractionMode,
+        graph: &mut Graph,
+    ) -> Option<ActionId> {
         ui.horizontal(|ui| {
             ui.text_edit_singleline(&mut self.filter).request_focus();
             if ui.button("X").clicked() {
@@ -197,14 +392,20 @@ impl ContextMenuState {
             }
         });
 
-        {
+        let press_origin = {
             let pointer = &ui.ctx().input().pointer;
-            if pointer.secondary_down() {
-                // context menu was just created
-                let pos = pointer.press_origin().unwrap();
-                self.start_location = zoom_pan.pos2_screen_to_area(pos);
-                self.filter = String::new(); // remove this line to keep the filter
-            }
+            pointer.press_origin().filter(|_| pointer.secondary_down())
+        };
+        if let Some(pos) = press_origin {
+            // context menu was just created
+            self.context = ContextMenuContext {
+                target,
+                location: zoom_pan.pos2_screen_to_area(pos),
+                selection,
+                interaction,
+            };
+            self.dynamic = Arc::new(content.build_dynamic(graph, &self.context));
+            self.filter = String::new(); // remove this line to keep the filter
         }
 
         //todo: store in state, thus no "heavy" calculation in each frame
@@ -215,16 +416,26 @@ impl ContextMenuState {
             .filter(|f| !f.is_empty())
             .collect::<Vec<&str>>();
 
+        let mut env = MenuEnv {
+            keymap,
+            triggered: None,
+        };
+        let dynamic = self.dynamic.clone();
         if filters.is_empty() {
-            if let ContextMenuKind::SubMenu { items, .. } = &content.root {
-                for sub_item in items {
-                    self.show_recursive(&content.items, sub_item, ui, graph);
-                }
-            } else {
-                unreachable!()
+            for sub_item in content.root_items() {
+                self.show_recursive(&content.items, sub_item, ui, graph, &mut env);
+            }
+            if !content.is_empty() && !dynamic.is_empty() {
+                ui.separator();
+            }
+            for sub_item in dynamic.root_items() {
+                self.show_recursive(&dynamic.items, sub_item, ui, graph, &mut env);
             }
         } else {
-            self.show_filtered(&content.items, &filters, ui, graph);
+            self.show_filtered(&content.items, &filters, ui, graph, &mut env);
+            self.show_filtered(&dynamic.items, &filters, ui, graph, &mut env);
         }
+
+        env.triggered
     }
 }
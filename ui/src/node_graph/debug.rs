@@ -0,0 +1,185 @@
+use crate::node_graph::{Graph, GraphEvaluator, Node, NodeId, NodeStatus};
+use egui::{Grid, Ui};
+use serde::{Deserialize, Serialize};
+use shine_core::slotmap::{Key, KeyData};
+use std::{fs, io, path::Path};
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum ExecutionTraceError {
+    #[error("Failed to access the trace")]
+    Io(#[from] io::Error),
+    #[error("Failed to parse the trace")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A node of the traced graph, the caption makes the trace readable in a bug report.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceNode {
+    node: u64,
+    pub kind: String,
+    pub caption: String,
+}
+
+impl TraceNode {
+    pub fn node_id(&self) -> NodeId {
+        KeyData::from_ffi(self.node).into()
+    }
+
+    /// Check if a node of a graph is the traced node, the id, the kind and the caption shall be the same.
+    pub fn matches(&self, node: &Node) -> bool {
+        self.node_id() == node.id() && self.kind == node.kind && self.caption == node.caption
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TraceEventKind {
+    Status(NodeStatus),
+    /// An output was set, the value is stored by its text if the type has a text representation.
+    Output {
+        port_id: usize,
+        value: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    node: u64,
+    pub kind: TraceEventKind,
+}
+
+impl TraceEvent {
+    pub fn new(node_id: NodeId, kind: TraceEventKind) -> Self {
+        Self {
+            node: node_id.data().as_ffi(),
+            kind,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        KeyData::from_ffi(self.node).into()
+    }
+}
+
+/// The events of an evaluation in the order they were collected. The events refer the nodes by their id, thus a
+/// trace can be replayed on the same graph, or on a graph loaded from the same document in a new session as long as
+/// the nodes get the same ids. The kind and the caption of the nodes are recorded too, a replay skips the events
+/// whose node does not match the recorded one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    nodes: Vec<TraceNode>,
+    events: Vec<TraceEvent>,
+}
+
+impl ExecutionTrace {
+    /// Clear the trace and record the nodes of the graph.
+    pub(in crate::node_graph) fn begin(&mut self, graph: &Graph) {
+        self.events.clear();
+        self.nodes = graph
+            .nodes()
+            .map(|node| TraceNode {
+                node: node.id().data().as_ffi(),
+                kind: node.kind.clone(),
+                caption: node.caption.clone(),
+            })
+            .collect();
+    }
+
+    pub fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    pub fn nodes(&self) -> &[TraceNode] {
+        &self.nodes
+    }
+
+    /// Return the recorded node with the given id.
+    pub fn node(&self, node_id: NodeId) -> Option<&TraceNode> {
+        self.nodes.iter().find(|node| node.node_id() == node_id)
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ExecutionTraceError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, ExecutionTraceError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ExecutionTraceError> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ExecutionTraceError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Show the state, the breakpoint and the port values of a node in an evaluation.
+pub struct NodeInspector<'a> {
+    evaluator: &'a mut GraphEvaluator,
+}
+
+impl<'a> NodeInspector<'a> {
+    pub fn new(evaluator: &'a mut GraphEvaluator) -> Self {
+        Self { evaluator }
+    }
+
+    pub fn show(self, ui: &mut Ui, graph: &Graph, node_id: NodeId) {
+        let node = match graph.node(node_id) {
+            Some(node) => node,
+            None => {
+                ui.label("No node");
+                return;
+            }
+        };
+
+        ui.strong(&node.caption);
+        let status = self
+            .evaluator
+            .status(node_id)
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        ui.label(format!("status: {}", status));
+        let mut breakpoint = self.evaluator.has_breakpoint(node_id);
+        if ui.checkbox(&mut breakpoint, "breakpoint").changed() {
+            self.evaluator.set_breakpoint(node_id, breakpoint);
+        }
+
+        let value_text = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        Grid::new(ui.id().with(node_id)).striped(true).show(ui, |ui| {
+            for port_id in 0..node.inputs.len() {
+                if let Some(input_id) = node.input_id(port_id) {
+                    let value = graph
+                        .connections()
+                        .find(|connection| connection.input_id() == input_id)
+                        .and_then(|connection| self.evaluator.value_text(connection.output_id()));
+                    ui.label(format!("⏵ {}", node.inputs[port_id].name));
+                    ui.label(value_text(value));
+                    ui.end_row();
+                }
+            }
+            for port_id in 0..node.outputs.len() {
+                if let Some(output_id) = node.output_id(port_id) {
+                    ui.label(format!("{} ⏵", node.outputs[port_id].name));
+                    ui.label(value_text(self.evaluator.value_text(output_id)));
+                    ui.end_row();
+                }
+            }
+        });
+    }
+}
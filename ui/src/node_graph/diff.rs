@@ -0,0 +1,394 @@
+use crate::node_graph::{utils::draw_connection, Graph, InputId, Node, NodeId, OutputId, PortViewState, ZoomPanState};
+use egui::{Color32, Id, LayerId, Order, Stroke, Ui};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The change of a node between two snapshots of a graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeChange {
+    Added,
+    Removed,
+    /// The node exists in both graphs. `moved` is set if the location differs, `changed` if the caption, kind,
+    /// ports or the data differ.
+    Modified {
+        moved: bool,
+        changed: bool,
+    },
+    /// The node exists in both graphs with the same caption, kind and ports, but the data has no
+    /// [content hash](crate::node_graph::NodeData::content_hash), thus it is not known if the data was changed.
+    Unknown {
+        moved: bool,
+    },
+}
+
+impl NodeChange {
+    pub fn is_moved(&self) -> bool {
+        matches!(
+            self,
+            NodeChange::Modified { moved: true, .. } | NodeChange::Unknown { moved: true }
+        )
+    }
+
+    pub fn is_changed(&self) -> bool {
+        matches!(self, NodeChange::Modified { changed: true, .. })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionChange {
+    Added,
+    Removed,
+}
+
+/// Compare the content of two nodes, the location is not considered. Return `None` if only the data could differ,
+/// but it has no content hash.
+fn is_node_changed(old: &Node, new: &Node) -> Option<bool> {
+    let ports_changed = old.inputs.len() != new.inputs.len()
+        || old.outputs.len() != new.outputs.len()
+        || old
+            .inputs
+            .iter()
+            .zip(new.inputs.iter())
+            .any(|(a, b)| a.name != b.name || a.port_type_id() != b.port_type_id())
+        || old
+            .outputs
+            .iter()
+            .zip(new.outputs.iter())
+            .any(|(a, b)| a.name != b.name || a.port_type_id() != b.port_type_id());
+
+    if old.caption != new.caption || old.kind != new.kind || ports_changed {
+        return Some(true);
+    }
+    match (old.data().content_hash(), new.data().content_hash()) {
+        (Some(old_hash), Some(new_hash)) => Some(old_hash != new_hash),
+        _ => None,
+    }
+}
+
+/// The differences between two snapshots of a graph.
+///
+/// The nodes are matched by their [`NodeId`] and the connections by their ports, thus the snapshots shall be
+/// created from the same graph, ex. by loading the versions of a document.
+#[derive(Clone, Debug, Default)]
+pub struct GraphDiff {
+    nodes: BTreeMap<NodeId, NodeChange>,
+    connections: HashMap<(InputId, OutputId), ConnectionChange>,
+}
+
+impl GraphDiff {
+    pub fn new(old: &Graph, new: &Graph) -> Self {
+        let mut nodes = BTreeMap::new();
+        for old_node in old.nodes() {
+            let node_id = old_node.id();
+            match new.node(node_id) {
+                Some(new_node) => {
+                    let moved = old_node.location != new_node.location;
+                    match is_node_changed(old_node, new_node) {
+                        Some(changed) => {
+                            if moved || changed {
+                                nodes.insert(node_id, NodeChange::Modified { moved, changed });
+                            }
+                        }
+                        None => {
+                            nodes.insert(node_id, NodeChange::Unknown { moved });
+                        }
+                    }
+                }
+                None => {
+                    nodes.insert(node_id, NodeChange::Removed);
+                }
+            }
+        }
+        for new_node in new.nodes() {
+            if old.node(new_node.id()).is_none() {
+                nodes.insert(new_node.id(), NodeChange::Added);
+            }
+        }
+
+        let old_connections: HashSet<_> = old.connections().map(|c| (c.input_id(), c.output_id())).collect();
+        let new_connections: HashSet<_> = new.connections().map(|c| (c.input_id(), c.output_id())).collect();
+        let mut connections = HashMap::new();
+        for key in old_connections.difference(&new_connections) {
+            connections.insert(*key, ConnectionChange::Removed);
+        }
+        for key in new_connections.difference(&old_connections) {
+            connections.insert(*key, ConnectionChange::Added);
+        }
+
+        Self { nodes, connections }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.connections.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, NodeChange)> + '_ {
+        self.nodes.iter().map(|(node_id, change)| (*node_id, *change))
+    }
+
+    pub fn connections(&self) -> impl Iterator<Item = (InputId, OutputId, ConnectionChange)> + '_ {
+        self.connections
+            .iter()
+            .map(|((input_id, output_id), change)| (*input_id, *output_id, *change))
+    }
+
+    pub fn node_change(&self, node_id: NodeId) -> Option<NodeChange> {
+        self.nodes.get(&node_id).cloned()
+    }
+
+    pub fn connection_change(&self, input_id: InputId, output_id: OutputId) -> Option<ConnectionChange> {
+        self.connections.get(&(input_id, output_id)).cloned()
+    }
+
+    /// Highlight the changed nodes and connections of the shown graph. Only the changes present in the graph
+    /// can be shown, i.e. the removed elements are visible when the old graph is shown.
+    pub(in crate::node_graph) fn show_overlay(
+        &self,
+        ui: &mut Ui,
+        id: Id,
+        zoom_pan: &ZoomPanState,
+        port_visual: &PortViewState,
+        graph: &Graph,
+    ) {
+        let theme = &zoom_pan.theme;
+        let painter = ui
+            .ctx()
+            .layer_painter(LayerId::new(Order::Foreground, id))
+            .with_clip_rect(zoom_pan.screen_rect);
+        let width = 2. * zoom_pan.zoom;
+
+        for (node_id, change) in self.nodes() {
+            if let Some(rect) = graph.node(node_id).and_then(|_| port_visual.get_node_rect(node_id)) {
+                let color = match change {
+                    NodeChange::Added => theme.added_color,
+                    NodeChange::Removed => theme.removed_color,
+                    NodeChange::Modified { changed: true, .. } | NodeChange::Unknown { .. } => theme.changed_color,
+                    NodeChange::Modified { .. } => theme.moved_color,
+                };
+                painter.rect_stroke(rect.expand(width), 4. * zoom_pan.zoom, Stroke::new(width, color));
+            }
+        }
+
+        for (input_id, output_id, change) in self.connections() {
+            if graph.find_connections(input_id, output_id).is_none() {
+                continue;
+            }
+            let start = port_visual.get_screen_pos(input_id.into());
+            let end = port_visual.get_screen_pos(output_id.into());
+            if let (Some(start), Some(end)) = (start, end) {
+                let color: Color32 = match change {
+                    ConnectionChange::Added => theme.added_color,
+                    ConnectionChange::Removed => theme.removed_color,
+                };
+                draw_connection(&painter, start, end, Stroke::new(width, color));
+            }
+        }
+    }
+}
+
+/// A change of "theirs" that can not be merged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// The node was modified on both sides differently or modified on one side and removed on the other. The nodes
+    /// with an [unknown](NodeChange::Unknown) change are always in conflict.
+    Node(NodeId),
+    /// The connection was added on one side, but one of its nodes was removed on the other.
+    Connection(InputId, OutputId),
+    /// The node was added or changed by "theirs", but its data does not support
+    /// [duplication](crate::node_graph::NodeData::duplicate), thus it can not be copied to "ours".
+    NotDuplicable(NodeId),
+}
+
+/// Three-way merge of the concurrent edits of a graph.
+///
+/// The changes of "theirs" are applied to "ours" except for the conflicts, where "ours" is kept.
+pub struct GraphMerge {
+    ours: GraphDiff,
+    theirs: GraphDiff,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl GraphMerge {
+    pub fn new(base: &Graph, ours: &Graph, theirs: &Graph) -> Self {
+        let ours_diff = GraphDiff::new(base, ours);
+        let theirs_diff = GraphDiff::new(base, theirs);
+
+        let mut conflicts = Vec::new();
+        for (node_id, their_change) in theirs_diff.nodes() {
+            let conflict = match (ours_diff.node_change(node_id), their_change) {
+                // nodes added on the two sides are unrelated, even if they have the same id
+                (_, NodeChange::Added) | (None, _) | (Some(NodeChange::Added), _) => false,
+                (Some(NodeChange::Removed), NodeChange::Removed) => false,
+                (Some(NodeChange::Removed), _) | (Some(_), NodeChange::Removed) => true,
+                (Some(NodeChange::Unknown { .. }), _) | (_, NodeChange::Unknown { .. }) => true,
+                (
+                    Some(NodeChange::Modified {
+                        moved: our_moved,
+                        changed: our_changed,
+                    }),
+                    NodeChange::Modified {
+                        moved: their_moved,
+                        changed: their_changed,
+                    },
+                ) => {
+                    let our_node = ours.node(node_id).unwrap();
+                    let their_node = theirs.node(node_id).unwrap();
+                    (our_changed && their_changed && is_node_changed(our_node, their_node) != Some(false))
+                        || (our_moved && their_moved && our_node.location != their_node.location)
+                }
+            };
+            if conflict {
+                conflicts.push(MergeConflict::Node(node_id));
+            } else if matches!(
+                their_change,
+                NodeChange::Added | NodeChange::Modified { changed: true, .. }
+            ) && theirs.node(node_id).and_then(|node| node.duplicate()).is_none()
+            {
+                conflicts.push(MergeConflict::NotDuplicable(node_id));
+            }
+        }
+
+        let is_removed = |diff: &GraphDiff, node_id: NodeId| diff.node_change(node_id) == Some(NodeChange::Removed);
+        for (input_id, output_id, change) in theirs_diff.connections() {
+            let nodes = [input_id.node_id(), output_id.node_id()];
+            if change == ConnectionChange::Added && nodes.iter().any(|node_id| is_removed(&ours_diff, *node_id)) {
+                conflicts.push(MergeConflict::Connection(input_id, output_id));
+            }
+        }
+        for (input_id, output_id, change) in ours_diff.connections() {
+            let nodes = [input_id.node_id(), output_id.node_id()];
+            if change == ConnectionChange::Added && nodes.iter().any(|node_id| is_removed(&theirs_diff, *node_id)) {
+                conflicts.push(MergeConflict::Connection(input_id, output_id));
+            }
+        }
+
+        Self {
+            ours: ours_diff,
+            theirs: theirs_diff,
+            conflicts,
+        }
+    }
+
+    pub fn ours(&self) -> &GraphDiff {
+        &self.ours
+    }
+
+    pub fn theirs(&self) -> &GraphDiff {
+        &self.theirs
+    }
+
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        &self.conflicts
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    fn is_node_conflict(&self, node_id: NodeId) -> bool {
+        self.conflicts.iter().any(
+            |conflict| matches!(conflict, MergeConflict::Node(id) | MergeConflict::NotDuplicable(id) if *id == node_id),
+        )
+    }
+
+    /// Remove the connections of a node whose port is missing or has a different type after the node was replaced.
+    fn remove_invalid_connections(graph: &mut Graph, node_id: NodeId) {
+        let invalid: Vec<_> = graph
+            .connections()
+            .filter(|connection| {
+                let input_id = connection.input_id();
+                let output_id = connection.output_id();
+                let input_valid = input_id.node_id() != node_id
+                    || graph
+                        .get_input(input_id)
+                        .is_some_and(|input| input.port_type_id() == input_id.port_type_id());
+                let output_valid = output_id.node_id() != node_id
+                    || graph
+                        .get_output(output_id)
+                        .is_some_and(|output| output.port_type_id() == output_id.port_type_id());
+                !input_valid || !output_valid
+            })
+            .map(|connection| connection.id())
+            .collect();
+        for connection_id in invalid {
+            graph.remove_connection(connection_id);
+        }
+    }
+
+    /// Apply the non-conflicting changes of "theirs" to "ours". The added nodes get new ids in "ours" and the
+    /// added connections are recreated by the validator. The connections of the nodes that could not be added are
+    /// skipped.
+    /// Return the ids of the added nodes in "ours" keyed by their id in "theirs".
+    pub fn apply(&self, ours: &mut Graph, theirs: &Graph) -> HashMap<NodeId, NodeId> {
+        let mut id_map = HashMap::new();
+
+        for (node_id, change) in self.theirs.nodes() {
+            if self.is_node_conflict(node_id) {
+                continue;
+            }
+            match change {
+                NodeChange::Added => {
+                    let node = theirs.node(node_id).and_then(|node| node.duplicate()).unwrap();
+                    id_map.insert(node_id, ours.add_node(node));
+                }
+                NodeChange::Removed => ours.remove_node(node_id),
+                NodeChange::Modified { moved, changed } => {
+                    let their_node = theirs.node(node_id).unwrap();
+                    if changed {
+                        let node = their_node.duplicate().unwrap();
+                        let location = ours.node(node_id).map(|node| node.location).unwrap_or(node.location);
+                        if let Some(our_node) = ours.node_mut(node_id) {
+                            *our_node = node.with_id(node_id);
+                            our_node.location = location;
+                        }
+                        Self::remove_invalid_connections(ours, node_id);
+                        ours.mark_changed();
+                    }
+                    if moved {
+                        if let Some(our_node) = ours.node_mut(node_id) {
+                            our_node.location = their_node.location;
+                        }
+                        ours.mark_changed();
+                    }
+                }
+                // the data can not be compared, "ours" is kept
+                NodeChange::Unknown { .. } => {}
+            }
+        }
+
+        // the nodes added by "theirs" are known by their new id only, the id of "theirs" may refer to an unrelated
+        // node added by "ours"
+        let map_node = |node_id: NodeId| match self.theirs.node_change(node_id) {
+            Some(NodeChange::Added) => id_map.get(&node_id).cloned(),
+            _ => Some(node_id),
+        };
+        for (input_id, output_id, change) in self.theirs.connections() {
+            if self.conflicts.contains(&MergeConflict::Connection(input_id, output_id)) {
+                continue;
+            }
+            match change {
+                ConnectionChange::Added => {
+                    let (input_node_id, output_node_id) =
+                        match (map_node(input_id.node_id()), map_node(output_id.node_id())) {
+                            (Some(input_node_id), Some(output_node_id)) => (input_node_id, output_node_id),
+                            _ => continue,
+                        };
+                    let input_id = InputId::new(input_node_id, input_id.port_type_id(), input_id.port_id());
+                    let output_id = OutputId::new(output_node_id, output_id.port_type_id(), output_id.port_id());
+                    if ours.get_output(output_id).is_none() {
+                        continue;
+                    }
+                    if let Some(connection) = ours.try_create_connection(input_id, output_id) {
+                        ours.add_connection(connection);
+                    }
+                }
+                ConnectionChange::Removed => {
+                    if let Some(connection_id) = ours.find_connections(input_id, output_id) {
+                        ours.remove_connection(connection_id);
+                    }
+                }
+            }
+        }
+
+        id_map
+    }
+}
@@ -0,0 +1,853 @@
+use crate::node_graph::{
+    utils::connection_point, ExecutionTrace, Graph, InputOutputId, Node, NodeId, OutputId, PortStyles, PortValueSource,
+    PortViewState, TraceEvent, TraceEventKind, TypedOutputId, ZoomPanState,
+};
+use egui::{pos2, vec2, Align2, Context, Id, LayerId, Order, Rect, Stroke, Ui};
+use serde::{Deserialize, Serialize};
+use shine_core::crossbeam::channel::{self, Receiver, Sender};
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, HashSet},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Number of the dots moving along the active wires.
+const FLOW_DOT_COUNT: usize = 3;
+/// Speed of the dots as the change of the curve parameter per second, a dot passes the wire in 2 seconds.
+const FLOW_SPEED: f64 = 0.5;
+
+/// A value computed by the evaluation.
+pub type PortValue = Arc<dyn Any + Send + Sync>;
+
+/// The computation of a node. It is created from the node on the UI thread and it is run on a worker thread of
+/// the [`GraphEvaluator`], thus it shall capture everything it needs from the node.
+pub trait NodeTask: 'static + Send {
+    fn run(self: Box<Self>, context: &mut TaskContext) -> Result<(), String>;
+}
+
+impl<F> NodeTask for F
+where
+    F: 'static + Send + FnOnce(&mut TaskContext) -> Result<(), String>,
+{
+    fn run(self: Box<Self>, context: &mut TaskContext) -> Result<(), String> {
+        (*self)(context)
+    }
+}
+
+pub trait TaskFactory {
+    /// Create the task of a node, `None` if the node has nothing to compute.
+    fn create_task(&self, node: &Node) -> Option<Box<dyn NodeTask>>;
+}
+
+impl<F> TaskFactory for F
+where
+    F: Fn(&Node) -> Option<Box<dyn NodeTask>>,
+{
+    fn create_task(&self, node: &Node) -> Option<Box<dyn NodeTask>> {
+        (self)(node)
+    }
+}
+
+/// The state of a node in an evaluation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NodeStatus {
+    /// Waiting for the inputs.
+    Pending,
+    /// The inputs are ready, the node is stopped by a breakpoint or by stepping.
+    Paused,
+    /// The task is running with the reported progress in the `[0,1]` range.
+    Running(f32),
+    Done,
+    Failed(String),
+    /// The task was not run as some of its inputs have failed.
+    Skipped,
+    Cancelled,
+}
+
+impl NodeStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, NodeStatus::Pending | NodeStatus::Paused | NodeStatus::Running(_))
+    }
+}
+
+impl fmt::Display for NodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeStatus::Pending => write!(f, "pending"),
+            NodeStatus::Paused => write!(f, "paused"),
+            NodeStatus::Running(progress) => write!(f, "running {:.0}%", progress * 100.),
+            NodeStatus::Done => write!(f, "done"),
+            NodeStatus::Failed(message) => write!(f, "failed: {}", message),
+            NodeStatus::Skipped => write!(f, "skipped, an input has failed"),
+            NodeStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+enum EventKind {
+    Status(NodeStatus),
+    Output(OutputId, PortValue),
+}
+
+struct Event {
+    run_id: u64,
+    node_id: NodeId,
+    kind: EventKind,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct PlannedNode {
+    task: Option<Box<dyn NodeTask>>,
+    /// The connected outputs and the default value of each input port.
+    inputs: Vec<(Vec<OutputId>, Option<PortValue>)>,
+    outputs: Vec<OutputId>,
+    /// Number of the connections whose source is not finished yet.
+    dependencies: usize,
+    dependents: Vec<NodeId>,
+    upstream_failed: bool,
+}
+
+/// The breakpoints and the tasks stopped by them, it is shared by the evaluator and the runs.
+#[derive(Default)]
+struct DebugState {
+    breakpoints: HashSet<NodeId>,
+    stepping: bool,
+    /// The stopped tasks with their topological order.
+    held: Vec<(usize, NodeId, Job)>,
+}
+
+impl DebugState {
+    fn release(&mut self, jobs: &Sender<Option<Job>>, count: usize) {
+        self.held.sort_by_key(|(order, _, _)| *order);
+        for (_, _, job) in self.held.drain(..count.min(self.held.len())) {
+            let _ = jobs.send(Some(job));
+        }
+    }
+}
+
+struct RunState {
+    nodes: HashMap<NodeId, PlannedNode>,
+    values: HashMap<OutputId, PortValue>,
+}
+
+/// The state of an evaluation shared with the worker threads.
+struct Run {
+    id: u64,
+    cancelled: AtomicBool,
+    state: Mutex<RunState>,
+    /// The topological order of the nodes, the stepping follows it.
+    order: HashMap<NodeId, usize>,
+    debug: Arc<Mutex<DebugState>>,
+    jobs: Sender<Option<Job>>,
+    events: Sender<Event>,
+    repaint: Option<Context>,
+}
+
+impl Run {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn send(&self, node_id: NodeId, kind: EventKind) {
+        let _ = self.events.send(Event {
+            run_id: self.id,
+            node_id,
+            kind,
+        });
+        if let Some(ctx) = &self.repaint {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Queue the task of a node whose inputs are all finished.
+    fn schedule(self: &Arc<Self>, node_id: NodeId) {
+        let (task, inputs, outputs, upstream_failed) = {
+            let mut state = self.state.lock().unwrap();
+            let RunState { nodes, values } = &mut *state;
+            let node = match nodes.get_mut(&node_id) {
+                Some(node) => node,
+                None => return,
+            };
+            let inputs: Vec<_> = node
+                .inputs
+                .iter()
+                .map(|(sources, default)| {
+                    let values: Vec<_> = sources
+                        .iter()
+                        .filter_map(|source| values.get(source).cloned())
+                        .collect();
+                    (values, default.clone())
+                })
+                .collect();
+            (node.task.take(), inputs, node.outputs.clone(), node.upstream_failed)
+        };
+
+        match task {
+            None => self.complete(node_id, !upstream_failed),
+            Some(_) if upstream_failed => {
+                self.send(node_id, EventKind::Status(NodeStatus::Skipped));
+                self.complete(node_id, false);
+            }
+            Some(task) => {
+                let run = self.clone();
+                let job: Job = Box::new(move || run.execute(node_id, task, inputs, outputs));
+                let mut debug = self.debug.lock().unwrap();
+                if debug.stepping || debug.breakpoints.contains(&node_id) {
+                    let order = self.order.get(&node_id).cloned().unwrap_or(usize::MAX);
+                    debug.held.push((order, node_id, job));
+                    drop(debug);
+                    self.send(node_id, EventKind::Status(NodeStatus::Paused));
+                } else {
+                    let _ = self.jobs.send(Some(job));
+                }
+            }
+        }
+    }
+
+    fn execute(
+        self: &Arc<Self>,
+        node_id: NodeId,
+        task: Box<dyn NodeTask>,
+        inputs: Vec<(Vec<PortValue>, Option<PortValue>)>,
+        outputs: Vec<OutputId>,
+    ) {
+        if self.is_cancelled() {
+            self.send(node_id, EventKind::Status(NodeStatus::Cancelled));
+            self.complete(node_id, false);
+            return;
+        }
+
+        self.send(node_id, EventKind::Status(NodeStatus::Running(0.)));
+        let mut context = TaskContext {
+            node_id,
+            inputs,
+            outputs,
+            run: self.clone(),
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| task.run(&mut context)));
+        let status = match result {
+            _ if self.is_cancelled() => NodeStatus::Cancelled,
+            Ok(Ok(())) => NodeStatus::Done,
+            Ok(Err(message)) => NodeStatus::Failed(message),
+            Err(_) => NodeStatus::Failed("Task has panicked".to_string()),
+        };
+        let succeeded = status == NodeStatus::Done;
+        self.send(node_id, EventKind::Status(status));
+        self.complete(node_id, succeeded);
+    }
+
+    /// Release the dependents of a finished node.
+    fn complete(self: &Arc<Self>, node_id: NodeId, succeeded: bool) {
+        let ready: Vec<NodeId> = {
+            let mut state = self.state.lock().unwrap();
+            let dependents = match state.nodes.get(&node_id) {
+                Some(node) => node.dependents.clone(),
+                None => return,
+            };
+            dependents
+                .into_iter()
+                .filter(|dependent| match state.nodes.get_mut(dependent) {
+                    Some(node) => {
+                        node.dependencies -= 1;
+                        node.upstream_failed |= !succeeded;
+                        node.dependencies == 0
+                    }
+                    None => false,
+                })
+                .collect()
+        };
+
+        for node_id in ready {
+            self.schedule(node_id);
+        }
+    }
+}
+
+/// The inputs and outputs of a running [`NodeTask`].
+pub struct TaskContext {
+    node_id: NodeId,
+    inputs: Vec<(Vec<PortValue>, Option<PortValue>)>,
+    outputs: Vec<OutputId>,
+    run: Arc<Run>,
+}
+
+impl TaskContext {
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Return the value of an input port. It is the value of the first connection, or the
+    /// [default](crate::node_graph::Input::with_default) of the port if it is not connected.
+    pub fn input<T: Any>(&self, port_id: usize) -> Option<&T> {
+        let (values, default) = self.inputs.get(port_id)?;
+        values.first().or(default.as_ref())?.downcast_ref::<T>()
+    }
+
+    /// Return the values of all the connections of an input port.
+    pub fn inputs<T: Any>(&self, port_id: usize) -> impl Iterator<Item = &T> {
+        self.inputs
+            .get(port_id)
+            .into_iter()
+            .flat_map(|(values, _)| values.iter())
+            .filter_map(|value| value.downcast_ref::<T>())
+    }
+
+    /// Set the value of an output port. The value is streamed to the editor immediately, the dependent nodes
+    /// get the last value set before the task has finished.
+    pub fn set_output<T: Any + Send + Sync>(&mut self, port_id: usize, value: T) {
+        let output_id = match self.outputs.get(port_id) {
+            Some(output_id) if output_id.port_type_id() == TypeId::of::<T>() => *output_id,
+            _ => {
+                log::warn!(
+                    "Output {} of {:?} has a different type or it is missing",
+                    port_id,
+                    self.node_id
+                );
+                return;
+            }
+        };
+        let value: PortValue = Arc::new(value);
+        self.run.state.lock().unwrap().values.insert(output_id, value.clone());
+        self.run.send(self.node_id, EventKind::Output(output_id, value));
+    }
+
+    pub fn set_progress(&self, progress: f32) {
+        self.run.send(
+            self.node_id,
+            EventKind::Status(NodeStatus::Running(progress.clamp(0., 1.))),
+        );
+    }
+
+    /// Long running tasks shall check it regularly and return early when the evaluation is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.run.is_cancelled()
+    }
+}
+
+/// Evaluate the nodes of a graph on a pool of worker threads. The independent branches of the graph are run in
+/// parallel, a node is run when all its inputs are computed.
+///
+/// The evaluation works on a snapshot of the graph taken by [`start`](Self::start), starting a new evaluation
+/// cancels the running one, thus the host shall restart it when the graph is changed. The progress and the results
+/// are collected by [`poll`](Self::poll) that shall be called on each frame.
+pub struct GraphEvaluator {
+    jobs: Sender<Option<Job>>,
+    pending_jobs: Receiver<Option<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    events_sender: Sender<Event>,
+    events: Receiver<Event>,
+    repaint: Option<Context>,
+    run: Option<Arc<Run>>,
+    next_run_id: u64,
+    status: HashMap<NodeId, NodeStatus>,
+    values: HashMap<OutputId, PortValue>,
+    port_styles: Arc<PortStyles>,
+    debug: Arc<Mutex<DebugState>>,
+    trace: Option<ExecutionTrace>,
+    /// The value texts of a replayed trace.
+    replayed_values: HashMap<OutputId, String>,
+}
+
+impl Default for GraphEvaluator {
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Self::new(0)
+        } else {
+            Self::new(thread::available_parallelism().map(|count| count.get()).unwrap_or(1))
+        }
+    }
+}
+
+impl GraphEvaluator {
+    /// Create an evaluator with the given number of worker threads. With no threads the tasks are run by
+    /// [`poll`](Self::poll) on the calling thread, ex. on the web.
+    pub fn new(thread_count: usize) -> Self {
+        let (jobs, pending_jobs) = channel::unbounded::<Option<Job>>();
+        let workers = (0..thread_count)
+            .map(|index| {
+                let pending_jobs = pending_jobs.clone();
+                thread::Builder::new()
+                    .name(format!("graph-evaluator-{}", index))
+                    .spawn(move || {
+                        while let Ok(Some(job)) = pending_jobs.recv() {
+                            job();
+                        }
+                    })
+                    .expect("Failed to start evaluator thread")
+            })
+            .collect();
+        let (events_sender, events) = channel::unbounded();
+
+        Self {
+            jobs,
+            pending_jobs,
+            workers,
+            events_sender,
+            events,
+            repaint: None,
+            run: None,
+            next_run_id: 0,
+            status: HashMap::new(),
+            values: HashMap::new(),
+            port_styles: Arc::new(PortStyles::default()),
+            debug: Arc::new(Mutex::new(DebugState::default())),
+            trace: None,
+            replayed_values: HashMap::new(),
+        }
+    }
+
+    /// Request a repaint of the context when a result is available.
+    pub fn with_repaint(mut self, ctx: Context) -> Self {
+        self.repaint = Some(ctx);
+        self
+    }
+
+    /// Start the evaluation of the graph, the running evaluation is cancelled.
+    pub fn start(&mut self, graph: &Graph, factory: &dyn TaskFactory) {
+        self.cancel();
+        self.port_styles = graph.get_port_styles().clone();
+        self.replayed_values.clear();
+        if let Some(trace) = &mut self.trace {
+            trace.begin(graph);
+        }
+
+        let mut nodes = HashMap::new();
+        for node in graph.nodes() {
+            nodes.insert(
+                node.id(),
+                PlannedNode {
+                    task: factory.create_task(node),
+                    inputs: node
+                        .inputs
+                        .iter()
+                        .map(|input| (Vec::new(), input.default_value().cloned()))
+                        .collect(),
+                    outputs: (0..node.outputs.len())
+                        .filter_map(|port_id| node.output_id(port_id))
+                        .collect(),
+                    dependencies: 0,
+                    dependents: Vec::new(),
+                    upstream_failed: false,
+                },
+            );
+        }
+        for connection in graph.connections() {
+            let input_id = connection.input_id();
+            let output_id = connection.output_id();
+            if !nodes.contains_key(&output_id.node_id()) {
+                continue;
+            }
+            let input_node = match nodes.get_mut(&input_id.node_id()) {
+                Some(node) => node,
+                None => continue,
+            };
+            match input_node.inputs.get_mut(input_id.port_id()) {
+                Some((sources, _)) => sources.push(output_id),
+                None => continue,
+            }
+            input_node.dependencies += 1;
+            nodes
+                .get_mut(&output_id.node_id())
+                .unwrap()
+                .dependents
+                .push(input_id.node_id());
+        }
+
+        // the nodes on a cycle and their dependents are never released
+        let mut dependencies: HashMap<NodeId, usize> = nodes
+            .iter()
+            .map(|(node_id, node)| (*node_id, node.dependencies))
+            .collect();
+        let roots: Vec<NodeId> = dependencies
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(node_id, _)| *node_id)
+            .collect();
+        let mut queue = roots.clone();
+        let mut order = HashMap::new();
+        while let Some(node_id) = queue.pop() {
+            order.insert(node_id, order.len());
+            for dependent in &nodes[&node_id].dependents {
+                let count = dependencies.get_mut(dependent).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    queue.push(*dependent);
+                }
+            }
+        }
+
+        self.status.clear();
+        self.values.clear();
+        for (node_id, node) in &nodes {
+            if node.task.is_some() {
+                let status = if dependencies[node_id] > 0 {
+                    NodeStatus::Failed("Node is on or after a cycle".to_string())
+                } else {
+                    NodeStatus::Pending
+                };
+                self.status.insert(*node_id, status);
+            }
+        }
+
+        self.next_run_id += 1;
+        let run = Arc::new(Run {
+            id: self.next_run_id,
+            cancelled: AtomicBool::new(false),
+            state: Mutex::new(RunState {
+                nodes,
+                values: HashMap::new(),
+            }),
+            order,
+            debug: self.debug.clone(),
+            jobs: self.jobs.clone(),
+            events: self.events_sender.clone(),
+            repaint: self.repaint.clone(),
+        });
+        for node_id in roots {
+            run.schedule(node_id);
+        }
+        self.run = Some(run);
+    }
+
+    /// Cancel the running evaluation. The running tasks are notified through [`TaskContext::is_cancelled`], the
+    /// tasks not started yet are skipped.
+    pub fn cancel(&mut self) {
+        if let Some(run) = self.run.take() {
+            run.cancelled.store(true, Ordering::Relaxed);
+        }
+        self.debug.lock().unwrap().held.clear();
+        for status in self.status.values_mut() {
+            if !status.is_finished() {
+                *status = NodeStatus::Cancelled;
+            }
+        }
+    }
+
+    /// Collect the progress and the results of the running evaluation. Return if anything has changed.
+    pub fn poll(&mut self) -> bool {
+        if self.workers.is_empty() {
+            while let Ok(Some(job)) = self.pending_jobs.try_recv() {
+                job();
+            }
+        }
+
+        let run_id = match &self.run {
+            Some(run) => run.id,
+            None => return false,
+        };
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            if event.run_id != run_id {
+                continue;
+            }
+            changed = true;
+            match event.kind {
+                EventKind::Status(status) => {
+                    if let Some(trace) = &mut self.trace {
+                        trace.push(TraceEvent::new(event.node_id, TraceEventKind::Status(status.clone())));
+                    }
+                    self.status.insert(event.node_id, status);
+                }
+                EventKind::Output(output_id, value) => {
+                    if self.trace.is_some() {
+                        let value = self.format_value(output_id, &value);
+                        let port_id = output_id.port_id();
+                        if let Some(trace) = &mut self.trace {
+                            trace.push(TraceEvent::new(
+                                event.node_id,
+                                TraceEventKind::Output { port_id, value },
+                            ));
+                        }
+                    }
+                    self.values.insert(output_id, value);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Return if some node is still waiting or running.
+    pub fn is_running(&self) -> bool {
+        self.status.values().any(|status| !status.is_finished())
+    }
+
+    pub fn status(&self, node_id: NodeId) -> Option<&NodeStatus> {
+        self.status.get(&node_id)
+    }
+
+    pub fn value(&self, output_id: OutputId) -> Option<&PortValue> {
+        self.values.get(&output_id)
+    }
+
+    pub fn output_value<T: Any>(&self, output_id: TypedOutputId<T>) -> Option<&T> {
+        self.value(output_id.id())?.downcast_ref::<T>()
+    }
+
+    /// Return the text of a value by the [display](crate::node_graph::PortStyle::display) of the port style.
+    fn format_value(&self, output_id: OutputId, value: &PortValue) -> Option<String> {
+        self.port_styles.find(output_id.port_type_id())?.display(&**value)
+    }
+
+    /// Return the text of the value of an output, it is available for the replayed traces too.
+    pub fn value_text(&self, output_id: OutputId) -> Option<String> {
+        match self.values.get(&output_id) {
+            Some(value) => self.format_value(output_id, value),
+            None => self.replayed_values.get(&output_id).cloned(),
+        }
+    }
+
+    /// Stop the evaluation before running the task of the node. It also affects the running evaluation.
+    pub fn set_breakpoint(&mut self, node_id: NodeId, enabled: bool) {
+        let mut debug = self.debug.lock().unwrap();
+        if enabled {
+            debug.breakpoints.insert(node_id);
+        } else {
+            debug.breakpoints.remove(&node_id);
+        }
+    }
+
+    pub fn toggle_breakpoint(&mut self, node_id: NodeId) {
+        let enabled = !self.has_breakpoint(node_id);
+        self.set_breakpoint(node_id, enabled);
+    }
+
+    pub fn has_breakpoint(&self, node_id: NodeId) -> bool {
+        self.debug.lock().unwrap().breakpoints.contains(&node_id)
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.debug.lock().unwrap().breakpoints.clear();
+    }
+
+    /// Stop before each node, see [`step`](Self::step).
+    pub fn set_stepping(&mut self, stepping: bool) {
+        self.debug.lock().unwrap().stepping = stepping;
+    }
+
+    pub fn is_stepping(&self) -> bool {
+        self.debug.lock().unwrap().stepping
+    }
+
+    /// Return if some node is stopped by a breakpoint or by stepping.
+    pub fn is_paused(&self) -> bool {
+        !self.debug.lock().unwrap().held.is_empty()
+    }
+
+    /// Run the first stopped node in topological order and stop again before the next one.
+    pub fn step(&mut self) {
+        let mut debug = self.debug.lock().unwrap();
+        debug.stepping = true;
+        debug.release(&self.jobs, 1);
+    }
+
+    /// Leave stepping and run the stopped nodes, the evaluation stops again at the next breakpoint.
+    pub fn resume(&mut self) {
+        let mut debug = self.debug.lock().unwrap();
+        debug.stepping = false;
+        let count = debug.held.len();
+        debug.release(&self.jobs, count);
+    }
+
+    /// Start recording the events of the next evaluations, `false` to stop and drop the recorded trace.
+    pub fn set_recording(&mut self, recording: bool) {
+        if !recording {
+            self.trace = None;
+        } else if self.trace.is_none() {
+            self.trace = Some(ExecutionTrace::default());
+        }
+    }
+
+    /// Return the trace of the last evaluation if recording is enabled.
+    pub fn trace(&self) -> Option<&ExecutionTrace> {
+        self.trace.as_ref()
+    }
+
+    /// Show the state of the nodes after the first `event_count` events of a recorded trace. The running
+    /// evaluation is cancelled. The events of the nodes that are missing from the graph or differ from the
+    /// [recorded node](crate::node_graph::TraceNode::matches) are skipped.
+    /// Return the number of the skipped events.
+    pub fn replay(&mut self, graph: &Graph, trace: &ExecutionTrace, event_count: usize) -> usize {
+        self.cancel();
+        self.status.clear();
+        self.values.clear();
+        self.replayed_values.clear();
+        let mut skipped = 0;
+        for event in trace.events().iter().take(event_count) {
+            let node = match (graph.node(event.node_id()), trace.node(event.node_id())) {
+                (Some(node), Some(traced)) if traced.matches(node) => node,
+                _ => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            match &event.kind {
+                TraceEventKind::Status(status) => {
+                    self.status.insert(node.id(), status.clone());
+                }
+                TraceEventKind::Output { port_id, value } => {
+                    if let (Some(output_id), Some(value)) = (node.output_id(*port_id), value) {
+                        self.replayed_values.insert(output_id, value.clone());
+                    }
+                }
+            }
+        }
+        if skipped > 0 {
+            log::warn!("{} events of the trace do not match the nodes of the graph", skipped);
+        }
+        skipped
+    }
+
+    fn is_node_running(&self, node_id: NodeId) -> bool {
+        matches!(self.status.get(&node_id), Some(NodeStatus::Running(_)))
+    }
+
+    fn breakpoints(&self) -> Vec<NodeId> {
+        self.debug.lock().unwrap().breakpoints.iter().cloned().collect()
+    }
+
+    /// Draw the breakpoints, highlight the paused and running nodes, show the progress, mark the failed nodes and show
+    /// the previews of the computed values.
+    pub(in crate::node_graph) fn show_overlay(
+        &self,
+        ui: &mut Ui,
+        id: Id,
+        zoom_pan: &ZoomPanState,
+        port_visual: &PortViewState,
+        graph: &Graph,
+    ) {
+        let painter = ui
+            .ctx()
+            .layer_painter(LayerId::new(Order::Foreground, id))
+            .with_clip_rect(zoom_pan.screen_rect);
+        let width = 2. * zoom_pan.zoom;
+        let port_styles = graph.get_port_styles();
+        let time = ui.input().time;
+        let mut is_animated = false;
+
+        for connection in graph.connections() {
+            let start = port_visual.get_screen_pos(connection.input_id().into());
+            let end = port_visual.get_screen_pos(connection.output_id().into());
+            let style = port_styles.find(connection.output_id().port_type_id());
+            let (start, end, style) = match (start, end, style) {
+                (Some(start), Some(end), Some(style)) => (start, end, style),
+                _ => continue,
+            };
+
+            // the value flows from the output (end) to the input (start)
+            if self.is_node_running(connection.output_node_id()) || self.is_node_running(connection.input_node_id()) {
+                is_animated = true;
+                let radius = (0.5 * style.connection_width + 1.) * zoom_pan.zoom;
+                for i in 0..FLOW_DOT_COUNT {
+                    let phase = (time * FLOW_SPEED + i as f64 / FLOW_DOT_COUNT as f64).fract() as f32;
+                    let pos = connection_point(start, end, 1. - phase);
+                    painter.circle_filled(pos, radius, zoom_pan.theme.hover_color);
+                }
+            }
+
+            let preview = self
+                .values
+                .get(&connection.output_id())
+                .and_then(|value| style.preview(&**value));
+            if let Some(preview) = preview {
+                let pos = connection_point(start, end, 0.5);
+                preview.paint(&painter, pos, Align2::CENTER_CENTER, zoom_pan.zoom, &zoom_pan.theme);
+            }
+        }
+
+        // the outputs without a connection show the preview next to the port
+        for (output_id, value) in &self.values {
+            if port_visual.get_connection_count((*output_id).into()) > 0 {
+                continue;
+            }
+            let pos = port_visual.get_screen_pos((*output_id).into());
+            let preview = port_styles
+                .find(output_id.port_type_id())
+                .and_then(|style| style.preview(&**value));
+            if let (Some(pos), Some(preview)) = (pos, preview) {
+                let anchor = pos + vec2(8. * zoom_pan.zoom, 0.);
+                preview.paint(&painter, anchor, Align2::LEFT_CENTER, zoom_pan.zoom, &zoom_pan.theme);
+            }
+        }
+
+        if is_animated {
+            ui.ctx().request_repaint();
+        }
+
+        for node_id in self.breakpoints() {
+            if let Some(rect) = port_visual.get_node_rect(node_id) {
+                painter.circle_filled(rect.left_top(), 4. * zoom_pan.zoom, zoom_pan.theme.error_color);
+            }
+        }
+
+        for (node_id, status) in &self.status {
+            let rect = match port_visual.get_node_rect(*node_id) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            match status {
+                NodeStatus::Paused => {
+                    painter.rect_stroke(
+                        rect.expand(width),
+                        4. * zoom_pan.zoom,
+                        Stroke::new(width, zoom_pan.theme.warning_color),
+                    );
+                }
+                NodeStatus::Running(progress) => {
+                    painter.rect_stroke(
+                        rect.expand(width),
+                        4. * zoom_pan.zoom,
+                        Stroke::new(width, zoom_pan.theme.hover_color),
+                    );
+                    let bar =
+                        Rect::from_min_size(pos2(rect.left(), rect.bottom() + width), vec2(rect.width(), 2. * width));
+                    painter.rect_filled(bar, 0., zoom_pan.theme.grid_color);
+                    painter.rect_filled(
+                        Rect::from_min_size(bar.min, vec2(bar.width() * progress, bar.height())),
+                        0.,
+                        zoom_pan.theme.hover_color,
+                    );
+                }
+                NodeStatus::Failed(_) => {
+                    painter.rect_stroke(
+                        rect.expand(width),
+                        4. * zoom_pan.zoom,
+                        Stroke::new(width, zoom_pan.theme.error_color),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl PortValueSource for GraphEvaluator {
+    fn port_value(&self, graph: &Graph, port_id: InputOutputId) -> Option<String> {
+        let output_id = match port_id {
+            InputOutputId::Output(output_id) => output_id,
+            InputOutputId::Input(input_id) => graph
+                .connections()
+                .find(|connection| connection.input_id() == input_id)?
+                .output_id(),
+        };
+        self.value_text(output_id)
+    }
+}
+
+impl Drop for GraphEvaluator {
+    fn drop(&mut self) {
+        self.cancel();
+        for _ in &self.workers {
+            let _ = self.jobs.send(None);
+        }
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,581 @@
+use crate::node_graph::{
+    utils::{connection_bezier_points, port_outline_width, port_shape_points},
+    Graph, GraphTheme, InputId, Node, NodeId, NodeShape, OutputId, PortShape, PortStyle,
+};
+use egui::{pos2, vec2, Color32, Pos2, Rect, Vec2};
+use std::{
+    any::TypeId,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+const FONT_SIZE: f32 = 14.;
+/// Estimated average width of a character, as no font metrics are available without a window.
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const HEADER_HEIGHT: f32 = 24.;
+const ROW_HEIGHT: f32 = 20.;
+const PADDING: f32 = 8.;
+const MIN_NODE_WIDTH: f32 = 80.;
+const SVG_MARGIN: f32 = 40.;
+
+fn hex_color(color: Color32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn opacity(color: Color32) -> f32 {
+    color.a() as f32 / 255.
+}
+
+fn text_width(text: &str) -> f32 {
+    text.chars().count() as f32 * CHAR_WIDTH
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+/// Position of a node and its ports in graph (area) units.
+struct NodeLayout {
+    rect: Rect,
+    inputs: Vec<(usize, Pos2)>,
+    outputs: Vec<(usize, Pos2)>,
+}
+
+/// Export a graph to text formats for documentation.
+///
+/// The port colors are taken from the [`PortStyles`](crate::node_graph::PortStyles) of the graph, the unstyled
+/// ports and the rest of the colors from the theme. As there is no font available outside of the editor, the size
+/// of the nodes in the SVG output is only an estimate of the size shown by the editor.
+pub struct GraphExport<'a> {
+    graph: &'a Graph,
+    theme: GraphTheme,
+}
+
+impl<'a> GraphExport<'a> {
+    pub fn new(graph: &'a Graph) -> Self {
+        Self {
+            graph,
+            theme: GraphTheme::default(),
+        }
+    }
+
+    pub fn with_theme(self, theme: &GraphTheme) -> Self {
+        Self {
+            theme: theme.clone(),
+            ..self
+        }
+    }
+
+    fn port_style(&self, type_id: TypeId) -> Option<&PortStyle> {
+        self.graph.get_port_styles().find(type_id)
+    }
+
+    fn port_color(&self, type_id: TypeId) -> Color32 {
+        self.port_style(type_id)
+            .map(|style| style.get_color(&self.theme))
+            .unwrap_or(self.theme.port_color)
+    }
+
+    fn wire_color(&self, type_id: TypeId) -> Color32 {
+        self.port_style(type_id)
+            .map(|style| style.get_wire_color(&self.theme))
+            .unwrap_or(self.theme.wire_color)
+    }
+
+    fn caption(&self, node: &Node) -> String {
+        match &self.graph.get_node_styles().find(&node.kind).icon {
+            Some(icon) => format!("{} {}", icon, node.caption),
+            None => node.caption.clone(),
+        }
+    }
+
+    /// Short names of the nodes that are valid identifiers in all the formats.
+    fn node_names(&self) -> HashMap<NodeId, String> {
+        self.graph
+            .nodes()
+            .enumerate()
+            .map(|(index, node)| (node.id(), format!("n{}", index)))
+            .collect()
+    }
+
+    /// Export the graph as a Graphviz DOT digraph. The nodes are drawn as tables with a row for each port pair and
+    /// the connections are attached to the ports. The kind, the caption and the location of the nodes are kept as
+    /// attributes, thus the output can be imported by
+    /// [`GraphImport::from_dot`](crate::node_graph::GraphImport::from_dot).
+    pub fn to_dot(&self) -> String {
+        let names = self.node_names();
+        let mut out = String::new();
+
+        writeln!(out, "digraph {{").unwrap();
+        writeln!(out, "    rankdir=LR;").unwrap();
+        writeln!(out, "    bgcolor=\"{}\";", hex_color(self.theme.background)).unwrap();
+        writeln!(
+            out,
+            "    node [shape=plaintext, fontcolor=\"{}\"];",
+            hex_color(self.theme.node_text_color)
+        )
+        .unwrap();
+
+        for node in self.graph.nodes() {
+            let style = self.graph.get_node_styles().find(&node.kind);
+            let header_color = style.header_color.unwrap_or(self.theme.node_fill);
+            let mut label = format!(
+                "<table border=\"1\" cellborder=\"0\" cellspacing=\"0\" color=\"{}\" bgcolor=\"{}\">",
+                hex_color(self.theme.node_stroke.color),
+                hex_color(self.theme.node_fill)
+            );
+            write!(
+                label,
+                "<tr><td colspan=\"2\" bgcolor=\"{}\"><b>{}</b></td></tr>",
+                hex_color(header_color),
+                escape_xml(&self.caption(node))
+            )
+            .unwrap();
+            for row in 0..node.inputs.len().max(node.outputs.len()) {
+                label.push_str("<tr>");
+                match node.inputs.get(row) {
+                    Some(input) => write!(
+                        label,
+                        "<td port=\"i{}\" align=\"left\"><font color=\"{}\">● </font>{}</td>",
+                        row,
+                        hex_color(self.port_color(input.port_type_id())),
+                        escape_xml(&input.name)
+                    )
+                    .unwrap(),
+                    None => label.push_str("<td></td>"),
+                }
+                match node.outputs.get(row) {
+                    Some(output) => write!(
+                        label,
+                        "<td port=\"o{}\" align=\"right\">{}<font color=\"{}\"> ●</font></td>",
+                        row,
+                        escape_xml(&output.name),
+                        hex_color(self.port_color(output.port_type_id()))
+                    )
+                    .unwrap(),
+                    None => label.push_str("<td></td>"),
+                }
+                label.push_str("</tr>");
+            }
+            label.push_str("</table>");
+            writeln!(
+                out,
+                "    {} [label=<{}>, kind=\"{}\", caption=\"{}\", pos=\"{},{}\"];",
+                names[&node.id()],
+                label,
+                escape_dot(&node.kind),
+                escape_dot(&node.caption),
+                node.location.x,
+                node.location.y
+            )
+            .unwrap();
+        }
+
+        for connection in self.graph.connections() {
+            let (input_id, output_id) = (connection.input_id(), connection.output_id());
+            let (input_node, output_node) = match (names.get(&input_id.node_id()), names.get(&output_id.node_id())) {
+                (Some(input_node), Some(output_node)) => (input_node, output_node),
+                _ => continue,
+            };
+            writeln!(
+                out,
+                "    {}:o{}:e -> {}:i{}:w [color=\"{}\"];",
+                output_node,
+                output_id.port_id(),
+                input_node,
+                input_id.port_id(),
+                hex_color(self.wire_color(input_id.port_type_id()))
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// Export the graph as a Mermaid flowchart. Mermaid has no ports, thus the port names are listed in the nodes
+    /// and the connections are labeled by the ports they connect.
+    pub fn to_mermaid(&self) -> String {
+        let names = self.node_names();
+        let mut out = String::new();
+
+        writeln!(out, "flowchart LR").unwrap();
+        for node in self.graph.nodes() {
+            let mut label = format!("<b>{}</b>", escape_mermaid(&self.caption(node)));
+            for (prefix, ports) in [
+                (
+                    "in",
+                    node.inputs.iter().map(|port| port.name.as_str()).collect::<Vec<_>>(),
+                ),
+                (
+                    "out",
+                    node.outputs.iter().map(|port| port.name.as_str()).collect::<Vec<_>>(),
+                ),
+            ] {
+                if !ports.is_empty() {
+                    write!(label, "<br/>{}: {}", prefix, escape_mermaid(&ports.join(", "))).unwrap();
+                }
+            }
+            writeln!(out, "    {}[\"{}\"]", names[&node.id()], label).unwrap();
+        }
+
+        let mut link_styles = Vec::new();
+        for connection in self.graph.connections() {
+            let (input_id, output_id) = (connection.input_id(), connection.output_id());
+            let (input_node, output_node) = match (names.get(&input_id.node_id()), names.get(&output_id.node_id())) {
+                (Some(input_node), Some(output_node)) => (input_node, output_node),
+                _ => continue,
+            };
+            let output_name = self
+                .graph
+                .get_output(output_id)
+                .map(|port| port.name.as_str())
+                .unwrap_or_default();
+            let input_name = self
+                .graph
+                .get_input(input_id)
+                .map(|port| port.name.as_str())
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "    {} -- \"{} → {}\" --> {}",
+                output_node,
+                escape_mermaid(output_name),
+                escape_mermaid(input_name),
+                input_node
+            )
+            .unwrap();
+            link_styles.push(hex_color(self.wire_color(input_id.port_type_id())));
+        }
+
+        for node in self.graph.nodes() {
+            let style = self.graph.get_node_styles().find(&node.kind);
+            let fill = style.header_color.unwrap_or(self.theme.node_fill);
+            writeln!(
+                out,
+                "    style {} fill:{},stroke:{},color:{}",
+                names[&node.id()],
+                hex_color(fill),
+                hex_color(self.theme.node_stroke.color),
+                hex_color(self.theme.node_text_color)
+            )
+            .unwrap();
+        }
+        for (index, color) in link_styles.iter().enumerate() {
+            writeln!(out, "    linkStyle {} stroke:{}", index, color).unwrap();
+        }
+
+        out
+    }
+
+    fn connected_ports(&self) -> (HashSet<InputId>, HashSet<OutputId>) {
+        let inputs = self.graph.connections().map(|c| c.input_id()).collect();
+        let outputs = self.graph.connections().map(|c| c.output_id()).collect();
+        (inputs, outputs)
+    }
+
+    fn layout_node(&self, node: &Node, connected: &(HashSet<InputId>, HashSet<OutputId>)) -> NodeLayout {
+        let node_id = node.id();
+        let inputs: Vec<_> = node
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(index, input)| {
+                !node.hide_unconnected_ports
+                    || connected
+                        .0
+                        .contains(&InputId::new(node_id, input.port_type_id(), *index))
+            })
+            .collect();
+        let outputs: Vec<_> = node
+            .outputs
+            .iter()
+            .enumerate()
+            .filter(|(index, output)| {
+                !node.hide_unconnected_ports
+                    || connected
+                        .1
+                        .contains(&OutputId::new(node_id, output.port_type_id(), *index))
+            })
+            .collect();
+
+        let style = self.graph.get_node_styles().find(&node.kind);
+        let caption_width = text_width(&self.caption(node)) + 2. * PADDING;
+        let is_compact = node.collapsed || style.shape == NodeShape::Pill;
+
+        let rect = if is_compact {
+            Rect::from_min_size(node.location, vec2(caption_width.max(MIN_NODE_WIDTH), HEADER_HEIGHT))
+        } else {
+            let input_width = inputs.iter().map(|(_, port)| text_width(&port.name)).fold(0., f32::max);
+            let output_width = outputs
+                .iter()
+                .map(|(_, port)| text_width(&port.name))
+                .fold(0., f32::max);
+            let rows = inputs.len().max(outputs.len()) as f32;
+            let mut size = vec2(
+                caption_width.max(input_width + output_width + 3. * PADDING),
+                HEADER_HEIGHT + rows * ROW_HEIGHT + PADDING,
+            );
+            if let Some(min_size) = node.size {
+                size = size.max(min_size + vec2(0., HEADER_HEIGHT));
+            }
+            Rect::from_min_size(node.location, size.max(vec2(MIN_NODE_WIDTH, HEADER_HEIGHT)))
+        };
+
+        // collapsed nodes stack the ports at the center of the sides, pills distribute them along the sides
+        let port_pos = |x: f32, index: usize, count: usize| {
+            if node.collapsed {
+                pos2(x, rect.center().y)
+            } else if is_compact {
+                pos2(x, rect.top() + rect.height() * (index + 1) as f32 / (count + 1) as f32)
+            } else {
+                pos2(x, rect.top() + HEADER_HEIGHT + ROW_HEIGHT * (index as f32 + 0.5))
+            }
+        };
+        let input_count = inputs.len();
+        let output_count = outputs.len();
+        NodeLayout {
+            rect,
+            inputs: inputs
+                .iter()
+                .enumerate()
+                .map(|(row, (index, _))| (*index, port_pos(rect.left(), row, input_count)))
+                .collect(),
+            outputs: outputs
+                .iter()
+                .enumerate()
+                .map(|(row, (index, _))| (*index, port_pos(rect.right(), row, output_count)))
+                .collect(),
+        }
+    }
+
+    fn write_port(out: &mut String, shape: PortShape, center: Pos2, r: f32, color: Color32, hollow: bool) {
+        let paint = if hollow {
+            format!(
+                "fill=\"none\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{}\"",
+                hex_color(color),
+                opacity(color),
+                port_outline_width(r)
+            )
+        } else {
+            format!("fill=\"{}\" fill-opacity=\"{}\"", hex_color(color), opacity(color))
+        };
+        // keep the outline inside the area of the filled shape, as the editor does
+        let r = if hollow { r - port_outline_width(r) * 0.5 } else { r };
+
+        match port_shape_points(shape, center, r) {
+            Some(points) => {
+                let points: Vec<_> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                writeln!(out, "<polygon points=\"{}\" {}/>", points.join(" "), paint).unwrap();
+            }
+            None => {
+                writeln!(
+                    out,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+                    center.x, center.y, r, paint
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Export the graph as a standalone SVG image drawn in graph (area) units, i.e. as the editor shows it at zoom 1.
+    pub fn to_svg(&self) -> String {
+        let connected = self.connected_ports();
+        let layouts: HashMap<NodeId, NodeLayout> = self
+            .graph
+            .nodes()
+            .map(|node| (node.id(), self.layout_node(node, &connected)))
+            .collect();
+        let input_pos: HashMap<(NodeId, usize), Pos2> = layouts
+            .iter()
+            .flat_map(|(node_id, layout)| layout.inputs.iter().map(|(index, pos)| ((*node_id, *index), *pos)))
+            .collect();
+        let output_pos: HashMap<(NodeId, usize), Pos2> = layouts
+            .iter()
+            .flat_map(|(node_id, layout)| layout.outputs.iter().map(|(index, pos)| ((*node_id, *index), *pos)))
+            .collect();
+
+        let bounds = layouts
+            .values()
+            .fold(Rect::NOTHING, |bounds, layout| bounds.union(layout.rect));
+        let bounds = if bounds.is_positive() {
+            bounds.expand(SVG_MARGIN)
+        } else {
+            Rect::from_min_size(Pos2::ZERO, Vec2::splat(2. * SVG_MARGIN))
+        };
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"{}\">",
+            bounds.left(),
+            bounds.top(),
+            bounds.width(),
+            bounds.height(),
+            bounds.width(),
+            bounds.height(),
+            FONT_SIZE
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            bounds.left(),
+            bounds.top(),
+            bounds.width(),
+            bounds.height(),
+            hex_color(self.theme.background)
+        )
+        .unwrap();
+
+        // nodes
+        let text_color = hex_color(self.theme.node_text_color);
+        for node in self.graph.nodes() {
+            let layout = &layouts[&node.id()];
+            let rect = layout.rect;
+            let style = self.graph.get_node_styles().find(&node.kind);
+            let rounding = match style.shape {
+                NodeShape::Box => 4.,
+                NodeShape::Pill => rect.height() / 2.,
+            };
+            writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                rect.left(),
+                rect.top(),
+                rect.width(),
+                rect.height(),
+                rounding,
+                hex_color(self.theme.node_fill),
+                hex_color(self.theme.node_stroke.color),
+                self.theme.node_stroke.width
+            )
+            .unwrap();
+            if let Some(header_color) = style.header_color {
+                let header = Rect::from_min_size(rect.min, vec2(rect.width(), HEADER_HEIGHT));
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"{}\"/>",
+                    header.left(),
+                    header.top(),
+                    header.width(),
+                    header.height(),
+                    rounding,
+                    hex_color(header_color)
+                )
+                .unwrap();
+            }
+            if rect.height() > HEADER_HEIGHT {
+                writeln!(
+                    out,
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"/>",
+                    rect.left(),
+                    rect.top() + HEADER_HEIGHT,
+                    rect.right(),
+                    rect.top() + HEADER_HEIGHT,
+                    hex_color(self.theme.node_stroke.color)
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                rect.center().x,
+                rect.top() + HEADER_HEIGHT / 2.,
+                text_color,
+                escape_xml(&self.caption(node))
+            )
+            .unwrap();
+
+            let is_compact = node.collapsed || style.shape == NodeShape::Pill;
+            for (index, pos) in &layout.inputs {
+                let input = &node.inputs[*index];
+                let type_id = input.port_type_id();
+                if !is_compact {
+                    writeln!(
+                        out,
+                        "<text x=\"{}\" y=\"{}\" fill=\"{}\" dominant-baseline=\"central\">{}</text>",
+                        pos.x + PADDING,
+                        pos.y,
+                        text_color,
+                        escape_xml(&input.name)
+                    )
+                    .unwrap();
+                }
+                let hollow = self.port_style(type_id).map(|s| s.hollow_unconnected).unwrap_or(false)
+                    && !connected.0.contains(&InputId::new(node.id(), type_id, *index));
+                self.write_port_at(&mut out, type_id, *pos, hollow);
+            }
+            for (index, pos) in &layout.outputs {
+                let output = &node.outputs[*index];
+                let type_id = output.port_type_id();
+                if !is_compact {
+                    writeln!(
+                        out,
+                        "<text x=\"{}\" y=\"{}\" fill=\"{}\" text-anchor=\"end\" dominant-baseline=\"central\">{}</text>",
+                        pos.x - PADDING,
+                        pos.y,
+                        text_color,
+                        escape_xml(&output.name)
+                    )
+                    .unwrap();
+                }
+                let hollow = self.port_style(type_id).map(|s| s.hollow_unconnected).unwrap_or(false)
+                    && !connected.1.contains(&OutputId::new(node.id(), type_id, *index));
+                self.write_port_at(&mut out, type_id, *pos, hollow);
+            }
+        }
+
+        // connections are drawn above the nodes as in the editor
+        for connection in self.graph.connections() {
+            let (input_id, output_id) = (connection.input_id(), connection.output_id());
+            let start = input_pos.get(&(input_id.node_id(), input_id.port_id()));
+            let end = output_pos.get(&(output_id.node_id(), output_id.port_id()));
+            if let Some([p0, p1, p2, p3]) = start.zip(end).and_then(|(s, e)| connection_bezier_points(*s, *e)) {
+                let type_id = input_id.port_type_id();
+                let width = self.port_style(type_id).map(|s| s.connection_width).unwrap_or(3.);
+                let color = self.wire_color(type_id);
+                writeln!(
+                    out,
+                    "<path d=\"M {} {} C {} {}, {} {}, {} {}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{}\"/>",
+                    p0.x,
+                    p0.y,
+                    p1.x,
+                    p1.y,
+                    p2.x,
+                    p2.y,
+                    p3.x,
+                    p3.y,
+                    hex_color(color),
+                    opacity(color),
+                    width
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(out, "</svg>").unwrap();
+        out
+    }
+
+    fn write_port_at(&self, out: &mut String, type_id: TypeId, pos: Pos2, hollow: bool) {
+        let (shape, size) = self
+            .port_style(type_id)
+            .map(|style| (style.shape, style.port_size))
+            .unwrap_or((PortShape::Circle, 5.));
+        Self::write_port(out, shape, pos, size, self.port_color(type_id), hollow);
+    }
+}
@@ -1,13 +1,20 @@
-use crate::node_graph::{Connection, ConnectionId, Input, InputId, Node, NodeId, Output, OutputId, PortStyles};
+use crate::node_graph::{
+    Connection, ConnectionId, GraphBuilder, Input, InputId, InputOutputId, Node, NodeId, NodeStyles, Output, OutputId,
+    PortStyles, PortValues, TypedInputId, TypedOutputId, ValidationReport,
+};
+use egui::Vec2;
 use shine_core::{
     downcast_rs::{impl_downcast, Downcast},
     slotmap::SlotMap,
     smallbox::{smallbox, space, SmallBox},
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{any::Any, collections::HashMap, sync::Arc};
 
 pub trait Validator: 'static + Downcast + Send + Sync {
     fn try_create_connection(&self, graph: &Graph, input_id: InputId, output_id: OutputId) -> Option<Connection>;
+
+    /// Add the application specific diagnostics of the whole graph, see [`Graph::validate`].
+    fn validate(&self, _graph: &Graph, _report: &mut ValidationReport) {}
 }
 impl_downcast!(Validator);
 
@@ -33,22 +40,28 @@ type BoxedGraphData = SmallBox<dyn GraphData, space::S32>;
 /// The node graph.
 pub struct Graph {
     styles: Arc<PortStyles>,
+    node_styles: Arc<NodeStyles>,
     nodes: SlotMap<NodeId, Node>,
     connections: SlotMap<ConnectionId, Connection>,
     connection_map: HashMap<(InputId, OutputId), ConnectionId>,
+    values: PortValues,
     data: BoxedGraphData,
     validator: Box<dyn Validator>,
+    revision: u64,
 }
 
 impl Default for Graph {
     fn default() -> Self {
         Self {
             styles: Arc::new(PortStyles::default()),
+            node_styles: Arc::new(NodeStyles::default()),
             nodes: SlotMap::default(),
             connections: SlotMap::default(),
             connection_map: HashMap::new(),
+            values: PortValues::default(),
             data: smallbox!(()),
             validator: Box::new(DefaultValidator),
+            revision: 0,
         }
     }
 }
@@ -69,6 +82,14 @@ impl Graph {
         self.styles = style.into()
     }
 
+    pub fn get_node_styles(&self) -> &Arc<NodeStyles> {
+        &self.node_styles
+    }
+
+    pub fn set_node_styles<S: Into<Arc<NodeStyles>>>(&mut self, style: S) {
+        self.node_styles = style.into()
+    }
+
     pub fn set_validator<V: Validator>(&mut self, validator: V) {
         self.validator = Box::new(validator);
     }
@@ -77,23 +98,46 @@ impl Graph {
         &*self.validator
     }
 
+    /// Return a builder to add nodes and connections to the graph.
+    pub fn builder(&mut self) -> GraphBuilder<'_> {
+        GraphBuilder::new(self)
+    }
+
     /// Clear the graph, but keeps the allocated memory.
     pub fn clear(&mut self) {
         self.nodes.clear();
         self.connections.clear();
+        self.connection_map.clear();
+        self.values.clear();
+        self.mark_changed();
+    }
+
+    /// Return a counter that is increased on each change of the graph: adding or removing nodes and connections
+    /// and the edits made in the [`GraphEdit`](crate::node_graph::GraphEdit). The changes made through the mutable
+    /// accessors are not tracked, the host shall report them by [`mark_changed`](Self::mark_changed).
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Report a change that is not tracked by the graph, ex. a change of the node data.
+    pub fn mark_changed(&mut self) {
+        self.revision += 1;
     }
 
     /// Add a new node to the graph with the given builder.
     pub fn add_node(&mut self, node: Node) -> NodeId {
+        self.mark_changed();
         self.nodes.insert_with_key(|node_id| node.with_id(node_id))
     }
 
     /// Remove a node with its connections from the graph.
     pub fn remove_node(&mut self, node_id: NodeId) {
         self.nodes.remove(node_id);
+        self.values.remove_node(node_id);
+        self.mark_changed();
 
         self.connections.retain(|_, connection| {
-            if connection.input_node_id() == node_id && connection.output_node_id() == node_id {
+            if connection.input_node_id() == node_id || connection.output_node_id() == node_id {
                 let key = (connection.input_id(), connection.output_id());
                 self.connection_map.remove(&key);
                 false
@@ -103,6 +147,46 @@ impl Graph {
         })
     }
 
+    /// Duplicate the nodes and the connections between them. The connections are recreated by
+    /// [`try_create_connection`](Self::try_create_connection).
+    /// Nodes that don't support duplication are skipped.
+    /// Return the ids of the new nodes in the order of the source nodes.
+    pub fn duplicate_nodes(&mut self, node_ids: &[NodeId], offset: Vec2) -> Vec<NodeId> {
+        let mut id_map = HashMap::new();
+        for node_id in node_ids {
+            if let Some(mut node) = self.nodes.get(*node_id).and_then(|node| node.duplicate()) {
+                node.location += offset;
+                let new_id = self.add_node(node);
+                id_map.insert(*node_id, new_id);
+            }
+        }
+
+        let connections: Vec<_> = self
+            .connections
+            .values()
+            .filter_map(|connection| {
+                let input_id = connection.input_id();
+                let output_id = connection.output_id();
+                let input_node = id_map.get(&input_id.node_id())?;
+                let output_node = id_map.get(&output_id.node_id())?;
+                Some((
+                    InputId::new(*input_node, input_id.port_type_id(), input_id.port_id()),
+                    OutputId::new(*output_node, output_id.port_type_id(), output_id.port_id()),
+                ))
+            })
+            .collect();
+        for (input_id, output_id) in connections {
+            if let Some(connection) = self.try_create_connection(input_id, output_id) {
+                self.add_connection(connection);
+            }
+        }
+
+        node_ids
+            .iter()
+            .filter_map(|node_id| id_map.get(node_id).cloned())
+            .collect()
+    }
+
     pub fn nodes(&self) -> impl Iterator<Item = &Node> {
         self.nodes.values()
     }
@@ -119,6 +203,15 @@ impl Graph {
         self.nodes.get_mut(node_id)
     }
 
+    /// Return the name of a port, `None` if the port is not part of the graph.
+    pub fn port_name(&self, port_id: InputOutputId) -> Option<&str> {
+        let node = self.node(port_id.node_id())?;
+        match port_id {
+            InputOutputId::Input(id) => node.inputs.get(id.port_id()).map(|input| input.name.as_str()),
+            InputOutputId::Output(id) => node.outputs.get(id.port_id()).map(|output| output.name.as_str()),
+        }
+    }
+
     /// Add a new connection to the graph with the given builder.
     /// # Panics
     /// This function will panic if there is a connection between these two ports.
@@ -129,11 +222,16 @@ impl Graph {
             .connections
             .insert_with_key(|connection_id| connection.with_id(connection_id));
         self.connection_map.insert(key, connection_id);
+        self.mark_changed();
         connection_id
     }
 
     pub fn remove_connection(&mut self, connection_id: ConnectionId) {
-        self.connections.remove(connection_id);
+        if let Some(connection) = self.connections.remove(connection_id) {
+            let key = (connection.input_id(), connection.output_id());
+            self.connection_map.remove(&key);
+            self.mark_changed();
+        }
     }
 
     pub fn connections(&self) -> impl Iterator<Item = &Connection> {
@@ -168,6 +266,76 @@ impl Graph {
             .and_then(|node| node.outputs.get(output_id.port_id()))
     }
 
+    /// Return the number of connections driving an input.
+    pub fn input_connection_count(&self, input_id: InputId) -> usize {
+        self.connections
+            .values()
+            .filter(|connection| connection.input_id() == input_id)
+            .count()
+    }
+
+    /// Try to create a new connection between the ports. The connection is rejected if the ports are
+    /// already connected, if the input is missing or it has reached its maximum number of connections,
+    /// otherwise the decision is made by the [`Validator`].
+    /// The connection is not added to the graph.
+    pub fn try_create_connection(&self, input_id: InputId, output_id: OutputId) -> Option<Connection> {
+        if self.find_connections(input_id, output_id).is_some() {
+            return None;
+        }
+        let input = self.get_input(input_id)?;
+        if let Some(max_connections) = input.max_connections() {
+            if self.input_connection_count(input_id) >= max_connections {
+                return None;
+            }
+        }
+        self.validator.try_create_connection(self, input_id, output_id)
+    }
+
+    /// Connect two ports of the same type. The connection is created by
+    /// [`try_create_connection`](Self::try_create_connection) and added to the graph.
+    pub fn connect<T: 'static>(
+        &mut self,
+        output_id: TypedOutputId<T>,
+        input_id: TypedInputId<T>,
+    ) -> Option<ConnectionId> {
+        let connection = self.try_create_connection(input_id.id(), output_id.id())?;
+        Some(self.add_connection(connection))
+    }
+
+    pub fn set_output_value<T: Any + Send + Sync>(&mut self, output_id: TypedOutputId<T>, value: T) {
+        self.values.set(output_id, value);
+    }
+
+    pub fn output_value<T: Any>(&self, output_id: TypedOutputId<T>) -> Option<&T> {
+        self.values.get(output_id)
+    }
+
+    pub fn output_value_mut<T: Any>(&mut self, output_id: TypedOutputId<T>) -> Option<&mut T> {
+        self.values.get_mut(output_id)
+    }
+
+    /// Return the values of the outputs connected to an input.
+    pub fn input_values<T: Any>(&self, input_id: TypedInputId<T>) -> impl Iterator<Item = &T> {
+        self.connections
+            .values()
+            .filter(move |connection| connection.input_id() == input_id.id())
+            .filter_map(|connection| TypedOutputId::<T>::try_from_id(connection.output_id()))
+            .filter_map(|output_id| self.values.get(output_id))
+    }
+
+    /// Return the value of an input. It is the value of the first connected output having a value, or
+    /// the [default](Input::with_default) of the port if there is no such output.
+    pub fn input_value<T: Any>(&self, input_id: TypedInputId<T>) -> Option<&T> {
+        self.input_values(input_id)
+            .next()
+            .or_else(|| self.get_input(input_id.id())?.default_as::<T>())
+    }
+
+    /// Validate the whole graph. See [`ValidationReport::validate`] for the checks.
+    pub fn validate(&self) -> ValidationReport {
+        ValidationReport::validate(self)
+    }
+
     pub fn data(&self) -> &dyn GraphData {
         &*self.data
     }
@@ -1,14 +1,19 @@
 use crate::node_graph::{
-    ConnectionEditState, ConnectionResult, ContextMenu, ContextMenuState, Graph, PortViewState, ZoomPanState,
+    ActionContext, ActionId, Actions, ConnectionEditState, ConnectionResult, ContextMenu, ContextMenuState,
+    ContextMenuTarget, DiagnosticLocation, Graph, GraphDiff, GraphEvaluator, GraphTheme, InputOutputId,
+    InteractionMode, NodeId, NodeStatus, PortValueSource, PortViewState, Selection, ValidationReport, ZoomPanState,
+    DEFAULT_LOD_ZOOM,
 };
-use egui::{Id, Key, Sense, Ui};
+use egui::{show_tooltip_at_pointer, vec2, Context, Id, Key, PointerButton, Pos2, Rect, Response, Sense, Ui, Vec2};
 
 /// Current editor mode
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
-pub(in crate::node_graph) enum EditorMode {
+pub enum EditorMode {
     #[default]
     None,
+    /// A node is hovered, clicked or dragged.
     NodeInteract,
+    /// A connection is dragged from a port.
     EditConnection,
     ContextMenu,
 }
@@ -16,6 +21,8 @@ pub(in crate::node_graph) enum EditorMode {
 #[derive(Default, Clone)]
 struct GraphEditState {
     mode: EditorMode,
+    selection: Selection,
+    dragged_node: Option<NodeId>,
 }
 
 impl GraphEditState {
@@ -28,11 +35,91 @@ impl GraphEditState {
     }
 }
 
+/// Snapshot of the view state of an editor stored in the egui memory, ex. to inspect the editor in tests.
+pub struct GraphEditView {
+    mode: EditorMode,
+    selection: Selection,
+    zoom_pan: ZoomPanState,
+    port_visual: PortViewState,
+}
+
+impl GraphEditView {
+    /// Load the state of the editor with the given id. Return `None` if the editor has not been shown yet.
+    pub fn load<I: Into<Id>>(ctx: &Context, id: I) -> Option<Self> {
+        let id = id.into();
+        let mut data = ctx.data();
+        let editor_state: GraphEditState = data.get_temp(id)?;
+        Some(Self {
+            mode: editor_state.mode,
+            selection: editor_state.selection,
+            zoom_pan: data.get_temp(id)?,
+            port_visual: data.get_temp(id)?,
+        })
+    }
+
+    pub fn mode(&self) -> EditorMode {
+        self.mode
+    }
+
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom_pan.zoom
+    }
+
+    pub fn pan(&self) -> Vec2 {
+        self.zoom_pan.pan
+    }
+
+    pub fn screen_rect(&self) -> Rect {
+        self.zoom_pan.screen_rect
+    }
+
+    pub fn area_to_screen(&self, pos: Pos2) -> Pos2 {
+        self.zoom_pan.pos2_area_to_screen(pos)
+    }
+
+    pub fn screen_to_area(&self, pos: Pos2) -> Pos2 {
+        self.zoom_pan.pos2_screen_to_area(pos)
+    }
+
+    /// Screen rect of a node including its ports as it was drawn in the last frame.
+    pub fn node_rect(&self, node_id: NodeId) -> Option<Rect> {
+        self.port_visual.get_node_rect(node_id)
+    }
+
+    /// Screen position of a port as it was drawn in the last frame.
+    pub fn port_pos<P: Into<InputOutputId>>(&self, port_id: P) -> Option<Pos2> {
+        self.port_visual.get_screen_pos(port_id.into())
+    }
+}
+
 /// The graph editor widget
+///
+/// The same graph can be shown in multiple views (ex. an overview and a detail panel) using an editor with a unique
+/// `id` for each view:
+/// - The [`Graph`] is owned by the host and borrowed only while the editor is shown. As the views are shown one after
+///   the other, the edits of a view are visible in the views shown later in the same frame and in all the views from
+///   the next frame.
+/// - The view state (camera, port locations, hover, connection edit) is stored in the egui memory keyed by the `id`,
+///   it is independent for each view.
+/// - The selection is also part of the view state, unless the host owns it and shares it among the views using
+///   [`with_selection`](Self::with_selection).
 pub struct GraphEdit<'a> {
     id: Id,
     graph: &'a mut Graph,
     context_menu: &'a ContextMenu,
+    actions: Option<&'a Actions>,
+    port_values: Option<&'a dyn PortValueSource>,
+    theme: Option<&'a GraphTheme>,
+    lod_zoom: f32,
+    selection: Option<&'a mut Selection>,
+    interaction: InteractionMode,
+    diff: Option<&'a GraphDiff>,
+    validation: Option<&'a ValidationReport>,
+    evaluation: Option<&'a GraphEvaluator>,
 }
 
 impl<'a> GraphEdit<'a> {
@@ -41,6 +128,248 @@ impl<'a> GraphEdit<'a> {
             id: id.into(),
             graph,
             context_menu,
+            actions: None,
+            port_values: None,
+            theme: None,
+            lod_zoom: DEFAULT_LOD_ZOOM,
+            selection: None,
+            interaction: InteractionMode::Full,
+            diff: None,
+            validation: None,
+            evaluation: None,
+        }
+    }
+
+    /// Mark the elements with diagnostics and show the messages in the tooltips.
+    pub fn with_validation(self, validation: &'a ValidationReport) -> Self {
+        Self {
+            validation: Some(validation),
+            ..self
+        }
+    }
+
+    /// Show the progress and the failures of an evaluation. The computed values are shown in the port tooltips,
+    /// unless [`with_port_values`](Self::with_port_values) is also used.
+    pub fn with_evaluation(self, evaluation: &'a GraphEvaluator) -> Self {
+        Self {
+            evaluation: Some(evaluation),
+            port_values: self.port_values.or(Some(evaluation)),
+            ..self
+        }
+    }
+
+    /// Center the view of the editor with the given id on an element in the next frame. Nodes and ports also
+    /// select the node.
+    pub fn focus<I: Into<Id>>(ctx: &Context, id: I, location: DiagnosticLocation) {
+        ctx.data().insert_temp(id.into().with("focus"), location);
+    }
+
+    fn apply_focus(
+        &self,
+        location: DiagnosticLocation,
+        editor_state: &mut GraphEditState,
+        zoom_pan: &mut ZoomPanState,
+        port_visual: &PortViewState,
+    ) {
+        let screen_rect = match location {
+            DiagnosticLocation::Graph => self
+                .graph
+                .nodes()
+                .filter_map(|node| port_visual.get_node_rect(node.id()))
+                .reduce(|a, b| a.union(b)),
+            DiagnosticLocation::Node(node_id) => port_visual.get_node_rect(node_id),
+            DiagnosticLocation::Port(port_id) => port_visual.get_node_rect(port_id.node_id()),
+            DiagnosticLocation::Connection(connection_id) => {
+                self.graph.connection(connection_id).and_then(|connection| {
+                    let input = port_visual.get_node_rect(connection.input_node_id())?;
+                    let output = port_visual.get_node_rect(connection.output_node_id())?;
+                    Some(input.union(output))
+                })
+            }
+        };
+
+        if let Some(node_id) = location.node_id().filter(|node_id| self.graph.node(*node_id).is_some()) {
+            editor_state.selection.set(node_id);
+        }
+        if let Some(rect) = screen_rect {
+            let area_rect = Rect::from_min_max(
+                zoom_pan.pos2_screen_to_area(rect.min),
+                zoom_pan.pos2_screen_to_area(rect.max),
+            );
+            zoom_pan.animate_center(area_rect, 20.);
+        }
+    }
+
+    /// Highlight the changes of the graph. Only the elements present in the shown graph can be highlighted, i.e.
+    /// the removed elements are visible only when the old graph is shown.
+    pub fn with_diff(self, diff: &'a GraphDiff) -> Self {
+        Self {
+            diff: Some(diff),
+            ..self
+        }
+    }
+
+    /// Restrict the modifications of the graph, ex. for a review screen.
+    pub fn with_interaction(self, interaction: InteractionMode) -> Self {
+        Self { interaction, ..self }
+    }
+
+    /// Use a selection owned by the host instead of the one stored with the view state.
+    pub fn with_selection(self, selection: &'a mut Selection) -> Self {
+        Self {
+            selection: Some(selection),
+            ..self
+        }
+    }
+
+    /// Set the actions and key bindings. If not set, the built-in actions with the default keymap are used.
+    pub fn with_actions(self, actions: &'a Actions) -> Self {
+        Self {
+            actions: Some(actions),
+            ..self
+        }
+    }
+
+    /// Set the source of the port values shown in the port tooltips.
+    pub fn with_port_values(self, port_values: &'a dyn PortValueSource) -> Self {
+        Self {
+            port_values: Some(port_values),
+            ..self
+        }
+    }
+
+    /// Set the theme of the editor. If not set, the preset matching the egui visuals is used.
+    /// The theme can be changed at any time, it is applied on the next frame.
+    pub fn with_theme(self, theme: &'a GraphTheme) -> Self {
+        Self {
+            theme: Some(theme),
+            ..self
+        }
+    }
+
+    /// Set the zoom level below which the nodes are drawn as simplified boxes, 0 to always draw the details.
+    pub fn with_lod_zoom(self, lod_zoom: f32) -> Self {
+        Self { lod_zoom, ..self }
+    }
+
+    fn show_port_tooltip(&self, ui: &mut Ui, port_id: InputOutputId) {
+        let port_name = match self.graph.port_name(port_id) {
+            Some(port_name) => port_name,
+            None => return,
+        };
+        let type_name = self
+            .graph
+            .get_port_styles()
+            .find(port_id.port_type_id())
+            .map(|style| style.name.as_str());
+        let value = self
+            .port_values
+            .and_then(|port_values| port_values.port_value(self.graph, port_id));
+
+        show_tooltip_at_pointer(ui.ctx(), self.id.with("port_tooltip"), |ui| {
+            ui.strong(port_name);
+            if let Some(type_name) = type_name {
+                ui.label(format!("type: {}", type_name));
+            }
+            if let Some(value) = value {
+                ui.label(format!("value: {}", value));
+            }
+            if let Some(validation) = self.validation {
+                validation.show_messages(ui, DiagnosticLocation::Port(port_id));
+            }
+        });
+    }
+
+    fn show_node_tooltip(&self, ui: &mut Ui, node_id: NodeId) {
+        let location = DiagnosticLocation::Node(node_id);
+        let status = self
+            .evaluation
+            .and_then(|evaluation| evaluation.status(node_id))
+            .filter(|status| !matches!(status, NodeStatus::Pending | NodeStatus::Done));
+        if status.is_none() {
+            self.show_diagnostics_tooltip(ui, location);
+            return;
+        }
+
+        show_tooltip_at_pointer(ui.ctx(), self.id.with("diagnostics_tooltip"), |ui| {
+            if let Some(validation) = self.validation {
+                validation.show_messages(ui, location);
+            }
+            if let Some(status) = status {
+                ui.label(status.to_string());
+            }
+        });
+    }
+
+    fn show_diagnostics_tooltip(&self, ui: &mut Ui, location: DiagnosticLocation) {
+        if let Some(validation) = self
+            .validation
+            .filter(|validation| validation.find(location).next().is_some())
+        {
+            show_tooltip_at_pointer(ui.ctx(), self.id.with("diagnostics_tooltip"), |ui| {
+                validation.show_messages(ui, location);
+            });
+        }
+    }
+
+    fn run_action(
+        &mut self,
+        actions: &Actions,
+        action: &ActionId,
+        location: Option<Pos2>,
+        editor_state: &mut GraphEditState,
+        zoom_pan: &mut ZoomPanState,
+        port_visual: &PortViewState,
+    ) {
+        if !self.interaction.allows_action(action) {
+            log::debug!("Action {:?} is not allowed in {:?} mode", action, self.interaction);
+            return;
+        }
+
+        let selection = editor_state.selection.to_vec();
+        if let Some(handler) = actions.handler(action) {
+            let context = ActionContext { selection, location };
+            handler.execute(self.graph, &context);
+            return;
+        }
+
+        match action {
+            ActionId::Delete => {
+                for node_id in selection {
+                    if self.graph.node(node_id).map(|node| !node.locked).unwrap_or(false) {
+                        self.graph.remove_node(node_id);
+                    }
+                }
+                editor_state.selection.retain_existing(self.graph);
+            }
+            ActionId::Duplicate => {
+                let new_nodes = self.graph.duplicate_nodes(&selection, vec2(20., 20.));
+                editor_state.selection.set_all(new_nodes);
+            }
+            ActionId::Frame => {
+                let framed_rect = self
+                    .graph
+                    .nodes()
+                    .filter(|node| selection.is_empty() || selection.contains(&node.id()))
+                    .filter_map(|node| port_visual.get_node_rect(node.id()))
+                    .map(|rect| {
+                        Rect::from_min_max(
+                            zoom_pan.pos2_screen_to_area(rect.min),
+                            zoom_pan.pos2_screen_to_area(rect.max),
+                        )
+                    })
+                    .reduce(|a, b| a.union(b));
+                if let Some(framed_rect) = framed_rect {
+                    zoom_pan.animate_frame(framed_rect, 20.);
+                }
+            }
+            ActionId::SelectAll => {
+                let all_nodes: Vec<_> = self.graph.nodes().map(|node| node.id()).collect();
+                editor_state.selection.set_all(all_nodes);
+            }
+            ActionId::Undo | ActionId::Group | ActionId::Custom(_) => {
+                log::debug!("No handler for action {:?}", action);
+            }
         }
     }
 
@@ -50,20 +379,42 @@ impl<'a> GraphEdit<'a> {
         zoom_pan: &ZoomPanState,
         editor_state: &mut GraphEditState,
         port_visual: &mut PortViewState,
-    ) {
+    ) -> Option<Response> {
         // render nodes
         let mut dragged_node = None;
+        let mut nodes_response: Option<Response> = None;
+        let mut changed = false;
 
         let style = self.graph.get_port_styles().clone();
+        let node_styles = self.graph.get_node_styles().clone();
         for node in self.graph.nodes_mut() {
-            let node_state = node.show(ui, zoom_pan, port_visual, &style);
+            let node_id = node.id();
+            let is_selected = editor_state.selection.contains(node_id);
+            let node_style = node_styles.find(&node.kind);
+            let (node_state, response) = node.show(ui, zoom_pan, port_visual, &style, node_style, is_selected);
             if node_state.dragged {
-                dragged_node = Some(node_state);
+                dragged_node = Some((node_id, node_state));
             }
+            changed |= response.changed();
+
+            if response.clicked() {
+                let modifiers = ui.input().modifiers;
+                if modifiers.command || modifiers.shift {
+                    editor_state.selection.toggle(node_id);
+                } else {
+                    editor_state.selection.set(node_id);
+                }
+            }
+
+            nodes_response = Some(match nodes_response {
+                Some(nodes_response) => nodes_response.union(response),
+                None => response,
+            });
         }
 
         if matches!(editor_state.mode, EditorMode::None | EditorMode::NodeInteract) {
-            if let Some(dragged_node) = dragged_node {
+            editor_state.dragged_node = dragged_node.as_ref().map(|(node_id, _)| *node_id);
+            if let Some((_, dragged_node)) = dragged_node {
                 // port hover has a higher precedence. Start connection edit instead of node drag (unless we are already in drag mode)
                 if dragged_node.drag_started && port_visual.has_hovered() {
                     editor_state.mode = EditorMode::EditConnection;
@@ -77,33 +428,111 @@ impl<'a> GraphEdit<'a> {
 
         //render connections
         for connection in self.graph.connections_mut() {
-            connection.show(ui, zoom_pan, port_visual, &style)
+            changed |= connection.show(ui, zoom_pan, port_visual, &style);
+        }
+
+        // the edits of the content and the layout are not tracked by the graph
+        if changed {
+            self.graph.mark_changed();
+        }
+
+        nodes_response
+    }
+
+    /// Find the element under the pointer that a context menu would be opened for.
+    fn hovered_target(port_visual: &PortViewState) -> ContextMenuTarget {
+        if let Some(port_id) = port_visual.get_hovered() {
+            ContextMenuTarget::Port(port_id)
+        } else if let Some(node_id) = port_visual.get_hovered_node() {
+            ContextMenuTarget::Node(node_id)
+        } else if let Some(connection_id) = port_visual.get_hovered_connection() {
+            ContextMenuTarget::Connection(connection_id)
+        } else {
+            ContextMenuTarget::Canvas
         }
     }
 
     pub fn show(&mut self, ui: &mut Ui) {
+        let default_actions;
+        let actions = match self.actions {
+            Some(actions) => actions,
+            None => {
+                default_actions = Actions::default();
+                &default_actions
+            }
+        };
+
         let mut editor_state = GraphEditState::load(ui, self.id).unwrap_or_default();
+        if let Some(selection) = &self.selection {
+            editor_state.selection = (*selection).clone();
+        }
         let mut zoom_pan = ZoomPanState::load(ui, self.id).unwrap_or_else(|| ZoomPanState::new(self.id, ui));
         let mut port_visual = PortViewState::load(ui, self.id).unwrap_or_default();
         let mut connection_edit = ConnectionEditState::load(ui, self.id).unwrap_or_default();
         let mut context_menu = ContextMenuState::load(ui, self.id).unwrap_or_default();
 
-        zoom_pan.prepare(ui.style());
+        // focus uses the layout of the previous frame
+        let focus_id = self.id.with("focus");
+        let focus = ui.data().get_temp::<DiagnosticLocation>(focus_id);
+        if let Some(location) = focus {
+            ui.data().remove::<DiagnosticLocation>(focus_id);
+            self.apply_focus(location, &mut editor_state, &mut zoom_pan, &port_visual);
+        }
+
+        let default_theme;
+        let theme = match self.theme {
+            Some(theme) => theme,
+            None => {
+                default_theme = GraphTheme::from_visuals(ui.visuals());
+                &default_theme
+            }
+        };
+        zoom_pan.prepare(ui.style(), theme);
+        zoom_pan.lod_zoom = self.lod_zoom;
         zoom_pan.screen_rect = ui.available_rect_before_wrap();
+        zoom_pan.draw_background(ui);
         port_visual.clear();
+        port_visual.update_connection_counts(self.graph);
         port_visual.set_nodes_enabled(matches!(editor_state.mode, EditorMode::None | EditorMode::NodeInteract));
-        port_visual.set_ports_enabled(matches!(
-            editor_state.mode,
-            EditorMode::None | EditorMode::EditConnection
-        ));
+        port_visual.set_ports_enabled(
+            self.interaction.allows_wiring()
+                && matches!(editor_state.mode, EditorMode::None | EditorMode::EditConnection),
+        );
+        port_visual.set_interaction(self.interaction);
         connection_edit.prepare(&mut port_visual);
+        editor_state.selection.retain_existing(self.graph);
 
-        let mut response = ui.interact(zoom_pan.screen_rect, self.id.with("graph"), Sense::drag());
+        let response = ui.interact(zoom_pan.screen_rect, self.id.with("graph"), Sense::click_and_drag());
 
-        zoom_pan.show_zoomed(ui, |ui| {
-            self.show_graph(ui, &zoom_pan, &mut editor_state, &mut port_visual);
+        let nodes_response = zoom_pan.show_zoomed(ui, |ui| {
+            self.show_graph(ui, &zoom_pan, &mut editor_state, &mut port_visual)
         });
 
+        if let Some(diff) = self.diff {
+            diff.show_overlay(ui, self.id.with("diff"), &zoom_pan, &port_visual, self.graph);
+        }
+        if let Some(validation) = self.validation {
+            validation.show_overlay(ui, self.id.with("validation"), &zoom_pan, &port_visual, self.graph);
+        }
+        if let Some(evaluation) = self.evaluation {
+            evaluation.show_overlay(ui, self.id.with("evaluation"), &zoom_pan, &port_visual, self.graph);
+        }
+
+        if response.clicked() {
+            editor_state.selection.clear();
+        }
+
+        if matches!(editor_state.mode, EditorMode::None) {
+            match Self::hovered_target(&port_visual) {
+                ContextMenuTarget::Port(port_id) => self.show_port_tooltip(ui, port_id),
+                ContextMenuTarget::Node(node_id) => self.show_node_tooltip(ui, node_id),
+                ContextMenuTarget::Connection(connection_id) => {
+                    self.show_diagnostics_tooltip(ui, DiagnosticLocation::Connection(connection_id))
+                }
+                ContextMenuTarget::Canvas => {}
+            }
+        }
+
         // connection edit
         if matches!(editor_state.mode, EditorMode::EditConnection) {
             editor_state.mode = match connection_edit.update(ui, &zoom_pan, &port_visual, self.graph) {
@@ -118,28 +547,128 @@ impl<'a> GraphEdit<'a> {
         }
 
         // context menu
-        if matches!(editor_state.mode, EditorMode::None | EditorMode::ContextMenu) {
+        let mut triggered_action = None;
+        if self.interaction.allows_context_menu()
+            && matches!(editor_state.mode, EditorMode::None | EditorMode::ContextMenu)
+        {
             editor_state.mode = EditorMode::None;
-            response = response.context_menu(|ui| {
+            let target = Self::hovered_target(&port_visual);
+            let selection = editor_state.selection.to_vec();
+            // open the menu for a right-click on the nodes too
+            let menu_response = match nodes_response {
+                Some(nodes_response) => response.union(nodes_response),
+                None => response.clone(),
+            };
+            menu_response.context_menu(|ui| {
                 editor_state.mode = EditorMode::ContextMenu;
-                context_menu.show(ui, &zoom_pan, self.context_menu, self.graph)
+                triggered_action = context_menu
+                    .show(
+                        ui,
+                        &zoom_pan,
+                        self.context_menu,
+                        actions.keymap(),
+                        target,
+                        selection,
+                        self.interaction,
+                        self.graph,
+                    )
+                    .map(|action| (action, Some(context_menu.location())));
             });
         }
 
-        //handle pan
+        // keyboard shortcuts, when the pointer is over the editor and no widget has the keyboard focus
+        if triggered_action.is_none() && matches!(editor_state.mode, EditorMode::None) {
+            let pointer_pos = ui
+                .ctx()
+                .pointer_latest_pos()
+                .filter(|pos| zoom_pan.screen_rect.contains(*pos));
+            if pointer_pos.is_some() && ui.memory().focus().is_none() {
+                triggered_action = actions
+                    .consume_pressed(ui.ctx())
+                    .map(|action| (action, pointer_pos.map(|pos| zoom_pan.pos2_screen_to_area(pos))));
+            }
+        }
+
+        if let Some((action, location)) = triggered_action {
+            self.run_action(
+                actions,
+                &action,
+                location,
+                &mut editor_state,
+                &mut zoom_pan,
+                &port_visual,
+            );
+        }
+
+        let dt = ui.input().stable_dt.min(0.1);
+        let pointer_pos = ui.ctx().pointer_latest_pos();
+        let pointer_in_screen = pointer_pos
+            .map(|pos| zoom_pan.screen_rect.contains(pos))
+            .unwrap_or(false);
+
+        //handle pan, with the primary button on the canvas and with the middle button anywhere
+        let mut is_panning = false;
         if matches!(editor_state.mode, EditorMode::None) {
-            zoom_pan.drag(response.drag_delta());
+            if response.drag_started() {
+                zoom_pan.stop();
+            }
+            let delta = if response.dragged_by(PointerButton::Primary) {
+                Some(response.drag_delta())
+            } else if pointer_in_screen && ui.input().pointer.button_down(PointerButton::Middle) {
+                Some(ui.input().pointer.delta())
+            } else {
+                None
+            };
+            if let Some(delta) = delta {
+                zoom_pan.drag_with_momentum(delta, dt);
+                is_panning = true;
+            }
         }
         //handle zoom
         if matches!(editor_state.mode, EditorMode::None | EditorMode::EditConnection) {
-            if let Some(pos) = ui.ctx().pointer_latest_pos() {
-                let zoom = ui.input().scroll_delta.y;
-                if zoom != 0. && zoom_pan.screen_rect.contains(pos) {
-                    let zoom = (zoom * 0.002).exp();
-                    zoom_pan.zoom_to_screen(pos, zoom);
+            if let Some(pos) = pointer_pos.filter(|_| pointer_in_screen) {
+                let (scroll, pinch) = {
+                    let input = ui.input();
+                    (input.scroll_delta.y, input.zoom_delta())
+                };
+                if pinch != 1. {
+                    // pinch is continuous, no need to animate
+                    zoom_pan.stop();
+                    zoom_pan.zoom_to_screen(pos, pinch);
+                } else if scroll != 0. {
+                    zoom_pan.animate_zoom_to_screen(pos, (scroll * 0.002).exp());
                 }
             }
         }
+        //handle scroll at the edges while dragging a node or a connection
+        if let Some(pos) = pointer_pos {
+            let is_dragging = match editor_state.mode {
+                EditorMode::NodeInteract => editor_state.dragged_node.is_some(),
+                EditorMode::EditConnection => true,
+                _ => false,
+            };
+            if is_dragging {
+                let delta = zoom_pan.edge_scroll(pos, dt);
+                if delta != Vec2::ZERO {
+                    // keep the dragged node under the pointer
+                    let interaction = self.interaction;
+                    let node = editor_state
+                        .dragged_node
+                        .and_then(|node_id| self.graph.node_mut(node_id))
+                        .filter(|node| node.is_layout_enabled(interaction));
+                    if let Some(node) = node {
+                        node.location -= delta;
+                        let location = node.location;
+                        node.data_mut().set_location(location);
+                        self.graph.mark_changed();
+                    }
+                    ui.ctx().request_repaint();
+                }
+            }
+        }
+        if !is_panning && zoom_pan.tick(dt) {
+            ui.ctx().request_repaint();
+        }
 
         if ui.input().key_pressed(Key::Escape) {
             // reset editor sate
@@ -158,6 +687,9 @@ impl<'a> GraphEdit<'a> {
                 ui.label(format!("clip: {:?}", zoom_pan.screen_rect));
             });*/
 
+        if let Some(selection) = &mut self.selection {
+            **selection = editor_state.selection.clone();
+        }
         editor_state.store(ui, self.id);
         zoom_pan.store(ui, self.id);
         port_visual.store(ui, self.id);
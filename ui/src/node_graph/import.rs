@@ -0,0 +1,641 @@
+use crate::node_graph::{Graph, Input, InputId, Node, NodeId, Output, OutputId};
+use egui::pos2;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, io, path::Path};
+use thiserror::Error as ThisError;
+
+/// Distance of the nodes placed automatically when the document has no location for them.
+const AUTO_LAYOUT_SPACING: (f32, f32) = (220., 160.);
+const AUTO_LAYOUT_COLUMNS: usize = 6;
+
+#[derive(Debug, ThisError)]
+pub enum GraphImportError {
+    #[error("Failed to read the graph")]
+    Io(#[from] io::Error),
+    #[error("Failed to parse the graph")]
+    Json(#[from] serde_json::Error),
+    #[error("Syntax error at line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("Node {node} is defined multiple times")]
+    DuplicateNode { node: String },
+    #[error("Node {node} has an unknown kind: {kind}")]
+    UnknownNodeKind { node: String, kind: String },
+    #[error("Port {node}.{port} has an unknown type: {port_type}")]
+    UnknownPortType {
+        node: String,
+        port: String,
+        port_type: String,
+    },
+    #[error("Unknown node: {node}")]
+    UnknownNode { node: String },
+    #[error("Node {node} has no {port} port")]
+    UnknownPort { node: String, port: String },
+    #[error("Connection {from}.{output} -> {to}.{input} was rejected by the validator")]
+    RejectedConnection {
+        from: String,
+        output: String,
+        to: String,
+        input: String,
+    },
+}
+
+/// Create the nodes of an imported graph by their kind.
+pub trait NodeFactory {
+    /// Create a node of the given kind, `None` if the kind is not known. The caption and the location are
+    /// overwritten by the document when it provides them.
+    fn create_node(&self, kind: &str) -> Option<Node>;
+}
+
+impl<F> NodeFactory for F
+where
+    F: Fn(&str) -> Option<Node>,
+{
+    fn create_node(&self, kind: &str) -> Option<Node> {
+        (self)(kind)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ImportPort {
+    name: String,
+    #[serde(rename = "type")]
+    port_type: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ImportNode {
+    id: String,
+    kind: String,
+    caption: Option<String>,
+    location: Option<[f32; 2]>,
+    inputs: Vec<ImportPort>,
+    outputs: Vec<ImportPort>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportEdge {
+    from: String,
+    output: String,
+    to: String,
+    input: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ImportDocument {
+    nodes: Vec<ImportNode>,
+    edges: Vec<ImportEdge>,
+}
+
+/// Build graphs from documents of other tools.
+///
+/// The nodes are created by a [`NodeFactory`] from their kind. The ports listed by the document that are missing
+/// from the created node are added, their type is resolved by the name of the registered
+/// [`PortStyle`](crate::node_graph::PortStyle)s. The connections refer the ports by name and they are created by the
+/// validator of the graph.
+///
+/// The import is atomic, on error the graph is left unchanged.
+pub struct GraphImport<'a> {
+    factory: &'a dyn NodeFactory,
+}
+
+impl<'a> GraphImport<'a> {
+    pub fn new(factory: &'a dyn NodeFactory) -> Self {
+        Self { factory }
+    }
+
+    /// Import a json document of the form:
+    /// ```json
+    /// {
+    ///   "nodes": [
+    ///     { "id": "a", "kind": "u8", "caption": "A", "location": [0, 0],
+    ///       "inputs": [], "outputs": [{ "name": "value", "type": "u8" }] }
+    ///   ],
+    ///   "edges": [{ "from": "a", "output": "value", "to": "b", "input": "x" }]
+    /// }
+    /// ```
+    /// Return the ids of the added nodes by their id in the document.
+    pub fn from_json(&self, graph: &mut Graph, json: &str) -> Result<HashMap<String, NodeId>, GraphImportError> {
+        let document: ImportDocument = serde_json::from_str(json)?;
+        self.import(graph, document)
+    }
+
+    /// Import a Graphviz DOT document. The node statements can have the following attributes:
+    /// - `kind`: the kind of the node, the `label` or the id of the node is used if missing
+    /// - `label`: the caption of the node, HTML labels are ignored
+    /// - `caption`: the caption of the node, it overrides the `label`
+    /// - `pos`: the location of the node as `"x,y"`
+    /// - `inputs`, `outputs`: the list of the ports as `"name:type,name:type"`
+    ///
+    /// Edges shall go from an output to an input port, ex. `a:value -> b:x`, the ports can be referred by their
+    /// index too, ex. `a:o0 -> b:i1`. Subgraphs are flattened and the default attributes are ignored. The output of
+    /// [`GraphExport::to_dot`](crate::node_graph::GraphExport::to_dot) can be imported with the same factory.
+    /// Return the ids of the added nodes by their id in the document.
+    pub fn from_dot(&self, graph: &mut Graph, dot: &str) -> Result<HashMap<String, NodeId>, GraphImportError> {
+        let document = DotParser::new(dot)?.parse()?;
+        self.import(graph, document)
+    }
+
+    /// Import a json or a DOT file selected by the extension (`.json` or `.dot`/`.gv`).
+    pub fn load<P: AsRef<Path>>(
+        &self,
+        graph: &mut Graph,
+        path: P,
+    ) -> Result<HashMap<String, NodeId>, GraphImportError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => self.from_json(graph, &content),
+            _ => self.from_dot(graph, &content),
+        }
+    }
+
+    fn create_node(&self, graph: &Graph, index: usize, import: &ImportNode) -> Result<Node, GraphImportError> {
+        let mut node = self
+            .factory
+            .create_node(&import.kind)
+            .ok_or_else(|| GraphImportError::UnknownNodeKind {
+                node: import.id.clone(),
+                kind: import.kind.clone(),
+            })?;
+
+        if let Some(caption) = &import.caption {
+            node.caption = caption.clone();
+        }
+        node.location = match import.location {
+            Some([x, y]) => pos2(x, y),
+            None => pos2(
+                (index % AUTO_LAYOUT_COLUMNS) as f32 * AUTO_LAYOUT_SPACING.0,
+                (index / AUTO_LAYOUT_COLUMNS) as f32 * AUTO_LAYOUT_SPACING.1,
+            ),
+        };
+
+        let port_styles = graph.get_port_styles();
+        let resolve = |port: &ImportPort| {
+            port_styles
+                .find_type_by_name(&port.port_type)
+                .ok_or_else(|| GraphImportError::UnknownPortType {
+                    node: import.id.clone(),
+                    port: port.name.clone(),
+                    port_type: port.port_type.clone(),
+                })
+        };
+        for port in &import.inputs {
+            let port_type_id = resolve(port)?;
+            if !node.inputs.iter().any(|input| input.name == port.name) {
+                node.inputs.push(Input::from_type_id(&port.name, port_type_id));
+            }
+        }
+        for port in &import.outputs {
+            let port_type_id = resolve(port)?;
+            if !node.outputs.iter().any(|output| output.name == port.name) {
+                node.outputs.push(Output::from_type_id(&port.name, port_type_id));
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn import(&self, graph: &mut Graph, document: ImportDocument) -> Result<HashMap<String, NodeId>, GraphImportError> {
+        // resolve everything before the graph is modified
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut node_index = HashMap::new();
+        for (index, import) in document.nodes.iter().enumerate() {
+            if node_index.insert(import.id.clone(), index).is_some() {
+                return Err(GraphImportError::DuplicateNode {
+                    node: import.id.clone(),
+                });
+            }
+            nodes.push(self.create_node(graph, index, import)?);
+        }
+
+        let mut edges = Vec::new();
+        for edge in &document.edges {
+            let find_node = |name: &String| {
+                node_index
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| GraphImportError::UnknownNode { node: name.clone() })
+            };
+            let unknown_port = |node: &String, port: &String| GraphImportError::UnknownPort {
+                node: node.clone(),
+                port: port.clone(),
+            };
+
+            let from = find_node(&edge.from)?;
+            let to = find_node(&edge.to)?;
+            let output_index = find_port(nodes[from].outputs.iter().map(|port| &port.name), 'o', &edge.output)
+                .ok_or_else(|| unknown_port(&edge.from, &edge.output))?;
+            let input_index = find_port(nodes[to].inputs.iter().map(|port| &port.name), 'i', &edge.input)
+                .ok_or_else(|| unknown_port(&edge.to, &edge.input))?;
+            edges.push((
+                edge,
+                (from, nodes[from].outputs[output_index].port_type_id(), output_index),
+                (to, nodes[to].inputs[input_index].port_type_id(), input_index),
+            ));
+        }
+
+        let node_ids: Vec<NodeId> = nodes.into_iter().map(|node| graph.add_node(node)).collect();
+        for (edge, (from, output_type, output_index), (to, input_type, input_index)) in edges {
+            let input_id = InputId::new(node_ids[to], input_type, input_index);
+            let output_id = OutputId::new(node_ids[from], output_type, output_index);
+            if graph.find_connections(input_id, output_id).is_some() {
+                continue;
+            }
+            match graph.try_create_connection(input_id, output_id) {
+                Some(connection) => {
+                    graph.add_connection(connection);
+                }
+                None => {
+                    // roll back, removing the nodes removes their connections too
+                    for node_id in node_ids {
+                        graph.remove_node(node_id);
+                    }
+                    return Err(GraphImportError::RejectedConnection {
+                        from: edge.from.clone(),
+                        output: edge.output.clone(),
+                        to: edge.to.clone(),
+                        input: edge.input.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(node_index
+            .into_iter()
+            .map(|(name, index)| (name, node_ids[index]))
+            .collect())
+    }
+}
+
+/// Find a port by its name or by its index as `i0`, `o0` written by the
+/// [`GraphExport::to_dot`](crate::node_graph::GraphExport::to_dot).
+fn find_port<'p>(names: impl Iterator<Item = &'p String>, prefix: char, name: &str) -> Option<usize> {
+    let names: Vec<_> = names.collect();
+    match names.iter().position(|port| *port == name) {
+        Some(index) => Some(index),
+        None => name
+            .strip_prefix(prefix)
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|index| *index < names.len()),
+    }
+}
+
+fn is_html(text: &str) -> bool {
+    text.starts_with('<') && text.ends_with('>')
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum DotToken {
+    Id(String),
+    Symbol(char),
+    Edge,
+}
+
+/// Minimal parser of the DOT language collecting the nodes and edges only.
+struct DotParser {
+    tokens: Vec<(DotToken, usize)>,
+    position: usize,
+}
+
+impl DotParser {
+    fn new(dot: &str) -> Result<Self, GraphImportError> {
+        let mut tokens = Vec::new();
+        let mut chars = dot.chars().peekable();
+        let mut line = 1;
+        let syntax_error = |line: usize, message: &str| GraphImportError::Syntax {
+            line,
+            message: message.to_string(),
+        };
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => line += 1,
+                c if c.is_whitespace() => {}
+                '#' => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            line += 1;
+                            break;
+                        }
+                    }
+                }
+                '/' if chars.peek() == Some(&'/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            line += 1;
+                            break;
+                        }
+                    }
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    let mut prev = ' ';
+                    loop {
+                        match chars.next() {
+                            Some('/') if prev == '*' => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                prev = c;
+                            }
+                            None => return Err(syntax_error(line, "unterminated comment")),
+                        }
+                    }
+                }
+                '-' if matches!(chars.peek(), Some('>') | Some('-')) => {
+                    chars.next();
+                    tokens.push((DotToken::Edge, line));
+                }
+                '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => tokens.push((DotToken::Symbol(c), line)),
+                '"' => {
+                    let start_line = line;
+                    let mut text = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('\\') => match chars.next() {
+                                Some('"') => text.push('"'),
+                                Some('\\') => text.push('\\'),
+                                Some('\n') => line += 1,
+                                Some(c) => {
+                                    text.push('\\');
+                                    text.push(c);
+                                }
+                                None => return Err(syntax_error(start_line, "unterminated string")),
+                            },
+                            Some('"') => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                text.push(c);
+                            }
+                            None => return Err(syntax_error(start_line, "unterminated string")),
+                        }
+                    }
+                    tokens.push((DotToken::Id(text), start_line));
+                }
+                '<' => {
+                    // html string, kept as it is with the enclosing brackets
+                    let start_line = line;
+                    let mut text = String::from("<");
+                    let mut depth = 1;
+                    loop {
+                        let c = match chars.next() {
+                            Some(c) => c,
+                            None => return Err(syntax_error(start_line, "unterminated html string")),
+                        };
+                        match c {
+                            '<' => depth += 1,
+                            '>' => depth -= 1,
+                            '\n' => line += 1,
+                            _ => {}
+                        }
+                        text.push(c);
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    tokens.push((DotToken::Id(text), start_line));
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                    let mut text = c.to_string();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_alphanumeric() || c == '_' || c == '.' {
+                            text.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    tokens.push((DotToken::Id(text), line));
+                }
+                c => return Err(syntax_error(line, &format!("unexpected character: {}", c))),
+            }
+        }
+
+        Ok(Self { tokens, position: 0 })
+    }
+
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<DotToken> {
+        let token = self.tokens.get(self.position).map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> GraphImportError {
+        self.error_at(self.position, message)
+    }
+
+    fn error_at(&self, position: usize, message: &str) -> GraphImportError {
+        let line = self
+            .tokens
+            .get(position.min(self.tokens.len().saturating_sub(1)))
+            .map(|(_, line)| *line)
+            .unwrap_or(1);
+        GraphImportError::Syntax {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    fn expect_id(&mut self) -> Result<String, GraphImportError> {
+        match self.next() {
+            Some(DotToken::Id(id)) => Ok(id),
+            _ => Err(self.error("identifier expected")),
+        }
+    }
+
+    fn skip_symbol(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&DotToken::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parse `[a=b, c=d; ...][...]`
+    fn parse_attributes(&mut self) -> Result<HashMap<String, String>, GraphImportError> {
+        let mut attributes = HashMap::new();
+        while self.skip_symbol('[') {
+            while !self.skip_symbol(']') {
+                let key = self.expect_id()?;
+                if !self.skip_symbol('=') {
+                    return Err(self.error("'=' expected"));
+                }
+                let value = self.expect_id()?;
+                attributes.insert(key, value);
+                if !self.skip_symbol(',') {
+                    self.skip_symbol(';');
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Parse `node_id[:port[:compass]]`
+    fn parse_node_port(&mut self) -> Result<(String, Option<String>), GraphImportError> {
+        let node = self.expect_id()?;
+        let port = if self.skip_symbol(':') {
+            let port = self.expect_id()?;
+            if self.skip_symbol(':') {
+                self.expect_id()?;
+            }
+            Some(port)
+        } else {
+            None
+        };
+        Ok((node, port))
+    }
+
+    /// Parse `name:type,name:type`
+    fn parse_ports(&self, list: Option<&String>) -> Result<Vec<ImportPort>, GraphImportError> {
+        list.map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|port| !port.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .map(|port| match port.split_once(':') {
+            Some((name, port_type)) => Ok(ImportPort {
+                name: name.trim().to_string(),
+                port_type: port_type.trim().to_string(),
+            }),
+            // the attribute list is already consumed, report the line of its last token
+            None => Err(self.error_at(
+                self.position - 1,
+                &format!("port type expected, ex. name:type: {}", port),
+            )),
+        })
+        .collect()
+    }
+
+    fn parse_location(value: &str) -> Option<[f32; 2]> {
+        let (x, y) = value.trim_end_matches('!').split_once(',')?;
+        Some([x.trim().parse().ok()?, y.trim().parse().ok()?])
+    }
+
+    fn parse(mut self) -> Result<ImportDocument, GraphImportError> {
+        let mut document = ImportDocument::default();
+        let mut node_index = HashMap::<String, usize>::new();
+        let mut add_node = |document: &mut ImportDocument, id: &str| -> usize {
+            *node_index.entry(id.to_string()).or_insert_with(|| {
+                document.nodes.push(ImportNode {
+                    id: id.to_string(),
+                    kind: id.to_string(),
+                    ..Default::default()
+                });
+                document.nodes.len() - 1
+            })
+        };
+
+        // header: [strict] (graph|digraph) [id] {
+        if self.peek() == Some(&DotToken::Id("strict".into())) {
+            self.position += 1;
+        }
+        match self.next() {
+            Some(DotToken::Id(kind)) if kind == "graph" || kind == "digraph" => {}
+            _ => return Err(self.error("graph or digraph expected")),
+        }
+        if let Some(DotToken::Id(_)) = self.peek() {
+            self.position += 1;
+        }
+        if !self.skip_symbol('{') {
+            return Err(self.error("'{' expected"));
+        }
+
+        let mut depth = 1;
+        while depth > 0 {
+            match self.peek().cloned() {
+                None => return Err(self.error("'}' expected")),
+                Some(DotToken::Symbol('}')) => {
+                    self.position += 1;
+                    depth -= 1;
+                }
+                Some(DotToken::Symbol('{')) => {
+                    self.position += 1;
+                    depth += 1;
+                }
+                Some(DotToken::Symbol(';')) => self.position += 1,
+                Some(DotToken::Id(id)) if id == "subgraph" => {
+                    self.position += 1;
+                    if let Some(DotToken::Id(_)) = self.peek() {
+                        self.position += 1;
+                    }
+                }
+                Some(DotToken::Id(id)) if id == "graph" || id == "node" || id == "edge" => {
+                    self.position += 1;
+                    self.parse_attributes()?;
+                }
+                Some(DotToken::Id(_)) => {
+                    let (first, first_port) = self.parse_node_port()?;
+                    if self.skip_symbol('=') {
+                        // graph attribute
+                        self.expect_id()?;
+                        continue;
+                    }
+
+                    let mut chain = vec![(first, first_port)];
+                    while self.peek() == Some(&DotToken::Edge) {
+                        self.position += 1;
+                        chain.push(self.parse_node_port()?);
+                    }
+                    let attributes = self.parse_attributes()?;
+
+                    if chain.len() == 1 {
+                        let (id, _) = chain.pop().unwrap();
+                        let index = add_node(&mut document, &id);
+                        let ports = (
+                            self.parse_ports(attributes.get("inputs"))?,
+                            self.parse_ports(attributes.get("outputs"))?,
+                        );
+                        let node = &mut document.nodes[index];
+                        if let Some(label) = attributes.get("label").filter(|label| !is_html(label)) {
+                            node.caption = Some(label.clone());
+                            node.kind = label.clone();
+                        }
+                        if let Some(caption) = attributes.get("caption") {
+                            node.caption = Some(caption.clone());
+                        }
+                        if let Some(kind) = attributes.get("kind") {
+                            node.kind = kind.clone();
+                        }
+                        if let Some(location) = attributes.get("pos").and_then(|pos| Self::parse_location(pos)) {
+                            node.location = Some(location);
+                        }
+                        node.inputs.extend(ports.0);
+                        node.outputs.extend(ports.1);
+                    } else {
+                        for pair in chain.windows(2) {
+                            let ((from, output), (to, input)) = (&pair[0], &pair[1]);
+                            add_node(&mut document, from);
+                            add_node(&mut document, to);
+                            let (output, input) = match (output, input) {
+                                (Some(output), Some(input)) => (output, input),
+                                _ => return Err(self.error("edges shall connect ports, ex. a:out -> b:in")),
+                            };
+                            document.edges.push(ImportEdge {
+                                from: from.clone(),
+                                output: output.clone(),
+                                to: to.clone(),
+                                input: input.clone(),
+                            });
+                        }
+                    }
+                }
+                Some(_) => return Err(self.error("statement expected")),
+            }
+        }
+
+        Ok(document)
+    }
+}
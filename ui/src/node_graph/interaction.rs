@@ -0,0 +1,46 @@
+use crate::node_graph::ActionId;
+
+/// The allowed modifications of the graph in an editor. Pan, zoom and selection are always allowed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InteractionMode {
+    /// Everything is allowed.
+    #[default]
+    Full,
+    /// The graph can not be modified, the context menu is disabled and the content of the nodes is not editable.
+    ReadOnly,
+    /// The nodes can be moved, resized and collapsed, but the connections can not be edited.
+    LayoutOnly,
+    /// The connections can be edited, but the nodes can not be moved.
+    WiringOnly,
+}
+
+impl InteractionMode {
+    /// Nodes can be moved, resized and collapsed.
+    pub fn allows_layout(&self) -> bool {
+        matches!(self, InteractionMode::Full | InteractionMode::LayoutOnly)
+    }
+
+    /// Connections can be created and removed.
+    pub fn allows_wiring(&self) -> bool {
+        matches!(self, InteractionMode::Full | InteractionMode::WiringOnly)
+    }
+
+    /// The context menu can be opened.
+    pub fn allows_context_menu(&self) -> bool {
+        !matches!(self, InteractionMode::ReadOnly)
+    }
+
+    /// The content of the nodes and connections is editable.
+    pub fn allows_content_edit(&self) -> bool {
+        !matches!(self, InteractionMode::ReadOnly)
+    }
+
+    /// Return if an action can be executed. Actions that add or remove nodes and the custom actions are allowed only
+    /// in the `Full` mode.
+    pub fn allows_action(&self, action: &ActionId) -> bool {
+        match action {
+            ActionId::Frame | ActionId::SelectAll => true,
+            _ => matches!(self, InteractionMode::Full),
+        }
+    }
+}
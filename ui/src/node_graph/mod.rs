@@ -1,17 +1,56 @@
 mod utils;
 
+mod theme;
+pub use self::theme::*;
 mod port_style;
 pub use self::port_style::*;
+mod preview;
+pub use self::preview::*;
+mod property;
+pub use self::property::*;
 mod port;
 pub use self::port::*;
+mod typed_port;
+pub use self::typed_port::*;
 mod port_visual;
 use self::port_visual::*;
 mod connection;
 pub use self::connection::*;
+mod node_style;
+pub use self::node_style::*;
 mod node;
 pub use self::node::*;
 mod graph;
 pub use self::graph::*;
+mod builder;
+pub use self::builder::*;
+mod template;
+pub use self::template::*;
+mod selection;
+pub use self::selection::*;
+mod diff;
+pub use self::diff::*;
+mod export;
+pub use self::export::*;
+mod import;
+pub use self::import::*;
+mod validation;
+pub use self::validation::*;
+mod evaluation;
+pub use self::evaluation::*;
+mod plan;
+pub use self::plan::*;
+mod codegen;
+pub use self::codegen::*;
+mod debug;
+pub use self::debug::*;
+mod property_panel;
+pub use self::property_panel::*;
+
+mod action;
+pub use self::action::*;
+mod interaction;
+pub use self::interaction::*;
 
 mod zoom_pan;
 use self::zoom_pan::*;
@@ -1,12 +1,16 @@
 use crate::{
     node_graph::{
-        Input, InputId, InputOutputId, Output, OutputId, PortSelection, PortStyle, PortStyles, PortViewState,
-        ZoomPanState,
+        show_properties, utils::draw_port_shape, GraphTheme, HasProperties, Input, InputId, InputOutputId,
+        InteractionMode, NodeBadge, NodeShape, NodeStyle, Output, OutputId, PortSelection, PortStyle, PortStyles,
+        PortViewState, TypedInputId, TypedOutputId, ZoomPanState,
     },
     utils::{FrameWithHeader, Scale},
 };
 use eframe::epaint::Shadow;
-use egui::{pos2, vec2, Area, Frame, Id, Order, Painter, Pos2, Rect, Stroke, Ui, Vec2};
+use egui::{
+    pos2, vec2, Align2, Area, Frame, Id, Order, Painter, PointerButton, Pos2, Rect, Response, Rounding, Sense,
+    TextStyle, Ui, Vec2,
+};
 use shine_core::{
     downcast_rs::{impl_downcast, Downcast},
     slotmap::new_key_type,
@@ -15,38 +19,97 @@ use shine_core::{
 
 new_key_type! { pub struct NodeId; }
 
-pub trait NodeData: 'static + Downcast + Send + Sync {
+/// Size of the simplified node if it was never drawn with details.
+const DEFAULT_LOD_SIZE: Vec2 = vec2(100., 40.);
+
+pub trait NodeData: 'static + Downcast + Send + Sync + HasProperties {
     fn set_location(&mut self, _new_location: Pos2) {}
 
-    fn show(&mut self, _ui: &mut Ui, _inputs: &mut Vec<Input>, _outputs: &mut Vec<Output>) {}
+    /// Show the editor of the data in the node and return if it was changed. By default the
+    /// [properties](HasProperties::properties_mut) of the data are shown.
+    fn show(&mut self, ui: &mut Ui, _inputs: &mut Vec<Input>, _outputs: &mut Vec<Output>) -> bool {
+        let id = ui.id().with("properties");
+        match self.properties_mut() {
+            Some(properties) => show_properties(ui, id, properties),
+            None => false,
+        }
+    }
+
+    /// Create a copy of the data for a duplicated node. Nodes returning `None` can not be duplicated.
+    fn duplicate(&self) -> Option<BoxedNodeData> {
+        None
+    }
+
+    /// Status badges shown on the node.
+    fn badges(&self) -> Vec<NodeBadge> {
+        Vec::new()
+    }
+
+    /// Hash of the content to detect the changes in a [`GraphDiff`](crate::node_graph::GraphDiff).
+    /// The changes of a data returning `None` can not be detected, the diff reports them as
+    /// [unknown](crate::node_graph::NodeChange::Unknown).
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
 }
 impl_downcast!(NodeData);
 
-impl NodeData for () {}
+impl NodeData for () {
+    fn duplicate(&self) -> Option<BoxedNodeData> {
+        Some(smallbox!(()))
+    }
+
+    fn content_hash(&self) -> Option<u64> {
+        Some(0)
+    }
+}
 
-type BoxedNodeData = SmallBox<dyn NodeData, space::S32>;
+pub type BoxedNodeData = SmallBox<dyn NodeData, space::S32>;
 
 pub struct Node {
     id: NodeId,
+    /// The kind of the node used to find the style. By default it is the caption.
+    pub kind: String,
     pub caption: String,
     pub location: Pos2,
     pub inputs: Vec<Input>,
     pub outputs: Vec<Output>,
+    /// Show only the header with the ports stacked on its edges.
+    pub collapsed: bool,
+    /// User defined minimum size of the content in graph (area) units, `None` to fit the content.
+    pub size: Option<Vec2>,
+    /// Show only the ports having some connection.
+    pub hide_unconnected_ports: bool,
+    /// The node can not be moved, resized, collapsed or removed in the editor.
+    pub locked: bool,
     data: BoxedNodeData,
 }
 
 impl Node {
     pub fn new<S: ToString>(caption: S, location: Pos2, inputs: Vec<Input>, outputs: Vec<Output>) -> Self {
+        let caption = caption.to_string();
         Self {
             id: NodeId::default(),
-            caption: caption.to_string(),
+            kind: caption.clone(),
+            caption,
             inputs,
             outputs,
             location,
+            collapsed: false,
+            size: None,
+            hide_unconnected_ports: false,
+            locked: false,
             data: smallbox!(()),
         }
     }
 
+    pub fn with_kind<S: ToString>(self, kind: S) -> Self {
+        Self {
+            kind: kind.to_string(),
+            ..self
+        }
+    }
+
     pub fn with_id(self, node_id: NodeId) -> Self {
         Self { id: node_id, ..self }
     }
@@ -55,6 +118,40 @@ impl Node {
         self.id
     }
 
+    /// Return the id of an input port by its index.
+    pub fn input_id(&self, port_id: usize) -> Option<InputId> {
+        let input = self.inputs.get(port_id)?;
+        Some(InputId::new(self.id, input.port_type_id(), port_id))
+    }
+
+    /// Return the id of an output port by its index.
+    pub fn output_id(&self, port_id: usize) -> Option<OutputId> {
+        let output = self.outputs.get(port_id)?;
+        Some(OutputId::new(self.id, output.port_type_id(), port_id))
+    }
+
+    /// Return the id of the first input port with the given name.
+    pub fn find_input_id(&self, name: &str) -> Option<InputId> {
+        let port_id = self.inputs.iter().position(|input| input.name == name)?;
+        self.input_id(port_id)
+    }
+
+    /// Return the id of the first output port with the given name.
+    pub fn find_output_id(&self, name: &str) -> Option<OutputId> {
+        let port_id = self.outputs.iter().position(|output| output.name == name)?;
+        self.output_id(port_id)
+    }
+
+    /// Return the typed id of an input port, `None` if the port is missing or has a different type.
+    pub fn typed_input_id<T: 'static>(&self, port_id: usize) -> Option<TypedInputId<T>> {
+        TypedInputId::try_from_id(self.input_id(port_id)?)
+    }
+
+    /// Return the typed id of an output port, `None` if the port is missing or has a different type.
+    pub fn typed_output_id<T: 'static>(&self, port_id: usize) -> Option<TypedOutputId<T>> {
+        TypedOutputId::try_from_id(self.output_id(port_id)?)
+    }
+
     pub fn with_data<N: NodeData>(self, data: N) -> Self {
         Self {
             data: smallbox!(data),
@@ -62,6 +159,11 @@ impl Node {
         }
     }
 
+    /// Return if the node can be moved, resized and collapsed in an editor with the given interaction mode.
+    pub fn is_layout_enabled(&self, interaction: InteractionMode) -> bool {
+        interaction.allows_layout() && !self.locked
+    }
+
     pub fn data(&self) -> &dyn NodeData {
         &*self.data
     }
@@ -80,6 +182,34 @@ impl Node {
         data.downcast_mut::<T>().unwrap()
     }
 
+    /// Create a copy of the node without an id, if the data of the node and of all the ports supports duplication.
+    pub fn duplicate(&self) -> Option<Node> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.duplicate())
+            .collect::<Option<Vec<_>>>()?;
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|output| output.duplicate())
+            .collect::<Option<Vec<_>>>()?;
+        let data = self.data.duplicate()?;
+        Some(Node {
+            id: NodeId::default(),
+            kind: self.kind.clone(),
+            caption: self.caption.clone(),
+            location: self.location,
+            inputs,
+            outputs,
+            collapsed: self.collapsed,
+            size: self.size,
+            hide_unconnected_ports: self.hide_unconnected_ports,
+            locked: self.locked,
+            data,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn draw_port(
         &self,
@@ -90,66 +220,266 @@ impl Node {
         port_id: InputOutputId,
         port_pos: Pos2,
         pointer_pos: Option<Pos2>,
+        interactive: bool,
         node_rect: &mut Rect,
     ) {
         port_visual.set_screen_pos(port_id, port_pos);
 
         let r = style.port_size * zoom_pan.zoom;
         let dist = pointer_pos.map(|p| port_pos.distance_sq(p)).unwrap_or(f32::MAX);
-        let is_hovered = dist < r * r * 1.3 && port_visual.is_ports_enabled() && !port_visual.has_hovered();
+        let is_hovered =
+            interactive && dist < r * r * 1.3 && port_visual.is_ports_enabled() && !port_visual.has_hovered();
 
         if is_hovered {
             port_visual.set_hovered(port_id);
         }
 
+        let theme = &zoom_pan.theme;
         let color = match port_visual.get_selection(port_id) {
             PortSelection::Normal => {
                 if is_hovered {
-                    style.hover_color
+                    style.get_hover_color(theme)
                 } else {
-                    style.color
+                    style.get_color(theme)
                 }
             }
-            PortSelection::Error => style.error_color,
-            PortSelection::Hover => style.hover_color,
+            PortSelection::Error => style.get_error_color(theme),
+            PortSelection::Hover => style.get_hover_color(theme),
         };
 
-        painter.circle(port_pos, r, color, Stroke::NONE);
+        let connection_count = port_visual.get_connection_count(port_id);
+        let hollow = style.hollow_unconnected && connection_count == 0;
+        draw_port_shape(painter, style.shape, port_pos, r, color, hollow);
         *node_rect = node_rect.union(Rect::from_center_size(port_pos, vec2(r * 2., r * 2.)));
+
+        if style.show_connection_count && connection_count > 1 {
+            let (pos, align) = match &port_id {
+                InputOutputId::Input(_) => (port_pos - vec2(r * 1.5, 0.), Align2::RIGHT_CENTER),
+                InputOutputId::Output(_) => (port_pos + vec2(r * 1.5, 0.), Align2::LEFT_CENTER),
+            };
+            let font_id = TextStyle::Small.resolve(&painter.ctx().style());
+            let text_rect = painter.text(pos, align, connection_count.to_string(), font_id, color);
+            *node_rect = node_rect.union(text_rect);
+        }
     }
 
+    /// Draw the ports of a compact node on the edges of the frame.
+    /// When `interactive` is false (collapsed node) the ports are merged at the center of the edge, otherwise
+    /// (pill node) they are distributed along the edge and can be connected.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_compact_ports(
+        &self,
+        ui: &mut Ui,
+        zoom_pan: &ZoomPanState,
+        port_visual: &mut PortViewState,
+        port_styles: &PortStyles,
+        frame_rect: Rect,
+        interactive: bool,
+        node_rect: &mut Rect,
+    ) {
+        let node_id = self.id;
+        let inputs: Vec<InputOutputId> = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(port_id, input)| InputOutputId::from(InputId::new(node_id, input.port_type_id(), port_id)))
+            .filter(|port_id| is_port_visible(self.hide_unconnected_ports, port_visual, *port_id))
+            .collect();
+        let outputs: Vec<InputOutputId> = self
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(port_id, output)| InputOutputId::from(OutputId::new(node_id, output.port_type_id(), port_id)))
+            .filter(|port_id| is_port_visible(self.hide_unconnected_ports, port_visual, *port_id))
+            .collect();
+
+        let pointer_pos = if interactive {
+            ui.ctx().pointer_latest_pos()
+        } else {
+            None
+        };
+        for (ports, x) in [(inputs, frame_rect.left()), (outputs, frame_rect.right())] {
+            let count = ports.len();
+            for (i, port_id) in ports.into_iter().enumerate() {
+                if let Some(style) = port_styles.find(port_id.port_type_id()) {
+                    let y = if interactive {
+                        frame_rect.top() + frame_rect.height() * (i + 1) as f32 / (count + 1) as f32
+                    } else {
+                        frame_rect.center().y
+                    };
+                    self.draw_port(
+                        ui.painter(),
+                        zoom_pan,
+                        port_visual,
+                        style,
+                        port_id,
+                        pos2(x, y),
+                        pointer_pos,
+                        interactive,
+                        node_rect,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Handle the resize grip at the bottom right corner of the frame.
+    fn resize_grip(&mut self, ui: &mut Ui, zoom_pan: &ZoomPanState, frame_rect: Rect, content_size: Vec2) {
+        let grip_size = ui.spacing().icon_width;
+        let grip_rect = Rect::from_min_max(frame_rect.max - Vec2::splat(grip_size), frame_rect.max);
+        let response = ui.interact(grip_rect, ui.id().with("resize"), Sense::click_and_drag());
+
+        if response.double_clicked() {
+            self.size = None;
+        } else if response.dragged() {
+            let size = self.size.unwrap_or_else(|| zoom_pan.vec2_screen_to_area(content_size));
+            let new_size = size + zoom_pan.vec2_screen_to_area(response.drag_delta());
+            self.size = Some(new_size.max(Vec2::splat(grip_size)));
+        }
+
+        let stroke = ui.style().interact(&response).fg_stroke;
+        let painter = ui.painter();
+        let corner = frame_rect.max - Vec2::splat(stroke.width + 2.);
+        for i in 1..=3 {
+            let offset = grip_size * i as f32 / 4.;
+            painter.line_segment([corner - offset * Vec2::X, corner - offset * Vec2::Y], stroke);
+        }
+    }
+
+    /// Draw the status badges on the top right corner of the frame.
+    fn draw_badges(&self, ui: &mut Ui, theme: &GraphTheme, frame_rect: Rect, node_rect: &mut Rect) {
+        let badges = self.data.badges();
+        if badges.is_empty() {
+            return;
+        }
+
+        let font_id = TextStyle::Small.resolve(ui.style());
+        let r = font_id.size * 0.7;
+        let painter = ui.painter();
+        let mut center = frame_rect.right_top();
+        for badge in badges {
+            painter.circle(center, r, badge.color(theme), ui.visuals().window_stroke());
+            painter.text(
+                center,
+                Align2::CENTER_CENTER,
+                badge.symbol(),
+                font_id.clone(),
+                ui.visuals().window_fill(),
+            );
+            *node_rect = node_rect.union(Rect::from_center_size(center, Vec2::splat(r * 2.)));
+            center.x -= r * 2.5;
+        }
+    }
+
+    /// Draw a simplified box of the node without any content when zoomed far out.
+    #[allow(clippy::too_many_arguments)]
+    fn show_simplified(
+        &self,
+        ui: &mut Ui,
+        zoom_pan: &ZoomPanState,
+        port_visual: &mut PortViewState,
+        port_styles: &PortStyles,
+        node_style: &NodeStyle,
+        is_selected: bool,
+        size: Vec2,
+    ) {
+        let rect = Rect::from_min_size(ui.cursor().min, zoom_pan.vec2_area_to_screen(size));
+        let visuals = ui.visuals();
+        let fill = node_style.header_color.unwrap_or(visuals.window_fill);
+        let stroke = if is_selected {
+            visuals.selection.stroke
+        } else {
+            visuals.window_stroke
+        };
+        let rounding = match node_style.shape {
+            NodeShape::Box => visuals.window_rounding,
+            NodeShape::Pill => Rounding::same(rect.height() / 2.),
+        };
+        ui.painter().rect(rect, rounding, fill, stroke);
+
+        let mut node_rect = rect;
+        self.draw_compact_ports(ui, zoom_pan, port_visual, port_styles, rect, false, &mut node_rect);
+        ui.expand_to_include_rect(node_rect);
+    }
+
+    /// Show the node, the response is [changed](Response::changed) if the content (data, ports) or the layout
+    /// (location, size, collapse) of the node was changed.
     pub(in crate::node_graph) fn show(
         &mut self,
         ui: &mut Ui,
         zoom_pan: &ZoomPanState,
         port_visual: &mut PortViewState,
         port_styles: &PortStyles,
-    ) -> NodeState {
+        node_style: &NodeStyle,
+        is_selected: bool,
+    ) -> (NodeState, Response) {
         let node_id = self.id;
         let id = zoom_pan.child_id(node_id);
 
         let mut node_state = NodeState::load(ui, id).unwrap_or_else(NodeState::new);
         let screen_location = zoom_pan.pos2_area_to_screen(self.location);
+        let interaction = port_visual.interaction();
+        let is_layout_enabled = port_visual.is_nodes_enabled() && self.is_layout_enabled(interaction);
 
-        let response = Area::new(id)
+        let layout = (self.location, self.size, self.collapsed);
+        let mut content_changed = false;
+        let mut response = Area::new(id)
             .order(Order::Middle)
             .current_pos(screen_location)
             .enabled(port_visual.is_nodes_enabled())
-            .movable(port_visual.is_nodes_enabled())
+            .movable(is_layout_enabled)
             .drag_bounds(Rect::EVERYTHING)
             .show(ui.ctx(), |ui| {
                 ui.set_clip_rect(zoom_pan.screen_rect);
                 ui.set_max_size(Vec2::INFINITY);
 
+                if zoom_pan.is_lod() {
+                    let size = node_state.size.or(self.size).unwrap_or(DEFAULT_LOD_SIZE);
+                    self.show_simplified(ui, zoom_pan, port_visual, port_styles, node_style, is_selected, size);
+                    return;
+                }
+
                 let mut node_rect = Rect::NOTHING;
                 let margin = ui.style().spacing.window_margin.scaled(2.);
+                let mut frame = Frame::window(ui.style()).shadow(Shadow::default()).inner_margin(margin);
+                if is_selected {
+                    frame = frame.stroke(ui.visuals().selection.stroke);
+                }
+                let is_pill = node_style.shape == NodeShape::Pill;
+                if is_pill {
+                    // rounding is clamped to the half of the size
+                    frame = frame.rounding(Rounding::same(f32::MAX));
+                }
+                let is_compact = is_pill || self.collapsed;
+
+                let title = match &node_style.icon {
+                    Some(icon) => format!("{} {}", icon, self.caption),
+                    None => self.caption.clone(),
+                };
+                let mut frame_with_header = FrameWithHeader::new(title).frame(frame);
+                frame_with_header = if is_pill {
+                    frame_with_header.header_only()
+                } else {
+                    frame_with_header.collapsible(self.collapsed)
+                };
+                if let Some(header_color) = node_style.header_color {
+                    frame_with_header = frame_with_header.header_color(header_color);
+                }
 
-                FrameWithHeader::new(&self.caption)
-                    .frame(Frame::window(ui.style()).shadow(Shadow::default()).inner_margin(margin))
-                    .show(ui, |ui| {
-                        self.data.show(ui, &mut self.inputs, &mut self.outputs);
+                let mut content_size = Vec2::ZERO;
+                let hide_unconnected_ports = self.hide_unconnected_ports;
+                let mut port_infos = Vec::<(InputOutputId, f32)>::new();
+                let mut port_rect = Rect::NOTHING;
+
+                let toggle_clicked = frame_with_header.show(ui, |ui| {
+                    if let Some(size) = self.size {
+                        ui.set_min_size(size * zoom_pan.zoom);
+                    }
+
+                    // the data of the node and the ports are editable only if the content edit is allowed
+                    ui.add_enabled_ui(interaction.allows_content_edit(), |ui| {
+                        content_changed |= self.data.show(ui, &mut self.inputs, &mut self.outputs);
 
-                        let mut port_infos = Vec::<(InputOutputId, f32)>::new();
                         let port_top = ui.min_rect().bottom();
                         ui.horizontal(|ui| {
                             //inputs
@@ -157,15 +487,18 @@ impl Node {
                                 let mut height_before = port_top;
                                 for (port_id, input) in self.inputs.iter_mut().enumerate() {
                                     let type_id = input.port_type_id();
+                                    let id = InputId::new(node_id, type_id, port_id);
+                                    if !is_port_visible(hide_unconnected_ports, port_visual, id.into()) {
+                                        continue;
+                                    }
+                                    // the unstyled ports are not shown, they are reported by the validation
                                     if let Some(style) = port_styles.find(type_id) {
-                                        input.show(ui, port_id, style);
+                                        let is_connected = port_visual.get_connection_count(id.into()) > 0;
+                                        content_changed |= input.show(ui, port_id, style, is_connected);
                                         let height_after = ui.min_rect().bottom();
                                         let y = (height_after + height_before) / 2.;
                                         height_before = height_after;
-                                        let id = InputId::new(node_id, type_id, port_id);
                                         port_infos.push((id.into(), y));
-                                    } else {
-                                        log::warn!("Skipping input port, style for {:?} not found", type_id);
                                     }
                                 }
                             });
@@ -174,64 +507,104 @@ impl Node {
                                 let mut height_before = port_top;
                                 for (port_id, output) in self.outputs.iter_mut().enumerate() {
                                     let type_id = output.port_type_id();
+                                    let id = OutputId::new(node_id, type_id, port_id);
+                                    if !is_port_visible(hide_unconnected_ports, port_visual, id.into()) {
+                                        continue;
+                                    }
                                     if let Some(style) = port_styles.find(type_id) {
-                                        output.show(ui, port_id, style);
+                                        content_changed |= output.show(ui, port_id, style);
                                         let height_after = ui.min_rect().bottom();
                                         let y = (height_after + height_before) / 2.;
                                         height_before = height_after;
-                                        let id = OutputId::new(node_id, type_id, port_id);
                                         port_infos.push((id.into(), y));
-                                    } else {
-                                        log::warn!("Skipping output port, style for {:?} not found", type_id);
                                     }
                                 }
                             });
                         });
+                    });
 
-                        let port_rect = Rect::from_min_max(
-                            ui.min_rect().min - margin.left_top(),
-                            ui.min_rect().max + margin.right_bottom(),
+                    content_size = ui.min_rect().size();
+                    port_rect = Rect::from_min_max(
+                        ui.min_rect().min - margin.left_top(),
+                        ui.min_rect().max + margin.right_bottom(),
+                    );
+                });
+
+                let frame_rect = ui.min_rect();
+
+                if is_compact {
+                    node_rect = node_rect.union(frame_rect);
+                    self.draw_compact_ports(
+                        ui,
+                        zoom_pan,
+                        port_visual,
+                        port_styles,
+                        frame_rect,
+                        is_pill,
+                        &mut node_rect,
+                    );
+                } else {
+                    node_rect = node_rect.union(port_rect);
+
+                    // render port after the frame on a background layer
+                    let painter = ui.painter();
+                    let pointer_pos = ui.ctx().pointer_latest_pos();
+                    for (port_id, y) in port_infos {
+                        let port_pos = match &port_id {
+                            InputOutputId::Input(_) => pos2(port_rect.left(), y),
+                            InputOutputId::Output(_) => pos2(port_rect.right(), y),
+                        };
+                        let style = port_styles
+                            .find(port_id.port_type_id())
+                            .expect("Port shall be drown only with known types");
+                        self.draw_port(
+                            painter,
+                            zoom_pan,
+                            port_visual,
+                            style,
+                            port_id,
+                            port_pos,
+                            pointer_pos,
+                            true,
+                            &mut node_rect,
                         );
-                        node_rect = node_rect.union(port_rect);
-
-                        // render port after the frame on a background layer
-                        let painter = ui.painter();
-                        let pointer_pos = ui.ctx().pointer_latest_pos();
-                        for (port_id, y) in port_infos {
-                            let port_pos = match &port_id {
-                                InputOutputId::Input(_) => pos2(port_rect.left(), y),
-                                InputOutputId::Output(_) => pos2(port_rect.right(), y),
-                            };
-                            let style = port_styles
-                                .find(port_id.port_type_id())
-                                .expect("Port shall be drown only with known types");
-                            self.draw_port(
-                                painter,
-                                zoom_pan,
-                                port_visual,
-                                style,
-                                port_id,
-                                port_pos,
-                                pointer_pos,
-                                &mut node_rect,
-                            );
-                        }
-                    });
+                    }
+
+                    if is_layout_enabled {
+                        self.resize_grip(ui, zoom_pan, frame_rect, content_size);
+                    }
+                }
+
+                self.draw_badges(ui, &zoom_pan.theme, frame_rect, &mut node_rect);
+                node_state.size = Some(zoom_pan.vec2_screen_to_area(frame_rect.size()));
+
+                if toggle_clicked && is_layout_enabled {
+                    self.collapsed = !self.collapsed;
+                }
 
                 // increment the node to include the ports
                 /*ui.painter().rect(
                     node_rect,
                     0.,
                     egui::Color32::TRANSPARENT,
-                    egui::Stroke::new(1., egui::Color32::YELLOW),
+                    egui::Stroke::new(1., zoom_pan.theme.debug_color),
                 );*/
                 ui.expand_to_include_rect(node_rect);
             })
             .response;
 
+        if response.hovered() {
+            port_visual.set_hovered_node(node_id);
+        }
+        port_visual.set_node_rect(node_id, response.rect);
+
         node_state.drag_started = false;
         if let Some(pos) = ui.ctx().pointer_latest_pos() {
-            if response.drag_started() && zoom_pan.screen_rect.contains(pos) {
+            // middle button is reserved for panning
+            if response.drag_started()
+                && response.dragged_by(PointerButton::Primary)
+                && zoom_pan.screen_rect.contains(pos)
+            {
                 node_state.drag_started = true;
                 node_state.dragged = true;
             }
@@ -240,23 +613,33 @@ impl Node {
             node_state.dragged = false;
         }
 
-        if node_state.dragged && response.drag_delta() != Vec2::ZERO {
+        if is_layout_enabled && node_state.dragged && response.drag_delta() != Vec2::ZERO {
             let new_location = self.location + zoom_pan.vec2_screen_to_area(response.drag_delta());
             self.location = new_location;
             self.data_mut().set_location(new_location);
         }
 
+        if content_changed || layout != (self.location, self.size, self.collapsed) {
+            response.mark_changed();
+        }
+
         node_state.clone().store(ui, id);
-        node_state
+        (node_state, response)
     }
 }
 
+fn is_port_visible(hide_unconnected_ports: bool, port_visual: &PortViewState, port_id: InputOutputId) -> bool {
+    !hide_unconnected_ports || port_visual.get_connection_count(port_id) > 0
+}
+
 #[derive(Clone)]
 pub(in crate::node_graph) struct NodeState {
     /// node drag was started in this frame
     pub drag_started: bool,
     /// this node is dragged
     pub dragged: bool,
+    /// size of the frame in graph (area) units when it was last drawn with details
+    pub size: Option<Vec2>,
 }
 
 impl NodeState {
@@ -272,6 +655,7 @@ impl NodeState {
         Self {
             drag_started: false,
             dragged: false,
+            size: None,
         }
     }
 }
@@ -0,0 +1,117 @@
+use crate::node_graph::GraphTheme;
+use egui::Color32;
+use std::collections::HashMap;
+
+/// The outline of a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeShape {
+    /// A frame with header, body and the ports.
+    Box,
+    /// A compact rounded node without a body, the ports are stacked on the sides.
+    Pill,
+}
+
+/// Status indicator shown on the corner of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeBadge {
+    Error,
+    Warning,
+    Running,
+}
+
+impl NodeBadge {
+    /// Return the color of the badge from the theme, a running node is marked by the hover color as the running
+    /// nodes of an evaluation.
+    pub fn color(&self, theme: &GraphTheme) -> Color32 {
+        match self {
+            NodeBadge::Error => theme.error_color,
+            NodeBadge::Warning => theme.warning_color,
+            NodeBadge::Running => theme.hover_color,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            NodeBadge::Error => "!",
+            NodeBadge::Warning => "?",
+            NodeBadge::Running => "…",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NodeStyle {
+    pub header_color: Option<Color32>,
+    /// Text (usually an emoji) shown before the caption.
+    pub icon: Option<String>,
+    pub shape: NodeShape,
+}
+
+impl Default for NodeStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeStyle {
+    pub fn new() -> Self {
+        NodeStyle {
+            header_color: None,
+            icon: None,
+            shape: NodeShape::Box,
+        }
+    }
+
+    pub fn with_header_color(self, header_color: Color32) -> Self {
+        Self {
+            header_color: Some(header_color),
+            ..self
+        }
+    }
+
+    pub fn with_icon<S: ToString>(self, icon: S) -> Self {
+        Self {
+            icon: Some(icon.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_shape(self, shape: NodeShape) -> Self {
+        Self { shape, ..self }
+    }
+}
+
+/// Style of the nodes by the kind of the node.
+#[derive(Default)]
+pub struct NodeStyles {
+    default_style: NodeStyle,
+    styles: HashMap<String, NodeStyle>,
+}
+
+impl NodeStyles {
+    /// Set the style used for the nodes without a registered style.
+    pub fn set_default(&mut self, style: NodeStyle) {
+        self.default_style = style;
+    }
+
+    pub fn set<S: ToString>(&mut self, kind: S, style: NodeStyle) {
+        self.styles.insert(kind.to_string(), style);
+    }
+
+    pub fn remove(&mut self, kind: &str) {
+        self.styles.remove(kind);
+    }
+
+    pub fn get(&self, kind: &str) -> Option<&NodeStyle> {
+        self.styles.get(kind)
+    }
+
+    pub fn get_mut(&mut self, kind: &str) -> Option<&mut NodeStyle> {
+        self.styles.get_mut(kind)
+    }
+
+    /// Find the style of a node kind falling back to the default style.
+    pub fn find(&self, kind: &str) -> &NodeStyle {
+        self.styles.get(kind).unwrap_or(&self.default_style)
+    }
+}
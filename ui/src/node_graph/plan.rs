@@ -0,0 +1,353 @@
+use crate::node_graph::{
+    utils::topological_order, Graph, InputId, Node, NodeId, OutputId, PortValue, TypedOutputId, ValidationReport,
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    ops::Range,
+    sync::Arc,
+};
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum CompileError {
+    #[error("Graph has validation errors")]
+    Invalid(ValidationReport),
+}
+
+#[derive(Debug, ThisError)]
+pub enum ExecutionError {
+    #[error("Node {node_id:?} has failed: {message}")]
+    Failed { node_id: NodeId, message: String },
+}
+
+/// The computation of a node in an [`ExecutionPlan`]. Unlike a [`NodeTask`](crate::node_graph::NodeTask) it is
+/// created once by the compilation and it is run on each execution of the plan.
+pub trait NodeKernel: 'static + Send + Sync {
+    fn execute(&self, context: &mut KernelContext<'_>) -> Result<(), String>;
+}
+
+impl<F> NodeKernel for F
+where
+    F: 'static + Send + Sync + Fn(&mut KernelContext<'_>) -> Result<(), String>,
+{
+    fn execute(&self, context: &mut KernelContext<'_>) -> Result<(), String> {
+        (self)(context)
+    }
+}
+
+pub trait KernelFactory {
+    /// Create the kernel of a node, `None` if the node has nothing to compute.
+    fn create_kernel(&self, node: &Node) -> Option<Box<dyn NodeKernel>>;
+}
+
+impl<F> KernelFactory for F
+where
+    F: Fn(&Node) -> Option<Box<dyn NodeKernel>>,
+{
+    fn create_kernel(&self, node: &Node) -> Option<Box<dyn NodeKernel>> {
+        (self)(node)
+    }
+}
+
+struct InputBinding {
+    /// The buffers of the connected outputs in the sources of the plan.
+    sources: Range<usize>,
+    /// The default value of the port in the constants of the plan.
+    default: Option<usize>,
+}
+
+struct OutputBinding {
+    port_type_id: TypeId,
+    /// The buffer of the value, `None` if the value is not used.
+    buffer: Option<usize>,
+}
+
+struct Instruction {
+    node_id: NodeId,
+    kernel: Box<dyn NodeKernel>,
+    inputs: Range<usize>,
+    outputs: Range<usize>,
+    /// The buffers whose last reader is this instruction, they are cleared after the kernel has run.
+    released: Range<usize>,
+}
+
+/// An immutable, linear form of a graph to execute it repeatedly, ex. on each frame.
+///
+/// The compilation resolves the connections into buffer indices and assigns the same buffer to the values whose
+/// lifetimes don't overlap, thus the execution touches neither the graph nor any map. The plan does not follow the
+/// changes of the graph, use [`is_outdated`](Self::is_outdated) to decide when it has to be compiled again.
+pub struct ExecutionPlan {
+    revision: u64,
+    instructions: Vec<Instruction>,
+    inputs: Vec<InputBinding>,
+    sources: Vec<usize>,
+    outputs: Vec<OutputBinding>,
+    released: Vec<usize>,
+    constants: Vec<PortValue>,
+    buffer_count: usize,
+    retained: HashMap<OutputId, usize>,
+}
+
+impl ExecutionPlan {
+    /// Compile a graph without validation errors. The nodes are ordered topologically, the nodes without a kernel
+    /// are left out and their outputs have no value.
+    ///
+    /// The values of the `retained` outputs are kept after the execution, see [`value`](Self::value), the other
+    /// values are dropped as soon as their last reader has run.
+    pub fn compile(
+        graph: &Graph,
+        factory: &dyn KernelFactory,
+        retained: &[OutputId],
+    ) -> Result<ExecutionPlan, CompileError> {
+        let report = graph.validate();
+        if report.has_errors() {
+            return Err(CompileError::Invalid(report));
+        }
+
+        let mut kernels: HashMap<NodeId, Box<dyn NodeKernel>> = graph
+            .nodes()
+            .filter_map(|node| Some((node.id(), factory.create_kernel(node)?)))
+            .collect();
+
+        let mut connections = HashMap::<InputId, Vec<OutputId>>::new();
+        for connection in graph.connections() {
+            connections
+                .entry(connection.input_id())
+                .or_default()
+                .push(connection.output_id());
+        }
+        let order: Vec<NodeId> = topological_order(graph)
+            .into_iter()
+            .filter(|node_id| kernels.contains_key(node_id))
+            .collect();
+        let index: HashMap<NodeId, usize> = order.iter().enumerate().map(|(i, node_id)| (*node_id, i)).collect();
+
+        // the index of the last instruction reading an output
+        let mut last_use = HashMap::<OutputId, usize>::new();
+        for (input_id, sources) in &connections {
+            if let Some(reader) = index.get(&input_id.node_id()) {
+                for output_id in sources {
+                    let last = last_use.entry(*output_id).or_insert(*reader);
+                    *last = (*last).max(*reader);
+                }
+            }
+        }
+        for output_id in retained {
+            last_use.insert(*output_id, usize::MAX);
+        }
+
+        let mut plan = ExecutionPlan {
+            revision: graph.revision(),
+            instructions: Vec::with_capacity(order.len()),
+            inputs: Vec::new(),
+            sources: Vec::new(),
+            outputs: Vec::new(),
+            released: Vec::new(),
+            constants: Vec::new(),
+            buffer_count: 0,
+            retained: HashMap::new(),
+        };
+        let mut buffers = HashMap::<OutputId, usize>::new();
+        let mut free_buffers = Vec::new();
+        let mut releases = HashMap::<usize, Vec<usize>>::new();
+        for (i, node_id) in order.into_iter().enumerate() {
+            let node = graph.node(node_id).unwrap();
+
+            let inputs_start = plan.inputs.len();
+            for (port_id, input) in node.inputs.iter().enumerate() {
+                let sources_start = plan.sources.len();
+                let sources = node.input_id(port_id).and_then(|input_id| connections.get(&input_id));
+                plan.sources.extend(
+                    sources
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|output_id| buffers.get(output_id).cloned()),
+                );
+                let default = input.default_value().map(|value| {
+                    plan.constants.push(value.clone());
+                    plan.constants.len() - 1
+                });
+                plan.inputs.push(InputBinding {
+                    sources: sources_start..plan.sources.len(),
+                    default,
+                });
+            }
+
+            // the outputs are allocated before the inputs are released, thus a node never overwrites its inputs
+            let outputs_start = plan.outputs.len();
+            for (port_id, output) in node.outputs.iter().enumerate() {
+                let output_id = node.output_id(port_id);
+                let buffer = output_id.and_then(|output_id| {
+                    let last = *last_use.get(&output_id)?;
+                    let buffer = free_buffers.pop().unwrap_or_else(|| {
+                        plan.buffer_count += 1;
+                        plan.buffer_count - 1
+                    });
+                    buffers.insert(output_id, buffer);
+                    if last == usize::MAX {
+                        plan.retained.insert(output_id, buffer);
+                    } else {
+                        releases.entry(last).or_default().push(buffer);
+                    }
+                    Some(buffer)
+                });
+                plan.outputs.push(OutputBinding {
+                    port_type_id: output.port_type_id(),
+                    buffer,
+                });
+            }
+
+            let released_start = plan.released.len();
+            if let Some(released) = releases.remove(&i) {
+                plan.released.extend_from_slice(&released);
+                free_buffers.extend(released);
+            }
+
+            plan.instructions.push(Instruction {
+                node_id,
+                kernel: kernels.remove(&node_id).unwrap(),
+                inputs: inputs_start..plan.inputs.len(),
+                outputs: outputs_start..plan.outputs.len(),
+                released: released_start..plan.released.len(),
+            });
+        }
+
+        Ok(plan)
+    }
+
+    /// The [revision](Graph::revision) of the compiled graph.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Return if the graph has changed since the compilation.
+    pub fn is_outdated(&self, graph: &Graph) -> bool {
+        self.revision != graph.revision()
+    }
+
+    /// The number of the nodes to execute.
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// The nodes in the order of the execution.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.instructions.iter().map(|instruction| instruction.node_id)
+    }
+
+    /// The number of the buffers holding the values during the execution.
+    pub fn buffer_count(&self) -> usize {
+        self.buffer_count
+    }
+
+    /// Run the kernels in order. The execution stops at the first failing kernel.
+    pub fn execute(&self, state: &mut ExecutionState) -> Result<(), ExecutionError> {
+        state.buffers.clear();
+        state.buffers.resize(self.buffer_count, None);
+
+        for instruction in &self.instructions {
+            let mut context = KernelContext {
+                node_id: instruction.node_id,
+                inputs: &self.inputs[instruction.inputs.clone()],
+                sources: &self.sources,
+                constants: &self.constants,
+                outputs: &self.outputs[instruction.outputs.clone()],
+                buffers: &mut state.buffers,
+            };
+            instruction
+                .kernel
+                .execute(&mut context)
+                .map_err(|message| ExecutionError::Failed {
+                    node_id: instruction.node_id,
+                    message,
+                })?;
+            for buffer in &self.released[instruction.released.clone()] {
+                state.buffers[*buffer] = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the value of a retained output computed by the last execution.
+    pub fn value<'s>(&self, state: &'s ExecutionState, output_id: OutputId) -> Option<&'s PortValue> {
+        let buffer = *self.retained.get(&output_id)?;
+        state.buffers.get(buffer)?.as_ref()
+    }
+
+    pub fn output_value<'s, T: Any>(&self, state: &'s ExecutionState, output_id: TypedOutputId<T>) -> Option<&'s T> {
+        self.value(state, output_id.id())?.downcast_ref::<T>()
+    }
+}
+
+/// The buffers of the executions of an [`ExecutionPlan`], they are reused by the consecutive executions.
+#[derive(Default)]
+pub struct ExecutionState {
+    buffers: Vec<Option<PortValue>>,
+}
+
+/// The inputs and outputs of a running [`NodeKernel`].
+pub struct KernelContext<'a> {
+    node_id: NodeId,
+    inputs: &'a [InputBinding],
+    sources: &'a [usize],
+    constants: &'a [PortValue],
+    outputs: &'a [OutputBinding],
+    buffers: &'a mut [Option<PortValue>],
+}
+
+impl KernelContext<'_> {
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Return the value of an input port. It is the value of the first connection having a value, or the
+    /// [default](crate::node_graph::Input::with_default) of the port.
+    pub fn input<T: Any>(&self, port_id: usize) -> Option<&T> {
+        let input = self.inputs.get(port_id)?;
+        self.sources[input.sources.clone()]
+            .iter()
+            .find_map(|buffer| self.buffers[*buffer].as_ref())
+            .or_else(|| input.default.map(|constant| &self.constants[constant]))?
+            .downcast_ref::<T>()
+    }
+
+    /// Return the values of all the connections of an input port.
+    pub fn inputs<T: Any>(&self, port_id: usize) -> impl Iterator<Item = &T> {
+        self.inputs
+            .get(port_id)
+            .into_iter()
+            .flat_map(|input| self.sources[input.sources.clone()].iter())
+            .filter_map(|buffer| self.buffers[*buffer].as_ref()?.downcast_ref::<T>())
+    }
+
+    /// Return if the value of an output is read by a node or it is retained by the plan. The kernels may skip the
+    /// computation of the unused outputs.
+    pub fn is_output_used(&self, port_id: usize) -> bool {
+        self.outputs
+            .get(port_id)
+            .map(|output| output.buffer.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Set the value of an output port, the value of an unused output is dropped.
+    pub fn set_output<T: Any + Send + Sync>(&mut self, port_id: usize, value: T) {
+        match self.outputs.get(port_id) {
+            Some(output) if output.port_type_id == TypeId::of::<T>() => {
+                if let Some(buffer) = output.buffer {
+                    self.buffers[buffer] = Some(Arc::new(value));
+                }
+            }
+            _ => log::warn!(
+                "Output {} of {:?} has a different type or it is missing",
+                port_id,
+                self.node_id
+            ),
+        }
+    }
+}
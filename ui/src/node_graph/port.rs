@@ -1,29 +1,49 @@
-use crate::node_graph::{NodeId, PortStyle};
+use crate::node_graph::{Graph, HasProperties, NodeId, PortStyle};
 use egui::Ui;
 use shine_core::{
     downcast_rs::{impl_downcast, Downcast},
     smallbox::{smallbox, space, SmallBox},
 };
-use std::any::{Any, TypeId};
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
 
 /// Some dummy type for "null" input and output ids.
 struct Void;
 
-pub trait InputPortData: 'static + Downcast + Send + Sync {
-    fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle);
+pub trait InputPortData: 'static + Downcast + Send + Sync + HasProperties {
+    /// Show the inline editor of the port data, it is shown only when the port is not connected. It is independent
+    /// of the default value of the port, that is edited by the [`PortStyle::with_editor`]. Return if the data was
+    /// changed.
+    fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle) -> bool;
+
+    /// Create a copy of the data for a duplicated port. Ports returning `None` can not be duplicated.
+    fn duplicate(&self) -> Option<BoxedInputPortData> {
+        None
+    }
 }
 impl_downcast!(InputPortData);
 
 impl InputPortData for () {
-    fn show(&mut self, _ui: &mut Ui, _port_id: usize, _style: &PortStyle) {}
+    fn show(&mut self, _ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        false
+    }
+
+    fn duplicate(&self) -> Option<BoxedInputPortData> {
+        Some(smallbox!(()))
+    }
 }
 
-type BoxedInputPortData = SmallBox<dyn InputPortData, space::S2>;
+pub type BoxedInputPortData = SmallBox<dyn InputPortData, space::S2>;
 
 /// Input port
 pub struct Input {
     pub name: String,
     port_type_id: TypeId,
+    min_connections: usize,
+    max_connections: Option<usize>,
+    default_value: Option<Arc<dyn Any + Send + Sync>>,
     data: BoxedInputPortData,
 }
 
@@ -32,6 +52,21 @@ impl Input {
         Self {
             name: name.to_string(),
             port_type_id: TypeId::of::<T>(),
+            min_connections: 0,
+            max_connections: None,
+            default_value: None,
+            data: smallbox!(()),
+        }
+    }
+
+    /// Create a port of a type known only at runtime, ex. resolved from the name of a [`PortStyle`].
+    pub fn from_type_id(name: impl ToString, port_type_id: TypeId) -> Self {
+        Self {
+            name: name.to_string(),
+            port_type_id,
+            min_connections: 0,
+            max_connections: None,
+            default_value: None,
             data: smallbox!(()),
         }
     }
@@ -46,6 +81,65 @@ impl Input {
         }
     }
 
+    /// Mark the input as required, the validation reports an error if it has no connection.
+    pub fn with_required(self, required: bool) -> Self {
+        Self {
+            min_connections: required as usize,
+            ..self
+        }
+    }
+
+    /// Set the allowed number of connections. Connections above the maximum are rejected by
+    /// [`Graph::try_create_connection`], the validation reports an error below the minimum.
+    pub fn with_connection_limits(self, min_connections: usize, max_connections: Option<usize>) -> Self {
+        Self {
+            min_connections,
+            max_connections,
+            ..self
+        }
+    }
+
+    /// Set the value used by the host when the input is not connected. It is edited inline by the
+    /// [editor](PortStyle::with_editor) of the port type.
+    /// # Panics
+    /// This function will panic if the type of the value differs from the type of the port.
+    pub fn with_default<T: Any + Send + Sync>(self, value: T) -> Self {
+        assert!(
+            TypeId::of::<T>() == self.port_type_id,
+            "Default value of input {} has a different type than the port",
+            self.name
+        );
+        Self {
+            default_value: Some(Arc::new(value)),
+            ..self
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.min_connections > 0
+    }
+
+    pub fn min_connections(&self) -> usize {
+        self.min_connections
+    }
+
+    pub fn max_connections(&self) -> Option<usize> {
+        self.max_connections
+    }
+
+    pub fn has_default(&self) -> bool {
+        self.default_value.is_some()
+    }
+
+    pub(in crate::node_graph) fn default_value(&self) -> Option<&Arc<dyn Any + Send + Sync>> {
+        self.default_value.as_ref()
+    }
+
+    /// Return the default value, if it is set with the given type.
+    pub fn default_as<T: Any>(&self) -> Option<&T> {
+        self.default_value.as_ref()?.downcast_ref::<T>()
+    }
+
     pub fn port_type_id(&self) -> TypeId {
         self.port_type_id
     }
@@ -54,6 +148,10 @@ impl Input {
         &*self.data
     }
 
+    pub fn data_mut(&mut self) -> &mut dyn InputPortData {
+        &mut *self.data
+    }
+
     pub fn data_as<T: InputPortData>(&self) -> &T {
         let data = &*self.data;
         data.downcast_ref::<T>().unwrap()
@@ -64,9 +162,37 @@ impl Input {
         data.downcast_mut::<T>().unwrap()
     }
 
-    pub fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle) {
+    /// Create a copy of the port, if the data supports duplication.
+    pub fn duplicate(&self) -> Option<Self> {
+        let data = self.data.duplicate()?;
+        Some(Self {
+            name: self.name.clone(),
+            port_type_id: self.port_type_id,
+            min_connections: self.min_connections,
+            max_connections: self.max_connections,
+            default_value: self.default_value.clone(),
+            data,
+        })
+    }
+
+    /// Show the port. When the port is not driven by a connection, the inline editors are shown: the
+    /// [editor](PortStyle::with_editor) of the default value and the data of the port. Return if the default value
+    /// or the data was changed.
+    pub fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle, is_connected: bool) -> bool {
         ui.label(&self.name);
-        self.data.show(ui, port_id, style);
+        if is_connected {
+            return false;
+        }
+
+        let mut changed = false;
+        if let (Some(editor), Some(value)) = (&style.editor, &self.default_value) {
+            if let Some(value) = editor.show(ui, &**value) {
+                self.default_value = Some(value);
+                changed = true;
+            }
+        }
+        changed |= self.data.show(ui, port_id, style);
+        changed
     }
 }
 
@@ -96,16 +222,28 @@ impl InputId {
         self.2
     }
 }
-pub trait OutputPortData: 'static + Downcast + Send + Sync {
-    fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle);
+pub trait OutputPortData: 'static + Downcast + Send + Sync + HasProperties {
+    /// Show the editor of the port data and return if it was changed.
+    fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle) -> bool;
+
+    /// Create a copy of the data for a duplicated port. Ports returning `None` can not be duplicated.
+    fn duplicate(&self) -> Option<BoxedOutputPortData> {
+        None
+    }
 }
 impl_downcast!(OutputPortData);
 
 impl OutputPortData for () {
-    fn show(&mut self, _ui: &mut Ui, _port_id: usize, _style: &PortStyle) {}
+    fn show(&mut self, _ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        false
+    }
+
+    fn duplicate(&self) -> Option<BoxedOutputPortData> {
+        Some(smallbox!(()))
+    }
 }
 
-type BoxedOutputPortData = SmallBox<dyn OutputPortData, space::S2>;
+pub type BoxedOutputPortData = SmallBox<dyn OutputPortData, space::S2>;
 
 /// Output port
 pub struct Output {
@@ -123,6 +261,15 @@ impl Output {
         }
     }
 
+    /// Create a port of a type known only at runtime, ex. resolved from the name of a [`PortStyle`].
+    pub fn from_type_id(name: impl ToString, port_type_id: TypeId) -> Self {
+        Self {
+            name: name.to_string(),
+            port_type_id,
+            data: smallbox!(()),
+        }
+    }
+
     pub fn with<I>(self, data: I) -> Self
     where
         I: OutputPortData,
@@ -141,6 +288,10 @@ impl Output {
         &*self.data
     }
 
+    pub fn data_mut(&mut self) -> &mut dyn OutputPortData {
+        &mut *self.data
+    }
+
     pub fn data_as<T: OutputPortData>(&self) -> &T {
         let data = &*self.data;
         data.downcast_ref::<T>().unwrap()
@@ -151,9 +302,20 @@ impl Output {
         data.downcast_mut::<T>().unwrap()
     }
 
-    pub fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle) {
+    /// Create a copy of the port, if the data supports duplication.
+    pub fn duplicate(&self) -> Option<Self> {
+        let data = self.data.duplicate()?;
+        Some(Self {
+            name: self.name.clone(),
+            port_type_id: self.port_type_id,
+            data,
+        })
+    }
+
+    /// Show the port and return if the data was changed.
+    pub fn show(&mut self, ui: &mut Ui, port_id: usize, style: &PortStyle) -> bool {
         ui.label(&self.name);
-        self.data.show(ui, port_id, style);
+        self.data.show(ui, port_id, style)
     }
 }
 
@@ -226,3 +388,8 @@ impl From<OutputId> for InputOutputId {
         InputOutputId::Output(output_id)
     }
 }
+
+/// Provides the current value of the ports, ex. from the evaluation of the graph, to show in the tooltips.
+pub trait PortValueSource {
+    fn port_value(&self, graph: &Graph, port_id: InputOutputId) -> Option<String>;
+}
@@ -1,18 +1,44 @@
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
+    fmt,
+    sync::Arc,
 };
 
-use egui::Color32;
+use crate::node_graph::{GraphTheme, PreviewFormatter, ValuePreview};
+use egui::{Color32, Response, Ui};
+
+/// The shape of a port
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortShape {
+    Circle,
+    Square,
+    Diamond,
+    /// A triangle pointing in the direction of the data flow.
+    Triangle,
+}
 
 #[derive(Clone, Debug)]
 pub struct PortStyle {
     pub name: String,
     pub port_size: f32,
     pub connection_width: f32,
-    pub color: Color32,
-    pub hover_color: Color32,
-    pub error_color: Color32,
+    /// Color of the port and the connections, `None` to use the color of the theme.
+    pub color: Option<Color32>,
+    pub hover_color: Option<Color32>,
+    pub error_color: Option<Color32>,
+    pub shape: PortShape,
+    /// Draw only the outline of the port when it has no connection.
+    pub hollow_unconnected: bool,
+    /// Show the number of connections next to the port when it has more than one.
+    pub show_connection_count: bool,
+    /// Preview of the computed values on the wires and ports, see [`GraphEvaluator`](crate::node_graph::GraphEvaluator).
+    pub preview: Option<PreviewFormatter>,
+    /// Text of the computed values shown in the tooltips, the inspector and the traces. If not set, the text
+    /// [preview](Self::with_preview) is used.
+    pub display: Option<PreviewFormatter>,
+    /// Inline editor of the [default value](crate::node_graph::Input::with_default) of the unconnected inputs.
+    pub editor: Option<ValueEditor>,
 }
 
 impl PortStyle {
@@ -21,9 +47,15 @@ impl PortStyle {
             name: name.to_string(),
             port_size: 5.,
             connection_width: 3.,
-            color: Color32::WHITE,
-            hover_color: Color32::BLUE,
-            error_color: Color32::RED,
+            color: None,
+            hover_color: None,
+            error_color: None,
+            shape: PortShape::Circle,
+            hollow_unconnected: false,
+            show_connection_count: false,
+            preview: None,
+            display: None,
+            editor: None,
         }
     }
 
@@ -32,15 +64,144 @@ impl PortStyle {
     }
 
     pub fn with_color(self, color: Color32) -> Self {
-        Self { color, ..self }
+        Self {
+            color: Some(color),
+            ..self
+        }
     }
 
     pub fn with_hover_color(self, hover_color: Color32) -> Self {
-        Self { hover_color, ..self }
+        Self {
+            hover_color: Some(hover_color),
+            ..self
+        }
     }
 
     pub fn with_error_color(self, error_color: Color32) -> Self {
-        Self { error_color, ..self }
+        Self {
+            error_color: Some(error_color),
+            ..self
+        }
+    }
+
+    pub fn get_color(&self, theme: &GraphTheme) -> Color32 {
+        self.color.unwrap_or(theme.port_color)
+    }
+
+    pub fn get_wire_color(&self, theme: &GraphTheme) -> Color32 {
+        self.color.unwrap_or(theme.wire_color)
+    }
+
+    pub fn get_hover_color(&self, theme: &GraphTheme) -> Color32 {
+        self.hover_color.unwrap_or(theme.hover_color)
+    }
+
+    pub fn get_error_color(&self, theme: &GraphTheme) -> Color32 {
+        self.error_color.unwrap_or(theme.error_color)
+    }
+
+    pub fn with_shape(self, shape: PortShape) -> Self {
+        Self { shape, ..self }
+    }
+
+    pub fn with_hollow_unconnected(self, hollow_unconnected: bool) -> Self {
+        Self {
+            hollow_unconnected,
+            ..self
+        }
+    }
+
+    pub fn with_connection_count(self, show_connection_count: bool) -> Self {
+        Self {
+            show_connection_count,
+            ..self
+        }
+    }
+
+    /// Set the preview of the values of the port type `T`.
+    pub fn with_preview<T, F>(self, format: F) -> Self
+    where
+        T: Any,
+        F: 'static + Fn(&T) -> ValuePreview + Send + Sync,
+    {
+        Self {
+            preview: Some(PreviewFormatter::new(format)),
+            ..self
+        }
+    }
+
+    /// Return the preview of a value, `None` if there is no preview for the type of the value.
+    pub fn preview(&self, value: &dyn Any) -> Option<ValuePreview> {
+        self.preview.as_ref()?.preview(value)
+    }
+
+    /// Show the values of the port type `T` as text by their [`Display`](fmt::Display) implementation.
+    pub fn with_display<T: Any + fmt::Display>(self) -> Self {
+        Self {
+            display: Some(PreviewFormatter::new(|value: &T| ValuePreview::text(value))),
+            ..self
+        }
+    }
+
+    /// Return the text of a value, `None` if there is neither a display nor a text preview for the type of the
+    /// value.
+    pub fn display(&self, value: &dyn Any) -> Option<String> {
+        let preview = match &self.display {
+            Some(display) => display.preview(value)?,
+            None => self.preview(value)?,
+        };
+        match preview {
+            ValuePreview::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Set the inline editor of the default value of the inputs with the port type `T`.
+    pub fn with_editor<T, F>(self, edit: F) -> Self
+    where
+        T: Any + Clone + Send + Sync,
+        F: 'static + Fn(&mut Ui, &mut T) -> Response + Send + Sync,
+    {
+        Self {
+            editor: Some(ValueEditor::new(edit)),
+            ..self
+        }
+    }
+}
+
+type EditorFn = dyn Fn(&mut Ui, &dyn Any) -> Option<Arc<dyn Any + Send + Sync>> + Send + Sync;
+
+/// Edit the values of a port type, see [`PortStyle::with_editor`]. The value is edited on a copy, thus the ports
+/// sharing a default value, ex. the duplicated ones, are not affected.
+#[derive(Clone)]
+pub struct ValueEditor(Arc<EditorFn>);
+
+impl ValueEditor {
+    pub fn new<T, F>(edit: F) -> Self
+    where
+        T: Any + Clone + Send + Sync,
+        F: 'static + Fn(&mut Ui, &mut T) -> Response + Send + Sync,
+    {
+        Self(Arc::new(move |ui: &mut Ui, value: &dyn Any| {
+            let mut value = value.downcast_ref::<T>()?.clone();
+            if edit(ui, &mut value).changed() {
+                Some(Arc::new(value) as Arc<dyn Any + Send + Sync>)
+            } else {
+                None
+            }
+        }))
+    }
+
+    /// Show the editor and return the new value if it was changed. Return `None` if the value was not changed or
+    /// it has a different type.
+    pub fn show(&self, ui: &mut Ui, value: &dyn Any) -> Option<Arc<dyn Any + Send + Sync>> {
+        (self.0)(ui, value)
+    }
+}
+
+impl fmt::Debug for ValueEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ValueEditor")
     }
 }
 
@@ -73,4 +234,12 @@ impl PortStyles {
     pub fn find(&self, ty: TypeId) -> Option<&PortStyle> {
         self.styles.get(&ty)
     }
+
+    /// Find the port type by the name of its style.
+    pub fn find_type_by_name(&self, name: &str) -> Option<TypeId> {
+        self.styles
+            .iter()
+            .find(|(_, style)| style.name == name)
+            .map(|(ty, _)| *ty)
+    }
 }
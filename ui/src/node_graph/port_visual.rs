@@ -1,5 +1,5 @@
-use crate::node_graph::InputOutputId;
-use egui::{Id, Pos2, Ui};
+use crate::node_graph::{ConnectionId, Graph, InputOutputId, InteractionMode, NodeId};
+use egui::{Id, Pos2, Rect, Ui};
 use shine_core::atomic_refcell::AtomicRefCell;
 use std::{collections::HashMap, sync::Arc};
 
@@ -14,26 +14,34 @@ pub(in crate::node_graph) enum PortSelection {
 struct Inner {
     selected_ports: HashMap<InputOutputId, PortSelection>,
     screen_location: HashMap<InputOutputId, Pos2>,
+    node_rects: HashMap<NodeId, Rect>,
+    connection_counts: HashMap<InputOutputId, usize>,
 }
 
 /// Visual information of the ports of a graph asociated to a view of it.
 #[derive(Clone)]
 pub(in crate::node_graph) struct PortViewState {
     hovered: Option<InputOutputId>,
+    hovered_node: Option<NodeId>,
+    hovered_connection: Option<ConnectionId>,
     /// Arc-ed larger data structurd to make the `Clone` cheaper.
     inner: Arc<AtomicRefCell<Inner>>,
 
     nodes_enabled: bool,
     ports_enabled: bool,
+    interaction: InteractionMode,
 }
 
 impl Default for PortViewState {
     fn default() -> Self {
         Self {
             hovered: None,
+            hovered_node: None,
+            hovered_connection: None,
             inner: Arc::new(AtomicRefCell::new(Inner::default())),
             nodes_enabled: true,
             ports_enabled: true,
+            interaction: InteractionMode::Full,
         }
     }
 }
@@ -50,8 +58,30 @@ impl PortViewState {
     pub fn clear(&mut self) {
         let inner = &mut *self.inner.borrow_mut();
         self.hovered = None;
+        self.hovered_node = None;
+        self.hovered_connection = None;
         inner.selected_ports.clear();
         inner.screen_location.clear();
+        inner.node_rects.clear();
+        inner.connection_counts.clear();
+    }
+
+    /// Count the connections of the ports
+    pub fn update_connection_counts(&mut self, graph: &Graph) {
+        let inner = &mut *self.inner.borrow_mut();
+        inner.connection_counts.clear();
+        for connection in graph.connections() {
+            *inner.connection_counts.entry(connection.input_id().into()).or_default() += 1;
+            *inner
+                .connection_counts
+                .entry(connection.output_id().into())
+                .or_default() += 1;
+        }
+    }
+
+    pub fn get_connection_count(&self, port_id: InputOutputId) -> usize {
+        let inner = self.inner.borrow();
+        inner.connection_counts.get(&port_id).cloned().unwrap_or(0)
     }
 
     pub fn is_nodes_enabled(&self) -> bool {
@@ -70,6 +100,14 @@ impl PortViewState {
         self.ports_enabled = enabled;
     }
 
+    pub fn interaction(&self) -> InteractionMode {
+        self.interaction
+    }
+
+    pub fn set_interaction(&mut self, interaction: InteractionMode) {
+        self.interaction = interaction;
+    }
+
     pub fn has_hovered(&self) -> bool {
         self.hovered.is_some()
     }
@@ -86,6 +124,22 @@ impl PortViewState {
         self.hovered = Some(port_id)
     }
 
+    pub fn get_hovered_node(&self) -> Option<NodeId> {
+        self.hovered_node
+    }
+
+    pub fn set_hovered_node(&mut self, node_id: NodeId) {
+        self.hovered_node = Some(node_id)
+    }
+
+    pub fn get_hovered_connection(&self) -> Option<ConnectionId> {
+        self.hovered_connection
+    }
+
+    pub fn set_hovered_connection(&mut self, connection_id: ConnectionId) {
+        self.hovered_connection = Some(connection_id)
+    }
+
     pub fn get_screen_pos(&self, port_id: InputOutputId) -> Option<Pos2> {
         let inner = self.inner.borrow();
         inner.screen_location.get(&port_id).cloned()
@@ -96,6 +150,16 @@ impl PortViewState {
         let _ = inner.screen_location.insert(port_id, pos);
     }
 
+    pub fn get_node_rect(&self, node_id: NodeId) -> Option<Rect> {
+        let inner = self.inner.borrow();
+        inner.node_rects.get(&node_id).cloned()
+    }
+
+    pub fn set_node_rect(&mut self, node_id: NodeId, rect: Rect) {
+        let inner = &mut *self.inner.borrow_mut();
+        let _ = inner.node_rects.insert(node_id, rect);
+    }
+
     pub fn get_selection(&self, port_id: InputOutputId) -> PortSelection {
         let inner = self.inner.borrow();
         inner
@@ -0,0 +1,147 @@
+use crate::node_graph::GraphTheme;
+use egui::{pos2, vec2, Align2, Color32, FontId, Painter, Pos2, Rect, Shape, Stroke, TextureId, Vec2};
+use std::{any::Any, fmt, sync::Arc};
+
+/// Maximum number of characters of a text preview.
+const MAX_TEXT_LENGTH: usize = 24;
+const FONT_SIZE: f32 = 10.;
+const SWATCH_SIZE: f32 = 12.;
+const SPARKLINE_SIZE: Vec2 = vec2(48., 14.);
+const PADDING: f32 = 2.;
+
+/// A compact visualization of a port value shown on the wires and ports.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValuePreview {
+    Text(String),
+    /// A color swatch.
+    Color(Color32),
+    /// A line chart of the samples scaled to their range.
+    Sparkline(Vec<f32>),
+    /// A thumbnail of a texture registered by the host with the size in graph (area) units.
+    Image(TextureId, Vec2),
+}
+
+impl ValuePreview {
+    /// Create a text preview from the displayed value.
+    pub fn text<T: fmt::Display>(value: T) -> Self {
+        ValuePreview::Text(value.to_string())
+    }
+
+    fn size(&self, painter: &Painter, zoom: f32, theme: &GraphTheme) -> Vec2 {
+        match self {
+            ValuePreview::Text(text) => {
+                let galley = painter.layout_no_wrap(
+                    Self::short_text(text),
+                    FontId::monospace(FONT_SIZE * zoom),
+                    theme.node_text_color,
+                );
+                galley.size()
+            }
+            ValuePreview::Color(_) => Vec2::splat(SWATCH_SIZE * zoom),
+            ValuePreview::Sparkline(_) => SPARKLINE_SIZE * zoom,
+            ValuePreview::Image(_, size) => *size * zoom,
+        }
+    }
+
+    fn short_text(text: &str) -> String {
+        if text.chars().count() > MAX_TEXT_LENGTH {
+            let mut text: String = text.chars().take(MAX_TEXT_LENGTH - 1).collect();
+            text.push('…');
+            text
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Paint the preview on a small label anchored at a point and return the area of the label.
+    pub(in crate::node_graph) fn paint(
+        &self,
+        painter: &Painter,
+        anchor: Pos2,
+        align: Align2,
+        zoom: f32,
+        theme: &GraphTheme,
+    ) -> Rect {
+        let padding = PADDING * zoom;
+        let size = self.size(painter, zoom, theme);
+        let frame = align.anchor_rect(Rect::from_min_size(anchor, size + Vec2::splat(2. * padding)));
+        let content = frame.shrink(padding);
+        painter.rect(frame, 2. * zoom, theme.node_fill, theme.node_stroke);
+
+        match self {
+            ValuePreview::Text(text) => {
+                painter.text(
+                    content.min,
+                    Align2::LEFT_TOP,
+                    Self::short_text(text),
+                    FontId::monospace(FONT_SIZE * zoom),
+                    theme.node_text_color,
+                );
+            }
+            ValuePreview::Color(color) => {
+                painter.rect_filled(content, 0., *color);
+            }
+            ValuePreview::Sparkline(samples) => {
+                let (min, max) = samples
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(min, max), v| (min.min(*v), max.max(*v)));
+                let range = if max > min { max - min } else { 1. };
+                let step = content.width() / (samples.len().max(2) - 1) as f32;
+                let points = samples
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        pos2(
+                            content.left() + i as f32 * step,
+                            content.bottom() - (v - min) / range * content.height(),
+                        )
+                    })
+                    .collect();
+                painter.add(Shape::line(
+                    points,
+                    Stroke {
+                        width: 1.,
+                        color: theme.node_text_color,
+                    },
+                ));
+            }
+            ValuePreview::Image(texture_id, _) => {
+                painter.image(
+                    *texture_id,
+                    content,
+                    Rect::from_min_max(pos2(0., 0.), pos2(1., 1.)),
+                    Color32::WHITE,
+                );
+            }
+        }
+
+        frame
+    }
+}
+
+type PreviewFn = dyn Fn(&dyn Any) -> Option<ValuePreview> + Send + Sync;
+
+/// Create the [`ValuePreview`] of the values of a port type, see [`PortStyle::with_preview`](crate::node_graph::PortStyle::with_preview).
+#[derive(Clone)]
+pub struct PreviewFormatter(Arc<PreviewFn>);
+
+impl PreviewFormatter {
+    pub fn new<T, F>(format: F) -> Self
+    where
+        T: Any,
+        F: 'static + Fn(&T) -> ValuePreview + Send + Sync,
+    {
+        Self(Arc::new(move |value: &dyn Any| value.downcast_ref::<T>().map(&format)))
+    }
+
+    /// Return the preview of a value, `None` if the value has a different type.
+    pub fn preview(&self, value: &dyn Any) -> Option<ValuePreview> {
+        (self.0)(value)
+    }
+}
+
+impl fmt::Debug for PreviewFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PreviewFormatter")
+    }
+}
@@ -0,0 +1,240 @@
+use egui::{Color32, DragValue, Grid, Slider, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, hash::Hash};
+
+/// The value of a [`Property`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PropertyValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Color(Color32),
+    /// The index of the selected item of the [choices](Property::with_choices).
+    Choice(usize),
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Bool(value)
+    }
+}
+
+impl From<i64> for PropertyValue {
+    fn from(value: i64) -> Self {
+        PropertyValue::Integer(value)
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        PropertyValue::Float(value)
+    }
+}
+
+impl From<f32> for PropertyValue {
+    fn from(value: f32) -> Self {
+        PropertyValue::Float(value as f64)
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::Text(value)
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        PropertyValue::Text(value.to_string())
+    }
+}
+
+impl From<Color32> for PropertyValue {
+    fn from(value: Color32) -> Self {
+        PropertyValue::Color(value)
+    }
+}
+
+/// Customize the editor of a property.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyHint {
+    None,
+    /// Edit a number by a slider in the given range.
+    Range(f64, f64),
+    /// Select a [`PropertyValue::Choice`] from the items.
+    Choices(Vec<String>),
+    /// Edit a text in multiple lines.
+    MultiLine,
+}
+
+/// The description of an editable field with its current value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+    /// The key of the property, it identifies the property in [`Properties::set_property`] and in a [`PropertySet`].
+    pub name: String,
+    pub label: String,
+    pub value: PropertyValue,
+    pub hint: PropertyHint,
+    pub read_only: bool,
+}
+
+impl Property {
+    pub fn new<S: ToString, V: Into<PropertyValue>>(name: S, value: V) -> Self {
+        let name = name.to_string();
+        Self {
+            label: name.clone(),
+            name,
+            value: value.into(),
+            hint: PropertyHint::None,
+            read_only: false,
+        }
+    }
+
+    pub fn with_label<S: ToString>(self, label: S) -> Self {
+        Self {
+            label: label.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_range(self, min: f64, max: f64) -> Self {
+        Self {
+            hint: PropertyHint::Range(min, max),
+            ..self
+        }
+    }
+
+    pub fn with_choices<S: ToString>(self, choices: &[S]) -> Self {
+        Self {
+            hint: PropertyHint::Choices(choices.iter().map(|choice| choice.to_string()).collect()),
+            ..self
+        }
+    }
+
+    pub fn with_multiline(self) -> Self {
+        Self {
+            hint: PropertyHint::MultiLine,
+            ..self
+        }
+    }
+
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Show the editor of the value, return if it was changed.
+    fn show_value(&mut self, ui: &mut Ui) -> bool {
+        ui.add_enabled_ui(!self.read_only, |ui| match (&mut self.value, &self.hint) {
+            (PropertyValue::Bool(value), _) => ui.checkbox(value, "").changed(),
+            (PropertyValue::Integer(value), PropertyHint::Range(min, max)) => {
+                ui.add(Slider::new(value, (*min as i64)..=(*max as i64))).changed()
+            }
+            (PropertyValue::Integer(value), _) => ui.add(DragValue::new(value)).changed(),
+            (PropertyValue::Float(value), PropertyHint::Range(min, max)) => {
+                ui.add(Slider::new(value, *min..=*max)).changed()
+            }
+            (PropertyValue::Float(value), _) => ui.add(DragValue::new(value).speed(0.1)).changed(),
+            (PropertyValue::Text(value), PropertyHint::MultiLine) => {
+                ui.add(TextEdit::multiline(value).desired_rows(3)).changed()
+            }
+            (PropertyValue::Text(value), _) => ui.text_edit_singleline(value).changed(),
+            (PropertyValue::Color(value), _) => ui.color_edit_button_srgba(value).changed(),
+            (PropertyValue::Choice(value), PropertyHint::Choices(choices)) => {
+                let mut changed = false;
+                for (index, choice) in choices.iter().enumerate() {
+                    changed |= ui.radio_value(value, index, choice).changed();
+                }
+                changed
+            }
+            (PropertyValue::Choice(value), _) => ui.add(DragValue::new(value)).changed(),
+        })
+        .inner
+    }
+}
+
+/// Describe the editable fields of a data, ex. a [`NodeData`](crate::node_graph::NodeData). The same description
+/// drives the inline editor (see [`show_properties`]), the [`PropertyPanel`](crate::node_graph::PropertyPanel) and
+/// the serialization by a [`PropertySet`].
+pub trait Properties {
+    /// Return the properties with their current value.
+    fn properties(&self) -> Vec<Property>;
+
+    /// Set the value of a property, return `false` if the property is unknown or the value is rejected.
+    fn set_property(&mut self, name: &str, value: PropertyValue) -> bool;
+}
+
+/// Give access to the [`Properties`] of a data. It is shared by the data of the nodes, the ports and the connections,
+/// the data without editable fields can use the default implementation.
+pub trait HasProperties {
+    fn properties(&self) -> Option<&dyn Properties> {
+        None
+    }
+
+    fn properties_mut(&mut self) -> Option<&mut dyn Properties> {
+        None
+    }
+}
+
+impl HasProperties for () {}
+
+/// Show an editor for each property in a grid and return if any of them was changed.
+pub fn show_properties<I: Hash>(ui: &mut Ui, id_source: I, properties: &mut dyn Properties) -> bool {
+    let mut changed = false;
+    Grid::new(id_source).num_columns(2).striped(true).show(ui, |ui| {
+        for mut property in properties.properties() {
+            ui.label(&property.label);
+            if property.show_value(ui) && !property.read_only {
+                changed |= properties.set_property(&property.name, property.value);
+            }
+            ui.end_row();
+        }
+    });
+    changed
+}
+
+/// The values of the properties by their name, it is the serializable form of a [`Properties`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PropertySet(BTreeMap<String, PropertyValue>);
+
+impl PropertySet {
+    pub fn from_properties(properties: &dyn Properties) -> Self {
+        Self(
+            properties
+                .properties()
+                .into_iter()
+                .map(|property| (property.name, property.value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PropertyValue> {
+        self.0.get(name)
+    }
+
+    pub fn set<V: Into<PropertyValue>>(&mut self, name: &str, value: V) {
+        self.0.insert(name.to_string(), value.into());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PropertyValue)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Set the values of the properties and return the names of the rejected ones, ex. the properties of an older
+    /// version of the data.
+    pub fn apply(&self, properties: &mut dyn Properties) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(name, value)| !properties.set_property(name, (*value).clone()))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+use crate::node_graph::{show_properties, ConnectionId, Graph, InputOutputId, InteractionMode, NodeId, Properties};
+use egui::{Checkbox, CollapsingHeader, Grid, Ui};
+use std::any::TypeId;
+
+/// Show the properties of a node, its ports and its connections in a form, ex. in a side panel. The editable fields
+/// are described by the [`Properties`] of the data.
+///
+/// A change of the properties is reported to the graph by [`Graph::mark_changed`].
+pub struct PropertyPanel<'a> {
+    graph: &'a mut Graph,
+    interaction: InteractionMode,
+}
+
+impl<'a> PropertyPanel<'a> {
+    pub fn new(graph: &'a mut Graph) -> Self {
+        Self {
+            graph,
+            interaction: InteractionMode::Full,
+        }
+    }
+
+    /// Set the allowed modifications, it shall match the mode of the [`GraphEdit`](crate::node_graph::GraphEdit)
+    /// showing the graph. The fields are read only if the content edit is not allowed, the collapse of a locked node
+    /// can not be changed.
+    pub fn with_interaction(self, interaction: InteractionMode) -> Self {
+        Self { interaction, ..self }
+    }
+
+    fn show_data(ui: &mut Ui, id: (NodeId, &str, usize), properties: Option<&mut dyn Properties>) -> bool {
+        match properties {
+            Some(properties) => show_properties(ui, id, properties),
+            None => false,
+        }
+    }
+
+    /// Show the panel of a node and return if anything was changed.
+    pub fn show(self, ui: &mut Ui, node_id: NodeId) -> bool {
+        ui.add_enabled_ui(self.interaction.allows_content_edit(), |ui| self.show_node(ui, node_id))
+            .inner
+    }
+
+    fn show_node(self, ui: &mut Ui, node_id: NodeId) -> bool {
+        let port_styles = self.graph.get_port_styles().clone();
+        let type_name = |type_id: TypeId| {
+            port_styles
+                .find(type_id)
+                .map(|style| style.name.clone())
+                .unwrap_or_else(|| "?".to_string())
+        };
+        let connections: Vec<(ConnectionId, String)> = self
+            .graph
+            .connections()
+            .filter(|connection| connection.input_node_id() == node_id || connection.output_node_id() == node_id)
+            .map(|connection| {
+                let port_text = |port_id: InputOutputId| {
+                    let caption = self
+                        .graph
+                        .node(port_id.node_id())
+                        .map(|node| node.caption.as_str())
+                        .unwrap_or("?");
+                    format!("{}.{}", caption, self.graph.port_name(port_id).unwrap_or("?"))
+                };
+                let text = format!(
+                    "{} → {}",
+                    port_text(connection.output_id().into()),
+                    port_text(connection.input_id().into())
+                );
+                (connection.id(), text)
+            })
+            .collect();
+        let connection_counts: Vec<usize> = match self.graph.node(node_id) {
+            Some(node) => (0..node.inputs.len())
+                .map(|port_id| {
+                    node.input_id(port_id)
+                        .map(|input_id| self.graph.input_connection_count(input_id))
+                        .unwrap_or(0)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let node = match self.graph.node_mut(node_id) {
+            Some(node) => node,
+            None => {
+                ui.label("No node");
+                return false;
+            }
+        };
+
+        let mut changed = false;
+        let is_layout_enabled = node.is_layout_enabled(self.interaction);
+        Grid::new((node_id, "node")).num_columns(2).show(ui, |ui| {
+            ui.label("caption");
+            changed |= ui.text_edit_singleline(&mut node.caption).changed();
+            ui.end_row();
+            ui.label("kind");
+            ui.label(&node.kind);
+            ui.end_row();
+            ui.label("collapsed");
+            changed |= ui
+                .add_enabled(is_layout_enabled, Checkbox::new(&mut node.collapsed, ""))
+                .changed();
+            ui.end_row();
+            ui.label("locked");
+            changed |= ui.checkbox(&mut node.locked, "").changed();
+            ui.end_row();
+        });
+
+        CollapsingHeader::new("Properties")
+            .id_source((node_id, "properties"))
+            .default_open(true)
+            .show(ui, |ui| match node.data_mut().properties_mut() {
+                Some(properties) => changed |= show_properties(ui, (node_id, "data", 0), properties),
+                None => {
+                    ui.label("No properties");
+                }
+            });
+
+        if !node.inputs.is_empty() {
+            CollapsingHeader::new("Inputs")
+                .id_source((node_id, "inputs"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for (port_id, input) in node.inputs.iter_mut().enumerate() {
+                        let limits = match input.max_connections() {
+                            Some(max) => format!("{}..{}", input.min_connections(), max),
+                            None => format!("{}..", input.min_connections()),
+                        };
+                        ui.strong(&input.name);
+                        ui.label(format!(
+                            "type: {}, connections: {} ({})",
+                            type_name(input.port_type_id()),
+                            connection_counts.get(port_id).cloned().unwrap_or(0),
+                            limits
+                        ));
+                        changed |= Self::show_data(ui, (node_id, "input", port_id), input.data_mut().properties_mut());
+                    }
+                });
+        }
+
+        if !node.outputs.is_empty() {
+            CollapsingHeader::new("Outputs")
+                .id_source((node_id, "outputs"))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for (port_id, output) in node.outputs.iter_mut().enumerate() {
+                        ui.strong(&output.name);
+                        ui.label(format!("type: {}", type_name(output.port_type_id())));
+                        changed |=
+                            Self::show_data(ui, (node_id, "output", port_id), output.data_mut().properties_mut());
+                    }
+                });
+        }
+
+        if !connections.is_empty() {
+            CollapsingHeader::new("Connections")
+                .id_source((node_id, "connections"))
+                .default_open(false)
+                .show(ui, |ui| {
+                    for (index, (connection_id, text)) in connections.iter().enumerate() {
+                        ui.label(text);
+                        if let Some(connection) = self.graph.connection_mut(*connection_id) {
+                            changed |= Self::show_data(
+                                ui,
+                                (node_id, "connection", index),
+                                connection.data_mut().properties_mut(),
+                            );
+                        }
+                    }
+                });
+        }
+
+        if changed {
+            self.graph.mark_changed();
+        }
+        changed
+    }
+}
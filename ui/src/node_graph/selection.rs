@@ -0,0 +1,65 @@
+use crate::node_graph::{Graph, NodeId};
+use std::collections::BTreeSet;
+
+/// The set of selected nodes of a graph.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Selection {
+    nodes: BTreeSet<NodeId>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn contains(&self, node_id: NodeId) -> bool {
+        self.nodes.contains(&node_id)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().cloned()
+    }
+
+    pub fn to_vec(&self) -> Vec<NodeId> {
+        self.nodes.iter().cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Select a single node, clearing the previous selection.
+    pub fn set(&mut self, node_id: NodeId) {
+        self.nodes.clear();
+        self.nodes.insert(node_id);
+    }
+
+    /// Replace the selection with the given nodes.
+    pub fn set_all<I: IntoIterator<Item = NodeId>>(&mut self, node_ids: I) {
+        self.nodes.clear();
+        self.nodes.extend(node_ids);
+    }
+
+    pub fn add(&mut self, node_id: NodeId) {
+        self.nodes.insert(node_id);
+    }
+
+    pub fn remove(&mut self, node_id: NodeId) {
+        self.nodes.remove(&node_id);
+    }
+
+    pub fn toggle(&mut self, node_id: NodeId) {
+        if !self.nodes.remove(&node_id) {
+            self.nodes.insert(node_id);
+        }
+    }
+
+    /// Remove the nodes that are not part of the graph anymore.
+    pub fn retain_existing(&mut self, graph: &Graph) {
+        self.nodes.retain(|node_id| graph.node(*node_id).is_some());
+    }
+}
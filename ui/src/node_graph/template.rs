@@ -0,0 +1,243 @@
+use crate::node_graph::{
+    ConextMenuBuilder, ContextMenuContext, ContextMenuData, ContextMenuProvider, Graph, InteractionMode, Node, NodeId,
+};
+use egui::{pos2, Pos2};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// A connection between the nodes of a template referring to the nodes by their index.
+struct TemplateConnection {
+    input_node: usize,
+    input_port: usize,
+    output_node: usize,
+    output_port: usize,
+}
+
+/// A reusable cluster of nodes with the connections between them.
+pub struct GraphTemplate {
+    pub name: String,
+    /// Copies of the nodes, the locations are relative to the top-left corner of the cluster.
+    nodes: Vec<Node>,
+    connections: Vec<TemplateConnection>,
+}
+
+impl GraphTemplate {
+    /// Create a template from the nodes and the connections between them. The nodes are copied with their data,
+    /// the nodes that don't support duplication are skipped. Return `None` if no node could be copied.
+    pub fn from_nodes<S: ToString>(name: S, graph: &Graph, node_ids: &[NodeId]) -> Option<Self> {
+        let mut index = HashMap::new();
+        let mut nodes = Vec::new();
+        for node_id in node_ids {
+            if index.contains_key(node_id) {
+                continue;
+            }
+            if let Some(node) = graph.node(*node_id).and_then(|node| node.duplicate()) {
+                index.insert(*node_id, nodes.len());
+                nodes.push(node);
+            }
+        }
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let origin = nodes.iter().fold(pos2(f32::MAX, f32::MAX), |origin, node| {
+            pos2(origin.x.min(node.location.x), origin.y.min(node.location.y))
+        });
+        for node in &mut nodes {
+            node.location = (node.location - origin).to_pos2();
+        }
+
+        let connections = graph
+            .connections()
+            .filter_map(|connection| {
+                Some(TemplateConnection {
+                    input_node: *index.get(&connection.input_node_id())?,
+                    input_port: connection.input_id().port_id(),
+                    output_node: *index.get(&connection.output_node_id())?,
+                    output_port: connection.output_id().port_id(),
+                })
+            })
+            .collect();
+
+        Some(Self {
+            name: name.to_string(),
+            nodes,
+            connections,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Add a copy of the nodes to the graph with the top-left corner of the cluster at the location. The connections
+    /// are recreated by [`Graph::try_create_connection`]. Return the ids of the new nodes in the order of the
+    /// template.
+    pub fn instantiate(&self, graph: &mut Graph, location: Pos2) -> Vec<NodeId> {
+        let node_ids: Vec<Option<NodeId>> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut node = node.duplicate()?;
+                node.location = location + node.location.to_vec2();
+                Some(graph.add_node(node))
+            })
+            .collect();
+
+        for connection in &self.connections {
+            let input_id = node_ids[connection.input_node]
+                .and_then(|node_id| graph.node(node_id)?.input_id(connection.input_port));
+            let output_id = node_ids[connection.output_node]
+                .and_then(|node_id| graph.node(node_id)?.output_id(connection.output_port));
+            if let (Some(input_id), Some(output_id)) = (input_id, output_id) {
+                if let Some(connection) = graph.try_create_connection(input_id, output_id) {
+                    graph.add_connection(connection);
+                }
+            }
+        }
+
+        node_ids.into_iter().flatten().collect()
+    }
+}
+
+/// A named collection of [`GraphTemplate`]s.
+#[derive(Default)]
+pub struct TemplateLibrary {
+    templates: Vec<GraphTemplate>,
+}
+
+impl TemplateLibrary {
+    /// Add a template, a template with the same name is replaced.
+    pub fn add(&mut self, template: GraphTemplate) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    /// Create a template from the nodes and add it to the library, see [`GraphTemplate::from_nodes`].
+    /// Return if the template was added.
+    pub fn save_nodes<S: ToString>(&mut self, name: S, graph: &Graph, node_ids: &[NodeId]) -> bool {
+        match GraphTemplate::from_nodes(name, graph, node_ids) {
+            Some(template) => {
+                self.add(template);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<GraphTemplate> {
+        let index = self.templates.iter().position(|t| t.name == name)?;
+        Some(self.templates.remove(index))
+    }
+
+    /// Rename a template, return `false` if it is missing or the new name is already used.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> bool {
+        if self.get(new_name).is_some() {
+            return false;
+        }
+        match self.templates.iter_mut().find(|t| t.name == name) {
+            Some(template) => {
+                template.name = new_name.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GraphTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    pub fn templates(&self) -> impl Iterator<Item = &GraphTemplate> {
+        self.templates.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Return the first free name of the `<prefix> <number>` form.
+    pub fn unique_name(&self, prefix: &str) -> String {
+        (1..)
+            .map(|index| format!("{} {}", prefix, index))
+            .find(|name| self.get(name).is_none())
+            .unwrap()
+    }
+}
+
+pub type SharedTemplateLibrary = Arc<RwLock<TemplateLibrary>>;
+
+enum TemplateMenuData {
+    Instantiate(SharedTemplateLibrary, String),
+    SaveSelection(SharedTemplateLibrary),
+}
+
+impl ContextMenuData for TemplateMenuData {
+    fn on_select(&self, graph: &mut Graph, context: &ContextMenuContext) {
+        match self {
+            TemplateMenuData::Instantiate(library, name) => {
+                if let Some(template) = library.read().unwrap().get(name) {
+                    template.instantiate(graph, context.location);
+                }
+            }
+            TemplateMenuData::SaveSelection(library) => {
+                let mut library = library.write().unwrap();
+                let name = library.unique_name("template");
+                library.save_nodes(name, graph, &context.selection);
+            }
+        }
+    }
+
+    fn is_enabled(&self, _graph: &Graph, context: &ContextMenuContext) -> bool {
+        match self {
+            TemplateMenuData::Instantiate(..) => context.interaction == InteractionMode::Full,
+            TemplateMenuData::SaveSelection(_) => !context.selection.is_empty(),
+        }
+    }
+}
+
+/// Add the templates of a library to the context menu of the canvas, the selected template is instantiated at the
+/// location of the click. When some nodes are selected, an item to save them as a new template is added too, the
+/// template gets a generated name that can be changed by [`TemplateLibrary::rename`].
+pub struct TemplateMenu {
+    library: SharedTemplateLibrary,
+}
+
+impl TemplateMenu {
+    pub fn new(library: SharedTemplateLibrary) -> Self {
+        Self { library }
+    }
+}
+
+impl ContextMenuProvider for TemplateMenu {
+    fn build(&self, _graph: &Graph, context: &ContextMenuContext, builder: &mut ConextMenuBuilder<'_>) {
+        let library = self.library.read().unwrap();
+        if context.target.is_canvas() && !library.is_empty() {
+            let mut group = builder.add_group("templates");
+            for template in library.templates() {
+                group.add_item(
+                    &template.name,
+                    TemplateMenuData::Instantiate(self.library.clone(), template.name.clone()),
+                );
+            }
+        }
+        if !context.selection.is_empty() {
+            builder.add_item(
+                "save selection as template",
+                TemplateMenuData::SaveSelection(self.library.clone()),
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use egui::{Color32, Stroke, Style, Visuals};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum GraphThemeError {
+    #[error("Failed to read the theme")]
+    Io(#[from] io::Error),
+    #[error("Failed to parse the theme")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The colors of the graph editor.
+///
+/// The colors of a [`PortStyle`](crate::node_graph::PortStyle) that are not set explicitly are taken from the theme.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphTheme {
+    pub background: Color32,
+    pub grid_color: Color32,
+    /// Distance of the grid lines in graph (area) units, 0 to hide the grid.
+    pub grid_spacing: f32,
+
+    pub node_fill: Color32,
+    pub node_stroke: Stroke,
+    pub node_text_color: Color32,
+    pub selection_stroke: Stroke,
+
+    pub wire_color: Color32,
+    pub port_color: Color32,
+    pub hover_color: Color32,
+    pub error_color: Color32,
+    pub warning_color: Color32,
+
+    /// Colors of the diff overlay
+    pub added_color: Color32,
+    pub removed_color: Color32,
+    pub changed_color: Color32,
+    pub moved_color: Color32,
+
+    /// Color of the debug overlays.
+    pub debug_color: Color32,
+}
+
+impl Default for GraphTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl GraphTheme {
+    pub fn dark() -> Self {
+        Self {
+            background: Color32::from_gray(20),
+            grid_color: Color32::from_gray(35),
+            grid_spacing: 32.,
+
+            node_fill: Color32::from_gray(35),
+            node_stroke: Stroke {
+                width: 1.,
+                color: Color32::from_gray(60),
+            },
+            node_text_color: Color32::from_gray(200),
+            selection_stroke: Stroke {
+                width: 2.,
+                color: Color32::from_rgb(90, 170, 255),
+            },
+
+            wire_color: Color32::from_gray(200),
+            port_color: Color32::WHITE,
+            hover_color: Color32::from_rgb(90, 170, 255),
+            error_color: Color32::from_rgb(255, 80, 80),
+            warning_color: Color32::from_rgb(255, 190, 60),
+
+            added_color: Color32::from_rgb(80, 200, 80),
+            removed_color: Color32::from_rgb(230, 70, 70),
+            changed_color: Color32::from_rgb(230, 180, 40),
+            moved_color: Color32::from_rgb(90, 150, 230),
+
+            debug_color: Color32::YELLOW,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            background: Color32::from_gray(235),
+            grid_color: Color32::from_gray(215),
+            grid_spacing: 32.,
+
+            node_fill: Color32::from_gray(250),
+            node_stroke: Stroke {
+                width: 1.,
+                color: Color32::from_gray(180),
+            },
+            node_text_color: Color32::from_gray(40),
+            selection_stroke: Stroke {
+                width: 2.,
+                color: Color32::from_rgb(0, 110, 220),
+            },
+
+            wire_color: Color32::from_gray(80),
+            port_color: Color32::from_gray(60),
+            hover_color: Color32::from_rgb(0, 110, 220),
+            error_color: Color32::from_rgb(210, 30, 30),
+            warning_color: Color32::from_rgb(200, 130, 0),
+
+            added_color: Color32::from_rgb(20, 150, 20),
+            removed_color: Color32::from_rgb(200, 30, 30),
+            changed_color: Color32::from_rgb(200, 130, 0),
+            moved_color: Color32::from_rgb(30, 90, 200),
+
+            debug_color: Color32::from_rgb(200, 120, 0),
+        }
+    }
+
+    /// Pick the preset matching the egui visuals.
+    pub fn from_visuals(visuals: &Visuals) -> Self {
+        if visuals.dark_mode {
+            Self::dark()
+        } else {
+            Self::light()
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, GraphThemeError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, GraphThemeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Load a theme from a json file. The missing colors are taken from the dark preset.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GraphThemeError> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GraphThemeError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Apply the node colors to the style used to render the nodes.
+    pub(in crate::node_graph) fn apply(&self, style: &mut Style) {
+        let visuals = &mut style.visuals;
+        visuals.window_fill = self.node_fill;
+        visuals.window_stroke = self.node_stroke;
+        visuals.override_text_color = Some(self.node_text_color);
+        visuals.selection.stroke = self.selection_stroke;
+    }
+}
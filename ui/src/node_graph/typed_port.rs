@@ -0,0 +1,163 @@
+use crate::node_graph::{InputId, NodeId, OutputId};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// An [`InputId`] with the type of the port checked at construction.
+pub struct TypedInputId<T: 'static> {
+    id: InputId,
+    _ph: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> TypedInputId<T> {
+    /// Create a typed id, return `None` if the type of the port differs.
+    pub fn try_from_id(id: InputId) -> Option<Self> {
+        if id.port_type_id() == TypeId::of::<T>() {
+            Some(Self { id, _ph: PhantomData })
+        } else {
+            None
+        }
+    }
+
+    pub fn id(&self) -> InputId {
+        self.id
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.id.node_id()
+    }
+
+    pub fn port_id(&self) -> usize {
+        self.id.port_id()
+    }
+}
+
+impl<T: 'static> Clone for TypedInputId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for TypedInputId<T> {}
+
+impl<T: 'static> PartialEq for TypedInputId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: 'static> Eq for TypedInputId<T> {}
+
+impl<T: 'static> Hash for TypedInputId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<T: 'static> fmt::Debug for TypedInputId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedInputId").field(&self.id).finish()
+    }
+}
+
+impl<T: 'static> From<TypedInputId<T>> for InputId {
+    fn from(id: TypedInputId<T>) -> Self {
+        id.id
+    }
+}
+
+/// An [`OutputId`] with the type of the port checked at construction.
+pub struct TypedOutputId<T: 'static> {
+    id: OutputId,
+    _ph: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> TypedOutputId<T> {
+    /// Create a typed id, return `None` if the type of the port differs.
+    pub fn try_from_id(id: OutputId) -> Option<Self> {
+        if id.port_type_id() == TypeId::of::<T>() {
+            Some(Self { id, _ph: PhantomData })
+        } else {
+            None
+        }
+    }
+
+    pub fn id(&self) -> OutputId {
+        self.id
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.id.node_id()
+    }
+
+    pub fn port_id(&self) -> usize {
+        self.id.port_id()
+    }
+}
+
+impl<T: 'static> Clone for TypedOutputId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for TypedOutputId<T> {}
+
+impl<T: 'static> PartialEq for TypedOutputId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: 'static> Eq for TypedOutputId<T> {}
+
+impl<T: 'static> Hash for TypedOutputId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<T: 'static> fmt::Debug for TypedOutputId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedOutputId").field(&self.id).finish()
+    }
+}
+
+impl<T: 'static> From<TypedOutputId<T>> for OutputId {
+    fn from(id: TypedOutputId<T>) -> Self {
+        id.id
+    }
+}
+
+/// Storage of the values written to the output ports.
+#[derive(Default)]
+pub(in crate::node_graph) struct PortValues {
+    values: HashMap<OutputId, Box<dyn Any + Send + Sync>>,
+}
+
+impl PortValues {
+    pub fn set<T: Any + Send + Sync>(&mut self, output_id: TypedOutputId<T>, value: T) {
+        self.values.insert(output_id.id(), Box::new(value));
+    }
+
+    pub fn get<T: Any>(&self, output_id: TypedOutputId<T>) -> Option<&T> {
+        self.values.get(&output_id.id())?.downcast_ref::<T>()
+    }
+
+    pub fn get_mut<T: Any>(&mut self, output_id: TypedOutputId<T>) -> Option<&mut T> {
+        self.values.get_mut(&output_id.id())?.downcast_mut::<T>()
+    }
+
+    /// Remove the values of all the outputs of a node.
+    pub fn remove_node(&mut self, node_id: NodeId) {
+        self.values.retain(|output_id, _| output_id.node_id() != node_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
@@ -1,12 +1,17 @@
+use crate::node_graph::{Graph, NodeId, PortShape};
 use eframe::epaint::CubicBezierShape;
-use egui::{Color32, Painter, Pos2, Stroke, Vec2};
-use std::f32::consts as f32_consts;
+use egui::{pos2, Color32, Painter, Pos2, Shape, Stroke, Vec2};
+use std::{
+    collections::{HashMap, VecDeque},
+    f32::consts as f32_consts,
+};
 
-pub fn draw_connection(painter: &Painter, start: Pos2, end: Pos2, stroke: Stroke) {
+/// Return the control points of the curve connecting an input (start) and an output (end) port.
+pub fn connection_bezier_points(start: Pos2, end: Pos2) -> Option<[Pos2; 4]> {
     let distance = start.distance(end);
     let half_distance = distance * 0.5;
     if distance < 5. {
-        return;
+        return None;
     }
 
     fn easy_strength(half_distance: f32, strength: f32) -> f32 {
@@ -22,7 +27,144 @@ pub fn draw_connection(painter: &Painter, start: Pos2, end: Pos2, stroke: Stroke
     let end_strength = easy_strength(half_distance, 300.);
     let cp1 = end + Vec2::X * end_strength;
 
-    let bezier = CubicBezierShape::from_points_stroke([start, cp0, cp1, end], false, Color32::TRANSPARENT, stroke);
+    Some([start, cp0, cp1, end])
+}
+
+fn connection_bezier(start: Pos2, end: Pos2, stroke: Stroke) -> Option<CubicBezierShape> {
+    let points = connection_bezier_points(start, end)?;
+    Some(CubicBezierShape::from_points_stroke(
+        points,
+        false,
+        Color32::TRANSPARENT,
+        stroke,
+    ))
+}
+
+pub fn draw_connection(painter: &Painter, start: Pos2, end: Pos2, stroke: Stroke) {
+    if let Some(bezier) = connection_bezier(start, end, stroke) {
+        painter.add(bezier);
+    }
+}
+
+/// Return the point of the curve drawn by [`draw_connection`] at the `t` parameter, 0 is the start and 1 is the end.
+pub fn connection_point(start: Pos2, end: Pos2, t: f32) -> Pos2 {
+    match connection_bezier_points(start, end) {
+        Some([p0, p1, p2, p3]) => {
+            let s = 1. - t;
+            let v = p0.to_vec2() * (s * s * s)
+                + p1.to_vec2() * (3. * s * s * t)
+                + p2.to_vec2() * (3. * s * t * t)
+                + p3.to_vec2() * (t * t * t);
+            v.to_pos2()
+        }
+        None => start + (end - start) * t,
+    }
+}
+
+/// Return the distance of a point from the curve drawn by [`draw_connection`].
+pub fn connection_distance(start: Pos2, end: Pos2, pos: Pos2) -> f32 {
+    if let Some(bezier) = connection_bezier(start, end, Stroke::NONE) {
+        let points = bezier.flatten(Some(1.));
+        points
+            .windows(2)
+            .map(|segment| segment_distance(segment[0], segment[1], pos))
+            .fold(f32::MAX, f32::min)
+    } else {
+        f32::MAX
+    }
+}
+
+fn segment_distance(a: Pos2, b: Pos2, pos: Pos2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_sq();
+    if len_sq <= f32::EPSILON {
+        return a.distance(pos);
+    }
+    let t = ((pos - a).dot(ab) / len_sq).clamp(0., 1.);
+    (a + ab * t).distance(pos)
+}
+
+/// Width of the outline of a hollow port with the given radius.
+pub fn port_outline_width(r: f32) -> f32 {
+    (r * 0.4).max(1.)
+}
 
-    painter.add(bezier);
+/// Return the outline of a port shape with the given radius, `None` for the circle.
+pub fn port_shape_points(shape: PortShape, center: Pos2, r: f32) -> Option<Vec<Pos2>> {
+    match shape {
+        PortShape::Circle => None,
+        PortShape::Square => {
+            let r = r * 0.9;
+            Some(vec![
+                pos2(center.x - r, center.y - r),
+                pos2(center.x + r, center.y - r),
+                pos2(center.x + r, center.y + r),
+                pos2(center.x - r, center.y + r),
+            ])
+        }
+        PortShape::Diamond => Some(vec![
+            pos2(center.x, center.y - r),
+            pos2(center.x + r, center.y),
+            pos2(center.x, center.y + r),
+            pos2(center.x - r, center.y),
+        ]),
+        PortShape::Triangle => Some(vec![
+            pos2(center.x + r, center.y),
+            pos2(center.x - r * 0.5, center.y + r * 0.87),
+            pos2(center.x - r * 0.5, center.y - r * 0.87),
+        ]),
+    }
+}
+
+/// Draw the shape of a port with the given radius. A hollow port is drawn only by its outline.
+pub fn draw_port_shape(painter: &Painter, shape: PortShape, center: Pos2, r: f32, color: Color32, hollow: bool) {
+    let (fill, stroke) = if hollow {
+        (Color32::TRANSPARENT, Stroke::new(port_outline_width(r), color))
+    } else {
+        (color, Stroke::NONE)
+    };
+    // keep the outline inside the area of the filled shape
+    let r = if hollow { r - stroke.width * 0.5 } else { r };
+
+    match port_shape_points(shape, center, r) {
+        Some(points) => {
+            painter.add(Shape::convex_polygon(points, fill, stroke));
+        }
+        None => {
+            painter.circle(center, r, fill, stroke);
+        }
+    }
+}
+
+/// Return the nodes ordered by their connections, a node follows all the nodes it depends on. The order of the
+/// independent nodes follows the order of [`Graph::nodes`]. The nodes on a cycle and their dependents are left out.
+pub fn topological_order(graph: &Graph) -> Vec<NodeId> {
+    let mut dependencies = HashMap::<NodeId, usize>::new();
+    let mut dependents = HashMap::<NodeId, Vec<NodeId>>::new();
+    for connection in graph.connections() {
+        *dependencies.entry(connection.input_node_id()).or_default() += 1;
+        dependents
+            .entry(connection.output_node_id())
+            .or_default()
+            .push(connection.input_node_id());
+    }
+
+    let mut queue: VecDeque<NodeId> = graph
+        .nodes()
+        .map(|node| node.id())
+        .filter(|node_id| !dependencies.contains_key(node_id))
+        .collect();
+    let mut order = Vec::new();
+    while let Some(node_id) = queue.pop_front() {
+        order.push(node_id);
+        for dependent in dependents.get(&node_id).into_iter().flatten() {
+            if let Some(count) = dependencies.get_mut(dependent) {
+                *count -= 1;
+                if *count == 0 {
+                    queue.push_back(*dependent);
+                }
+            }
+        }
+    }
+    order
 }
@@ -0,0 +1,373 @@
+use crate::node_graph::{
+    utils::draw_connection, ConnectionId, Graph, GraphEdit, InputId, InputOutputId, NodeId, OutputId, PortViewState,
+    ZoomPanState,
+};
+use egui::{Color32, Id, LayerId, Order, Response, RichText, ScrollArea, Stroke, Ui};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Severity::Warning => "⚠",
+            Severity::Error => "⊗",
+        }
+    }
+}
+
+/// The element a diagnostic refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticLocation {
+    Graph,
+    Node(NodeId),
+    Port(InputOutputId),
+    Connection(ConnectionId),
+}
+
+impl DiagnosticLocation {
+    /// The node the location belongs to, if any.
+    pub fn node_id(&self) -> Option<NodeId> {
+        match self {
+            DiagnosticLocation::Node(node_id) => Some(*node_id),
+            DiagnosticLocation::Port(port_id) => Some(port_id.node_id()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location: DiagnosticLocation,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new<S: ToString>(severity: Severity, location: DiagnosticLocation, message: S) -> Self {
+        Self {
+            severity,
+            location,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// The result of the validation of a whole graph.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationReport {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Run the built-in checks and the checks of the [`Validator`](crate::node_graph::Validator) of the graph:
+    /// - inputs with too few or too many connections
+    /// - ports without a registered style, they are not shown in the editor
+    /// - connections referring to missing nodes or ports
+    /// - connections closing a cycle
+    pub fn validate(graph: &Graph) -> Self {
+        let mut report = ValidationReport::default();
+        report.check_ports(graph);
+        report.check_connections(graph);
+        report.check_cycles(graph);
+        graph.validator().validate(graph, &mut report);
+        report
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn error<S: ToString>(&mut self, location: DiagnosticLocation, message: S) {
+        self.push(Diagnostic::new(Severity::Error, location, message));
+    }
+
+    pub fn warning<S: ToString>(&mut self, location: DiagnosticLocation, message: S) {
+        self.push(Diagnostic::new(Severity::Warning, location, message));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn find(&self, location: DiagnosticLocation) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(move |d| d.location == location)
+    }
+
+    /// The highest severity of the diagnostics at a location.
+    pub fn severity(&self, location: DiagnosticLocation) -> Option<Severity> {
+        self.find(location).map(|d| d.severity).max()
+    }
+
+    fn check_ports(&mut self, graph: &Graph) {
+        let port_styles = graph.get_port_styles();
+        let mut connection_counts = HashMap::<InputId, usize>::new();
+        for connection in graph.connections() {
+            *connection_counts.entry(connection.input_id()).or_default() += 1;
+        }
+
+        for node in graph.nodes() {
+            let node_id = node.id();
+            for (port_id, input) in node.inputs.iter().enumerate() {
+                let input_id = InputId::new(node_id, input.port_type_id(), port_id);
+                let location = DiagnosticLocation::Port(input_id.into());
+                if port_styles.find(input.port_type_id()).is_none() {
+                    self.error(
+                        location,
+                        format!("Input {} has a type without a port style", input.name),
+                    );
+                }
+                let connection_count = connection_counts.get(&input_id).cloned().unwrap_or(0);
+                if connection_count < input.min_connections() {
+                    if connection_count == 0 {
+                        self.error(location, format!("Required input {} is not connected", input.name));
+                    } else {
+                        self.error(
+                            location,
+                            format!(
+                                "Input {} has {} connections, at least {} is required",
+                                input.name,
+                                connection_count,
+                                input.min_connections()
+                            ),
+                        );
+                    }
+                }
+                if let Some(max_connections) = input.max_connections() {
+                    if connection_count > max_connections {
+                        self.error(
+                            location,
+                            format!(
+                                "Input {} has {} connections, at most {} is allowed",
+                                input.name, connection_count, max_connections
+                            ),
+                        );
+                    }
+                }
+            }
+            for (port_id, output) in node.outputs.iter().enumerate() {
+                let output_id = OutputId::new(node_id, output.port_type_id(), port_id);
+                if port_styles.find(output.port_type_id()).is_none() {
+                    self.error(
+                        DiagnosticLocation::Port(output_id.into()),
+                        format!("Output {} has a type without a port style", output.name),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_connections(&mut self, graph: &Graph) {
+        for connection in graph.connections() {
+            let location = DiagnosticLocation::Connection(connection.id());
+            let input_id = connection.input_id();
+            let output_id = connection.output_id();
+            let input = graph
+                .get_input(input_id)
+                .filter(|input| input.port_type_id() == input_id.port_type_id());
+            let output = graph
+                .get_output(output_id)
+                .filter(|output| output.port_type_id() == output_id.port_type_id());
+            if input.is_none() {
+                self.error(location, "Connection refers to a missing input");
+            }
+            if output.is_none() {
+                self.error(location, "Connection refers to a missing output");
+            }
+        }
+    }
+
+    /// Report the connections closing a cycle found by a depth first search.
+    fn check_cycles(&mut self, graph: &Graph) {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Visit {
+            InProgress,
+            Done,
+        }
+
+        let mut edges = HashMap::<NodeId, Vec<(ConnectionId, NodeId)>>::new();
+        for connection in graph.connections() {
+            edges
+                .entry(connection.output_node_id())
+                .or_default()
+                .push((connection.id(), connection.input_node_id()));
+        }
+
+        let mut visits = HashMap::<NodeId, Visit>::new();
+        for node in graph.nodes() {
+            if visits.contains_key(&node.id()) {
+                continue;
+            }
+            visits.insert(node.id(), Visit::InProgress);
+            let mut stack = vec![(node.id(), 0)];
+            while let Some((node_id, edge_index)) = stack.last().cloned() {
+                match edges.get(&node_id).and_then(|edges| edges.get(edge_index)) {
+                    Some((connection_id, next_id)) => {
+                        stack.last_mut().unwrap().1 += 1;
+                        match visits.get(next_id) {
+                            None => {
+                                visits.insert(*next_id, Visit::InProgress);
+                                stack.push((*next_id, 0));
+                            }
+                            Some(Visit::InProgress) => {
+                                self.error(
+                                    DiagnosticLocation::Connection(*connection_id),
+                                    "Connection closes a cycle",
+                                );
+                            }
+                            Some(Visit::Done) => {}
+                        }
+                    }
+                    None => {
+                        visits.insert(node_id, Visit::Done);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn color(severity: Severity, zoom_pan: &ZoomPanState) -> Color32 {
+        match severity {
+            Severity::Error => zoom_pan.theme.error_color,
+            Severity::Warning => zoom_pan.theme.warning_color,
+        }
+    }
+
+    /// Draw the markers of the nodes, ports and connections with diagnostics.
+    pub(in crate::node_graph) fn show_overlay(
+        &self,
+        ui: &mut Ui,
+        id: Id,
+        zoom_pan: &ZoomPanState,
+        port_visual: &PortViewState,
+        graph: &Graph,
+    ) {
+        let painter = ui
+            .ctx()
+            .layer_painter(LayerId::new(Order::Foreground, id))
+            .with_clip_rect(zoom_pan.screen_rect);
+        let width = 2. * zoom_pan.zoom;
+
+        for diagnostic in &self.diagnostics {
+            let color = Self::color(diagnostic.severity, zoom_pan);
+            match diagnostic.location {
+                DiagnosticLocation::Graph => {}
+                DiagnosticLocation::Node(node_id) => {
+                    if let Some(rect) = port_visual.get_node_rect(node_id) {
+                        painter.rect_stroke(rect.expand(width), 4. * zoom_pan.zoom, Stroke::new(width, color));
+                    }
+                }
+                DiagnosticLocation::Port(port_id) => {
+                    if let Some(pos) = port_visual.get_screen_pos(port_id) {
+                        painter.circle_stroke(pos, 8. * zoom_pan.zoom, Stroke::new(width, color));
+                    }
+                }
+                DiagnosticLocation::Connection(connection_id) => {
+                    let connection = match graph.connection(connection_id) {
+                        Some(connection) => connection,
+                        None => continue,
+                    };
+                    let start = port_visual.get_screen_pos(connection.input_id().into());
+                    let end = port_visual.get_screen_pos(connection.output_id().into());
+                    if let (Some(start), Some(end)) = (start, end) {
+                        draw_connection(&painter, start, end, Stroke::new(width, color));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Show the messages of a location as a list, ex. in a tooltip.
+    pub(in crate::node_graph) fn show_messages(&self, ui: &mut Ui, location: DiagnosticLocation) {
+        for diagnostic in self.find(location) {
+            ui.label(format!("{} {}", diagnostic.severity.symbol(), diagnostic.message));
+        }
+    }
+}
+
+/// A list of the diagnostics of a report. Clicking on an item centers the editor on the element.
+pub struct ProblemsList<'a> {
+    report: &'a ValidationReport,
+    editor_id: Id,
+}
+
+impl<'a> ProblemsList<'a> {
+    /// Create a list for the editor with the given id, see [`GraphEdit::focus`].
+    pub fn new<I: Into<Id>>(report: &'a ValidationReport, editor_id: I) -> Self {
+        Self {
+            report,
+            editor_id: editor_id.into(),
+        }
+    }
+
+    fn location_text(graph: &Graph, location: DiagnosticLocation) -> String {
+        let caption = |node_id: NodeId| graph.node(node_id).map(|node| node.caption.as_str()).unwrap_or("?");
+        match location {
+            DiagnosticLocation::Graph => "graph".to_string(),
+            DiagnosticLocation::Node(node_id) => caption(node_id).to_string(),
+            DiagnosticLocation::Port(port_id) => format!(
+                "{}.{}",
+                caption(port_id.node_id()),
+                graph.port_name(port_id).unwrap_or("?")
+            ),
+            DiagnosticLocation::Connection(connection_id) => match graph.connection(connection_id) {
+                Some(connection) => format!(
+                    "{} → {}",
+                    caption(connection.output_node_id()),
+                    caption(connection.input_node_id())
+                ),
+                None => "connection".to_string(),
+            },
+        }
+    }
+
+    /// Show the list and return the location of the clicked item.
+    pub fn show(self, ui: &mut Ui, graph: &Graph) -> Option<DiagnosticLocation> {
+        let mut clicked = None;
+        if self.report.is_empty() {
+            ui.label("No problems");
+            return None;
+        }
+
+        let theme_colors = (ui.visuals().error_fg_color, ui.visuals().warn_fg_color);
+        ScrollArea::vertical()
+            .id_source(self.editor_id.with("problems"))
+            .show(ui, |ui| {
+                for diagnostic in self.report.diagnostics() {
+                    let color = match diagnostic.severity {
+                        Severity::Error => theme_colors.0,
+                        Severity::Warning => theme_colors.1,
+                    };
+                    let text = format!(
+                        "{} {}: {}",
+                        diagnostic.severity.symbol(),
+                        Self::location_text(graph, diagnostic.location),
+                        diagnostic.message
+                    );
+                    let response: Response = ui.selectable_label(false, RichText::new(text).color(color));
+                    if response.clicked() {
+                        GraphEdit::focus(ui.ctx(), self.editor_id, diagnostic.location);
+                        clicked = Some(diagnostic.location);
+                    }
+                }
+            });
+        clicked
+    }
+}
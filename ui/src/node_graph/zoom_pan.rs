@@ -1,8 +1,32 @@
-use crate::utils::Scale;
-use egui::{pos2, vec2, Id, Pos2, Rect, Style, Ui, Vec2};
+use crate::{node_graph::GraphTheme, utils::Scale};
+use egui::{Id, Pos2, Rect, Stroke, Style, Ui, Vec2};
 use shine_core::collections::HashWithType;
 use std::{any::Any, hash::Hash, sync::Arc};
 
+pub(in crate::node_graph) const MIN_ZOOM: f32 = 0.1;
+pub(in crate::node_graph) const MAX_ZOOM: f32 = 10.;
+pub(in crate::node_graph) const DEFAULT_LOD_ZOOM: f32 = 0.4;
+
+/// Speed of the animations, the remaining distance decays by `exp(-ANIMATION_SPEED * dt)`.
+const ANIMATION_SPEED: f32 = 12.;
+/// Decay of the momentum panning, the velocity decays by `exp(-PAN_FRICTION * dt)`.
+const PAN_FRICTION: f32 = 5.;
+/// Momentum panning stops below this speed (screen units per second).
+const MIN_PAN_SPEED: f32 = 10.;
+/// Width of the band along the border of the screen that scrolls the view while dragging.
+const EDGE_SCROLL_MARGIN: f32 = 32.;
+/// Speed of the edge scroll in screen units per second.
+const EDGE_SCROLL_SPEED: f32 = 600.;
+
+/// A programmatic move of the view in progress.
+#[derive(Clone, Copy, Debug)]
+enum Animation {
+    /// Move to the given pan and zoom.
+    PanZoom { pan: Vec2, zoom: f32 },
+    /// Zoom to the given level keeping the anchor at the same screen location.
+    ZoomAt { anchor: Pos2, zoom: f32 },
+}
+
 #[derive(Clone)]
 pub(in crate::node_graph) struct ZoomPanState {
     pub id: Id,
@@ -12,6 +36,13 @@ pub(in crate::node_graph) struct ZoomPanState {
 
     pub default_style: Arc<Style>,
     pub zoomed_style: Arc<Style>,
+    pub theme: Arc<GraphTheme>,
+    /// Below this zoom the nodes are drawn as simplified boxes.
+    pub lod_zoom: f32,
+
+    animation: Option<Animation>,
+    /// Velocity of the momentum panning in screen units per second.
+    velocity: Vec2,
 }
 
 impl ZoomPanState {
@@ -24,44 +55,48 @@ impl ZoomPanState {
     }
 
     pub fn new(id: Id, ui: &mut Ui) -> Self {
-        Self {
+        let mut zoom_pan = Self {
             id,
             pan: Vec2::ZERO,
             zoom: 1.,
             screen_rect: Rect::NOTHING,
             default_style: ui.style().clone(),
             zoomed_style: ui.style().clone(),
-        }
+            theme: Arc::new(GraphTheme::from_visuals(ui.visuals())),
+            lod_zoom: DEFAULT_LOD_ZOOM,
+            animation: None,
+            velocity: Vec2::ZERO,
+        };
+        zoom_pan.update_zoomed_style();
+        zoom_pan
     }
 
     pub fn child_id<I: Any + Hash>(&self, id: I) -> Id {
         self.id.with(HashWithType(id))
     }
 
-    /*pub fn vec2_area_to_screen(&self, v: Vec2) -> Vec2 {
-        vec2(v.x * self.zoom, v.y * self.zoom)
-    }*/
+    pub fn vec2_area_to_screen(&self, v: Vec2) -> Vec2 {
+        v * self.zoom
+    }
 
+    /// Transform a point from graph (area) coordinates to screen coordinates:
+    /// `screen = screen_rect.min + (area + pan) * zoom`
     pub fn pos2_area_to_screen(&self, p: Pos2) -> Pos2 {
-        let Pos2 { x, y } = p;
-        let x = x + self.screen_rect.left();
-        let y = y + self.screen_rect.top();
-        let x = (x + self.pan.x) * self.zoom;
-        let y = (y + self.pan.y) * self.zoom;
-        pos2(x, y)
+        self.screen_rect.min + (p.to_vec2() + self.pan) * self.zoom
     }
 
     pub fn vec2_screen_to_area(&self, v: Vec2) -> Vec2 {
-        vec2(v.x / self.zoom, v.y / self.zoom)
+        v / self.zoom
     }
 
+    /// The inverse of [`pos2_area_to_screen`](Self::pos2_area_to_screen).
     pub fn pos2_screen_to_area(&self, p: Pos2) -> Pos2 {
-        let Pos2 { x, y } = p;
-        let x = x / self.zoom - self.pan.x;
-        let y = y / self.zoom - self.pan.y;
-        let x = x - self.screen_rect.left();
-        let y = y - self.screen_rect.top();
-        pos2(x, y)
+        ((p - self.screen_rect.min) / self.zoom - self.pan).to_pos2()
+    }
+
+    /// Return if the nodes are drawn as simplified boxes.
+    pub fn is_lod(&self) -> bool {
+        self.zoom < self.lod_zoom
     }
 
     pub fn drag(&mut self, delta: Vec2) {
@@ -69,36 +104,151 @@ impl ZoomPanState {
         self.update(self.pan + delta, self.zoom);
     }
 
+    /// Zoom by the given factor keeping the point under `screen_pos` at the same location.
     pub fn zoom_to_screen(&mut self, screen_pos: Pos2, zoom: f32) {
-        let new_zoom = (self.zoom * zoom).clamp(0.1, 10.);
+        let new_zoom = (self.zoom * zoom).clamp(MIN_ZOOM, MAX_ZOOM);
+
+        // solve screen_pos = screen_rect.min + (area_pos + new_pan) * new_zoom for new_pan
+        let area_pos = self.pos2_screen_to_area(screen_pos);
+        let new_pan = (screen_pos - self.screen_rect.min) / new_zoom - area_pos.to_vec2();
+        self.update(new_pan, new_zoom);
+    }
+
+    /// Calculate the pan and zoom to show the given area in the center of the screen.
+    fn frame_target(&self, area_rect: Rect, margin: f32) -> (Vec2, f32) {
+        let screen_size = self.screen_rect.size() - Vec2::splat(2. * margin);
+        let area_size = area_rect.size().max(Vec2::splat(1.));
+        let new_zoom = (screen_size.x / area_size.x)
+            .min(screen_size.y / area_size.y)
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+
+        let new_pan = (self.screen_rect.center() - self.screen_rect.min) / new_zoom - area_rect.center().to_vec2();
+        (new_pan, new_zoom)
+    }
+
+    /// Start an animated move to show the given area in the center of the screen.
+    pub fn animate_frame(&mut self, area_rect: Rect, margin: f32) {
+        let (pan, zoom) = self.frame_target(area_rect, margin);
+        self.velocity = Vec2::ZERO;
+        self.animation = Some(Animation::PanZoom { pan, zoom });
+    }
+
+    /// Start an animated move to show the given area in the center of the screen. Unlike
+    /// [`animate_frame`](Self::animate_frame), the view is zoomed out only if the area does not fit the screen.
+    pub fn animate_center(&mut self, area_rect: Rect, margin: f32) {
+        let (_, fit_zoom) = self.frame_target(area_rect, margin);
+        let zoom = self.zoom.min(fit_zoom);
+        let pan = (self.screen_rect.center() - self.screen_rect.min) / zoom - area_rect.center().to_vec2();
+        self.velocity = Vec2::ZERO;
+        self.animation = Some(Animation::PanZoom { pan, zoom });
+    }
+
+    /// Start an animated zoom by the given factor keeping the point under `screen_pos` at the same location.
+    /// Consecutive calls accumulate the target zoom.
+    pub fn animate_zoom_to_screen(&mut self, screen_pos: Pos2, zoom: f32) {
+        let current = match self.animation {
+            Some(Animation::ZoomAt { zoom, .. }) => zoom,
+            _ => self.zoom,
+        };
+        self.velocity = Vec2::ZERO;
+        self.animation = Some(Animation::ZoomAt {
+            anchor: screen_pos,
+            zoom: (current * zoom).clamp(MIN_ZOOM, MAX_ZOOM),
+        });
+    }
 
-        // keep the screen_pos remain at the same location
-        // solved for the equations: a2s_pos_zoom(s2a_pre_zoom(screen_pos)) = screen_pos
+    /// Pan by a user drag, the velocity of the drag is kept for the momentum panning.
+    pub fn drag_with_momentum(&mut self, delta: Vec2, dt: f32) {
+        self.animation = None;
+        self.drag(delta);
+        if dt > 0. {
+            // smooth the velocity as the pointer events are not evenly distributed among the frames
+            self.velocity = self.velocity * 0.5 + (delta / dt) * 0.5;
+        }
+    }
+
+    /// Stop the animations and the momentum panning.
+    pub fn stop(&mut self) {
+        self.animation = None;
+        self.velocity = Vec2::ZERO;
+    }
 
-        let test = self.pos2_screen_to_area(screen_pos);
+    /// Advance the animations and the momentum panning. Return true while the view is moving.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        let t = 1. - (-ANIMATION_SPEED * dt).exp();
+        match self.animation {
+            Some(Animation::PanZoom { pan, zoom }) => {
+                // interpolate zoom in log space to have a uniform perceived speed
+                let new_zoom = (self.zoom.ln() + (zoom.ln() - self.zoom.ln()) * t).exp();
+                let new_pan = self.pan + (pan - self.pan) * t;
+                if (new_zoom / zoom - 1.).abs() < 1e-3 && (new_pan - pan).length() * zoom < 0.5 {
+                    self.animation = None;
+                    self.update(pan, zoom);
+                } else {
+                    self.update(new_pan, new_zoom);
+                }
+            }
+            Some(Animation::ZoomAt { anchor, zoom }) => {
+                let new_zoom = (self.zoom.ln() + (zoom.ln() - self.zoom.ln()) * t).exp();
+                if (new_zoom / zoom - 1.).abs() < 1e-3 {
+                    self.animation = None;
+                    self.zoom_to_screen(anchor, zoom / self.zoom);
+                } else {
+                    self.zoom_to_screen(anchor, new_zoom / self.zoom);
+                }
+            }
+            None => {
+                if self.velocity.length() < MIN_PAN_SPEED {
+                    self.velocity = Vec2::ZERO;
+                } else {
+                    self.drag(self.velocity * dt);
+                    self.velocity *= (-PAN_FRICTION * dt).exp();
+                }
+            }
+        }
 
-        let Pos2 { x, y } = screen_pos;
-        let new_pan = vec2(
-            x / new_zoom - x / self.zoom + self.pan.x,
-            y / new_zoom - y / self.zoom + self.pan.y,
+        self.animation.is_some() || self.velocity != Vec2::ZERO
+    }
+
+    /// Scroll the view when the pointer is close to the border of the screen, ex. while dragging a node.
+    /// Return the change of the pan in graph (area) units.
+    pub fn edge_scroll(&mut self, pointer_pos: Pos2, dt: f32) -> Vec2 {
+        let rect = self.screen_rect;
+        let strength = |dist: f32| ((EDGE_SCROLL_MARGIN - dist) / EDGE_SCROLL_MARGIN).clamp(0., 1.);
+        let dir = Vec2::new(
+            strength(pointer_pos.x - rect.left()) - strength(rect.right() - pointer_pos.x),
+            strength(pointer_pos.y - rect.top()) - strength(rect.bottom() - pointer_pos.y),
         );
+        if dir == Vec2::ZERO {
+            return Vec2::ZERO;
+        }
 
-        let err = self.pos2_area_to_screen(test) - screen_pos;
-        assert!(err.x < 1.);
-        assert!(err.y < 1.);
-        self.update(new_pan, new_zoom);
+        self.stop();
+        let delta = self.vec2_screen_to_area(dir * EDGE_SCROLL_SPEED * dt);
+        self.update(self.pan + delta, self.zoom);
+        delta
     }
 
-    pub fn prepare(&mut self, style: &Arc<Style>) {
-        self.default_style = style.clone();
+    pub fn prepare(&mut self, style: &Arc<Style>, theme: &GraphTheme) {
+        if !Arc::ptr_eq(&self.default_style, style) || *self.theme != *theme {
+            self.default_style = style.clone();
+            self.theme = Arc::new(theme.clone());
+            self.update_zoomed_style();
+        }
+    }
+
+    fn update_zoomed_style(&mut self) {
+        let mut style = (*self.default_style).clone();
+        self.theme.apply(&mut style);
+        self.zoomed_style = Arc::new(style.scaled(self.zoom));
     }
 
     pub fn update(&mut self, pan: Vec2, zoom: f32) {
+        self.pan = pan;
         if self.zoom != zoom {
-            self.zoomed_style = Arc::new(self.default_style.scaled(self.zoom));
+            self.zoom = zoom;
+            self.update_zoomed_style();
         }
-        self.pan = pan;
-        self.zoom = zoom;
     }
 
     pub fn show_zoomed<R, F>(&self, ui: &mut Ui, add_content: F) -> R
@@ -115,6 +265,34 @@ impl ZoomPanState {
         response
     }
 
+    /// Draw the background and the grid of the canvas.
+    pub fn draw_background(&self, ui: &mut Ui) {
+        let theme = &self.theme;
+        let painter = ui.painter().with_clip_rect(self.screen_rect);
+        painter.rect_filled(self.screen_rect, 0., theme.background);
+
+        let spacing = theme.grid_spacing * self.zoom;
+        if spacing < 4. {
+            return;
+        }
+        let stroke = Stroke {
+            width: 1.,
+            color: theme.grid_color,
+        };
+        let origin = self.pos2_area_to_screen(Pos2::ZERO);
+        let rect = self.screen_rect;
+        let mut x = rect.left() + (origin.x - rect.left()).rem_euclid(spacing);
+        while x < rect.right() {
+            painter.vline(x, rect.y_range(), stroke);
+            x += spacing;
+        }
+        let mut y = rect.top() + (origin.y - rect.top()).rem_euclid(spacing);
+        while y < rect.bottom() {
+            painter.hline(rect.x_range(), y, stroke);
+            y += spacing;
+        }
+    }
+
     pub fn show_clipped<R, F>(&self, ui: &mut Ui, add_content: F) -> R
     where
         F: FnOnce(&mut Ui) -> R,
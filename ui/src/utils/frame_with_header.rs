@@ -1,15 +1,27 @@
-use egui::{pos2, vec2, Frame, Rect, TextStyle, Ui, Vec2, WidgetText};
+use egui::{
+    collapsing_header::paint_default_icon, pos2, vec2, Color32, Frame, Rect, Rounding, Sense, Shape, TextStyle, Ui,
+    Vec2, WidgetText,
+};
 
 /// Draw a header by reserving it fist and
 pub struct FrameWithHeader {
     title: WidgetText,
     frame: Option<Frame>,
+    collapsed: bool,
+    show_toggle: bool,
+    header_color: Option<Color32>,
 }
 
 impl FrameWithHeader {
     pub fn new<S: Into<WidgetText>>(text: S) -> Self {
         let title = text.into().heading();
-        Self { title, frame: None }
+        Self {
+            title,
+            frame: None,
+            collapsed: false,
+            show_toggle: false,
+            header_color: None,
+        }
     }
 
     pub fn frame(self, frame: Frame) -> Self {
@@ -19,30 +31,69 @@ impl FrameWithHeader {
         }
     }
 
-    pub fn show<F>(self, ui: &mut Ui, add_content: F)
+    /// Show a collapse toggle in the header. When collapsed, only the header is shown.
+    pub fn collapsible(self, collapsed: bool) -> Self {
+        Self {
+            collapsed,
+            show_toggle: true,
+            ..self
+        }
+    }
+
+    /// Show only the header without a collapse toggle.
+    pub fn header_only(self) -> Self {
+        Self {
+            collapsed: true,
+            show_toggle: false,
+            ..self
+        }
+    }
+
+    /// Fill the background of the header.
+    pub fn header_color(self, header_color: Color32) -> Self {
+        Self {
+            header_color: Some(header_color),
+            ..self
+        }
+    }
+
+    /// Show the frame and return if the collapse toggle was clicked.
+    pub fn show<F>(self, ui: &mut Ui, add_content: F) -> bool
     where
         F: FnOnce(&mut Ui),
     {
         let mut title_rect = Rect::NOTHING;
-        self.frame.unwrap_or_default().show(ui, |ui| {
+        let mut toggle_clicked = false;
+        let collapsed = self.collapsed;
+        let frame = self.frame.unwrap_or_default();
+        frame.show(ui, |ui| {
             let top_left = ui.min_rect().min;
+            let header_background = ui.painter().add(Shape::Noop);
 
             let title_galley = self
                 .title
                 .into_galley(ui, Some(false), f32::INFINITY, TextStyle::Heading);
 
-            let (tile_size, top_margin) = {
+            let (tile_size, top_margin, toggle_width) = {
                 let style = ui.style();
                 let size = title_galley.size();
-                let w = size.x;
                 let h = size.y;
                 //let h = self.title.font_height(&ui.ctx().fonts(), style);
+                let toggle_width = if self.show_toggle {
+                    style.spacing.icon_width + style.spacing.icon_spacing
+                } else {
+                    0.
+                };
+                let w = size.x + toggle_width;
                 let top_margin = style.spacing.item_spacing.y * 2.;
-                (vec2(w, h + top_margin), top_margin)
+                (vec2(w, h + top_margin), top_margin, toggle_width)
             };
-            ui.add_space(tile_size.y + top_margin);
-
-            add_content(ui);
+            if collapsed {
+                ui.add_space(tile_size.y);
+            } else {
+                ui.add_space(tile_size.y + top_margin);
+                add_content(ui);
+            }
 
             // make sure header is enclosed in the frame
             let frame_rect = ui.min_rect();
@@ -54,8 +105,43 @@ impl FrameWithHeader {
             title_rect = header_title_rect.union(content_title_rect);
             ui.expand_to_include_rect(title_rect);
 
+            if let Some(header_color) = self.header_color {
+                // when collapsed, the header covers the whole frame
+                let margin = frame.inner_margin;
+                let (bottom, rounding) = if collapsed {
+                    (title_rect.bottom() + margin.bottom, frame.rounding)
+                } else {
+                    let rounding = Rounding {
+                        sw: 0.,
+                        se: 0.,
+                        ..frame.rounding
+                    };
+                    (title_rect.bottom(), rounding)
+                };
+                let header_rect = Rect::from_min_max(
+                    title_rect.min - vec2(margin.left, margin.top),
+                    pos2(title_rect.right() + margin.right, bottom),
+                );
+                ui.painter().set(
+                    header_background,
+                    Shape::rect_filled(header_rect, rounding, header_color),
+                );
+            }
+
+            if self.show_toggle {
+                let icon_width = ui.spacing().icon_width;
+                let toggle_rect = Rect::from_center_size(
+                    pos2(title_rect.left() + icon_width / 2., title_rect.center().y - top_margin),
+                    Vec2::splat(icon_width),
+                );
+                let response = ui.interact(toggle_rect, ui.id().with("collapse"), Sense::click());
+                toggle_clicked = response.clicked();
+                paint_default_icon(ui, if collapsed { 0. } else { 1. }, &response);
+            }
+
             // align header to center
-            let text_pos = emath::align::center_size_in_rect(title_galley.size(), title_rect).left_top();
+            let text_rect = Rect::from_min_max(title_rect.min + toggle_width * Vec2::X, title_rect.max);
+            let text_pos = emath::align::center_size_in_rect(title_galley.size(), text_rect).left_top();
             let text_pos = text_pos - title_galley.galley().rect.min.to_vec2();
             let text_pos = text_pos - top_margin * Vec2::Y; // HACK: center on x-height of text (looks better)
 
@@ -63,7 +149,11 @@ impl FrameWithHeader {
         });
 
         // HACK: moved sperartor here to make it aligned to the frame horizontally.
-        let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
-        ui.painter().hline(ui.min_rect().x_range(), title_rect.bottom(), stroke);
+        if !collapsed {
+            let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+            ui.painter().hline(ui.min_rect().x_range(), title_rect.bottom(), stroke);
+        }
+
+        toggle_clicked
     }
 }
@@ -76,6 +76,7 @@ impl Scale for Style {
         self.spacing.item_spacing.scale(amount);
         self.spacing.window_margin.scale(amount);
         self.spacing.button_padding.scale(amount);
+        self.spacing.menu_margin.scale(amount);
         self.spacing.indent *= amount;
         self.spacing.interact_size.scale(amount);
         self.spacing.slider_width *= amount;
@@ -86,6 +87,8 @@ impl Scale for Style {
         self.spacing.tooltip_width *= amount;
         self.spacing.combo_height *= amount;
         self.spacing.scroll_bar_width *= amount;
+        self.spacing.scroll_bar_inner_margin *= amount;
+        self.spacing.scroll_bar_outer_margin *= amount;
 
         self.interaction.resize_grab_radius_side *= amount;
         self.interaction.resize_grab_radius_corner *= amount;
@@ -102,6 +105,7 @@ impl Scale for Style {
         self.visuals.text_cursor_width *= amount;
         self.visuals.clip_rect_margin *= amount;
         self.visuals.window_rounding.scale(amount);
+        self.visuals.window_stroke.scale(amount);
         self.visuals.window_shadow.scale(amount);
         self.visuals.popup_shadow.scale(amount);
     }
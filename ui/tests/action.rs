@@ -0,0 +1,67 @@
+use egui::{Key, Modifiers};
+use shine_ui::node_graph::{ActionId, KeyChord, KeyMap, KeyMapError};
+
+#[test]
+fn bind_rejects_a_chord_of_another_action() {
+    let mut keymap = KeyMap::default();
+    let chord = keymap.chord(&ActionId::Delete).unwrap();
+
+    match keymap.bind(ActionId::custom("erase"), chord) {
+        Err(KeyMapError::Conflict {
+            chord: conflict,
+            action,
+        }) => {
+            assert_eq!(conflict, chord);
+            assert_eq!(action, ActionId::Delete);
+        }
+        result => panic!("Conflict expected, got {:?}", result),
+    }
+    assert_eq!(keymap.chord(&ActionId::custom("erase")), None);
+    assert_eq!(keymap.action(&chord), Some(&ActionId::Delete));
+    // binding the same chord again is not a conflict
+    keymap.bind(ActionId::Delete, chord).unwrap();
+}
+
+#[test]
+fn rebinding_replaces_the_chord() {
+    let mut keymap = KeyMap::default();
+    let old_chord = keymap.chord(&ActionId::Frame).unwrap();
+    let new_chord = KeyChord::new(Modifiers::SHIFT, Key::F);
+
+    keymap.bind(ActionId::Frame, new_chord).unwrap();
+    assert_eq!(keymap.chord(&ActionId::Frame), Some(new_chord));
+    assert_eq!(keymap.action(&old_chord), None);
+    assert_eq!(keymap.action(&new_chord), Some(&ActionId::Frame));
+    assert_eq!(keymap.bindings().len(), KeyMap::default().bindings().len());
+}
+
+#[test]
+fn keymap_is_serialized() {
+    let mut keymap = KeyMap::default();
+    keymap.bind(ActionId::custom("run"), KeyChord::command(Key::R)).unwrap();
+
+    let json = serde_json::to_string(&keymap).unwrap();
+    let loaded: KeyMap = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.bindings(), keymap.bindings());
+    assert!(loaded.conflicts().is_empty());
+}
+
+#[test]
+fn conflicts_of_a_loaded_keymap_are_reported() {
+    let keymap = KeyMap::default();
+    let delete = keymap.chord(&ActionId::Delete).unwrap();
+
+    // an edited file binding the chord of delete to frame too
+    let mut json = serde_json::to_value(&keymap).unwrap();
+    for binding in json["bindings"].as_array_mut().unwrap() {
+        if binding["action"] == "Frame" {
+            binding["chord"] = serde_json::to_value(delete).unwrap();
+        }
+    }
+    let loaded: KeyMap = serde_json::from_value(json).unwrap();
+
+    assert_eq!(
+        loaded.conflicts(),
+        vec![(delete, vec![ActionId::Delete, ActionId::Frame])]
+    );
+}
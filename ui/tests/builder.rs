@@ -0,0 +1,53 @@
+use egui::pos2;
+use shine_ui::{
+    build_graph,
+    node_graph::{Graph, Input},
+};
+
+#[test]
+fn builder_creates_nodes_and_connections() {
+    let mut graph = Graph::default();
+    let mut builder = graph.builder();
+    let a = builder.node("constant").at(pos2(0., 0.)).output::<f32>("x").build();
+    let b = builder
+        .node("add")
+        .kind("math.add")
+        .at((200., 0.))
+        .input::<f32>("a")
+        .with_input(Input::new::<f32>("b").with_default(1f32))
+        .output::<f32>("sum")
+        .build();
+    assert!(builder.connect(a.output("x"), b.input("a")).is_some());
+    assert!(builder.connect(a.output("x"), b.input("a")).is_none());
+
+    let node = graph.node(b.id()).unwrap();
+    assert_eq!(node.kind, "math.add");
+    assert_eq!(node.location, pos2(200., 0.));
+    assert_eq!(node.find_input_id("b"), Some(b.input("b")));
+    assert_eq!(graph.connections().count(), 1);
+    assert_eq!(graph.input_value(b.typed_input::<f32>("b")), Some(&1.));
+}
+
+#[test]
+#[should_panic(expected = "Node has no input named c")]
+fn missing_port_panics() {
+    let mut graph = Graph::default();
+    let node = graph.builder().node("add").input::<f32>("a").build();
+    node.input("c");
+}
+
+#[test]
+fn macro_declares_the_graph() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        source = "source" at (100., 100.), inputs [], outputs [value: u8];
+        sink = "sink" at (450., 300.), inputs [value: u8, other: u16], outputs [];
+        source.value -> sink.value;
+    }
+    assert_eq!(graph.nodes().count(), 2);
+    assert_eq!(graph.node(sink.id()).unwrap().inputs.len(), 2);
+    assert!(graph
+        .find_connections(sink.input("value"), source.output("value"))
+        .is_some());
+}
@@ -0,0 +1,166 @@
+mod harness;
+
+use harness::u32_graph;
+use shine_ui::{
+    build_graph,
+    node_graph::{CodeGenerator, CodegenBackend, CodegenError, Graph, HasProperties, Input, NodeData, SnippetContext},
+};
+
+struct Constant(u32);
+
+impl HasProperties for Constant {}
+
+impl NodeData for Constant {
+    fn content_hash(&self) -> Option<u64> {
+        Some(self.0 as u64)
+    }
+}
+
+/// A tiny expression language: `one` and `constant` are literals, `add` sums its inputs and `print` is a result.
+struct TestBackend;
+
+impl CodegenBackend for TestBackend {
+    fn emit_node(&self, context: &SnippetContext<'_>) -> Option<String> {
+        let node = context.node();
+        match node.kind.as_str() {
+            "one" => Some(format!("let {} = 1;", context.output(0)?)),
+            "constant" => Some(format!(
+                "let {} = {};",
+                context.output(0)?,
+                node.data_as::<Constant>().0
+            )),
+            "add" => {
+                let mut terms = context.inputs(0).to_vec();
+                terms.extend(context.input(1).map(|offset| offset.to_string()));
+                Some(format!("let {} = {};", context.output(0)?, terms.join(" + ")))
+            }
+            "print" => Some(format!("print({});", context.input(0)?)),
+            _ => None,
+        }
+    }
+
+    fn emit_literal(&self, input: &Input) -> Option<String> {
+        input.default_as::<u32>().map(|value| value.to_string())
+    }
+
+    fn prologue(&self, _graph: &Graph) -> String {
+        "fn main() {\n".to_string()
+    }
+
+    fn epilogue(&self, _graph: &Graph) -> String {
+        "}\n".to_string()
+    }
+}
+
+#[test]
+fn code_follows_the_connections() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        print = "print" at (600., 0.), inputs [value: u32], outputs [];
+        sum = "add" at (300., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        a.value -> sum.values;
+        a.value -> sum.offset;
+        sum.sum -> print.value;
+    }
+
+    let code = CodeGenerator::new(&TestBackend).generate(&graph).unwrap();
+    assert_eq!(
+        code.source,
+        "fn main() {\nlet v0 = 1;\nlet v1 = v0 + v0;\nprint(v1);\n}\n"
+    );
+    assert_eq!(code.nodes, vec![a.id(), sum.id(), print.id()]);
+    assert_eq!(code.variables.get(&sum.output("sum")).map(|v| v.as_str()), Some("v1"));
+}
+
+#[test]
+fn unused_nodes_are_eliminated() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        unused = "add" at (300., 100.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        print = "print" at (600., 0.), inputs [value: u32], outputs [];
+        a.value -> unused.values;
+        a.value -> print.value;
+    }
+
+    let code = CodeGenerator::new(&TestBackend).generate(&graph).unwrap();
+    assert_eq!(code.nodes, vec![a.id(), print.id()]);
+
+    let code = CodeGenerator::new(&TestBackend)
+        .with_dead_node_elimination(false)
+        .generate(&graph)
+        .unwrap();
+    assert_eq!(code.nodes, vec![a.id(), unused.id(), print.id()]);
+}
+
+#[test]
+fn identical_nodes_are_shared() {
+    let mut graph = u32_graph();
+    let mut builder = graph.builder();
+    let constants: Vec<_> = [2, 2, 3]
+        .into_iter()
+        .map(|value| {
+            builder
+                .node("constant")
+                .with_data(Constant(value))
+                .output::<u32>("value")
+                .build()
+        })
+        .collect();
+    let sums: Vec<_> = constants
+        .iter()
+        .map(|constant| {
+            let sum = builder
+                .node("add")
+                .input::<u32>("values")
+                .with_input(Input::new::<u32>("offset").with_default(1u32))
+                .output::<u32>("sum")
+                .build();
+            builder.connect(constant.output("value"), sum.input("values")).unwrap();
+            sum
+        })
+        .collect();
+    for sum in &sums {
+        let print = builder.node("print").input::<u32>("value").build();
+        builder.connect(sum.output("sum"), print.input("value")).unwrap();
+    }
+
+    let code = CodeGenerator::new(&TestBackend).generate(&graph).unwrap();
+    assert_eq!(
+        code.source,
+        "fn main() {\nlet v0 = 2;\nlet v1 = 3;\nlet v2 = v0 + 1;\nlet v3 = v1 + 1;\nprint(v2);\nprint(v2);\nprint(v3);\n}\n"
+    );
+    assert_eq!(
+        code.variables.get(&sums[1].output("sum")),
+        code.variables.get(&sums[0].output("sum"))
+    );
+
+    let code = CodeGenerator::new(&TestBackend)
+        .with_shared_expressions(false)
+        .generate(&graph)
+        .unwrap();
+    assert_eq!(code.nodes.len(), 9);
+}
+
+#[test]
+fn unsupported_node_is_rejected() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        unknown = "unknown" at (0., 0.), inputs [], outputs [value: u32];
+        print = "print" at (300., 0.), inputs [value: u32], outputs [];
+        unknown.value -> print.value;
+    }
+
+    match CodeGenerator::new(&TestBackend).generate(&graph) {
+        Err(CodegenError::UnsupportedNode { node_id, kind }) => {
+            assert_eq!(node_id, unknown.id());
+            assert_eq!(kind, "unknown");
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+    assert!(graph.node(print.id()).is_some());
+}
@@ -0,0 +1,173 @@
+mod harness;
+
+use egui::Context;
+use harness::source_sink_graph;
+use shine_ui::node_graph::{
+    ActionId, ActionMenuItem, ConextMenuBuilder, ContextMenu, ContextMenuContext, ContextMenuData, ContextMenuProvider,
+    ContextMenuTarget, Graph, InteractionMode, KeyMap, NodeId,
+};
+
+/// Toggle the lock of a node.
+struct LockItem(NodeId);
+
+impl ContextMenuData for LockItem {
+    fn on_select(&self, graph: &mut Graph, _context: &ContextMenuContext) {
+        if let Some(node) = graph.node_mut(self.0) {
+            node.locked = !node.locked;
+        }
+    }
+
+    fn is_enabled(&self, _graph: &Graph, context: &ContextMenuContext) -> bool {
+        context.interaction.allows_layout()
+    }
+
+    fn is_checked(&self, graph: &Graph, _context: &ContextMenuContext) -> Option<bool> {
+        graph.node(self.0).map(|node| node.locked)
+    }
+}
+
+/// Shown only if there is a selection.
+struct ClearSelectionItem;
+
+impl ContextMenuData for ClearSelectionItem {
+    fn on_select(&self, _graph: &mut Graph, _context: &ContextMenuContext) {}
+
+    fn is_visible(&self, _graph: &Graph, context: &ContextMenuContext) -> bool {
+        !context.selection.is_empty()
+    }
+
+    fn shortcut(&self) -> Option<String> {
+        Some("Esc".to_string())
+    }
+}
+
+struct TargetMenu;
+
+impl ContextMenuProvider for TargetMenu {
+    fn build(&self, _graph: &Graph, context: &ContextMenuContext, builder: &mut ConextMenuBuilder<'_>) {
+        match context.target {
+            ContextMenuTarget::Node(node_id) => {
+                builder
+                    .add_item("delete", ActionMenuItem(ActionId::Delete))
+                    .add_item("locked", LockItem(node_id));
+            }
+            ContextMenuTarget::Canvas => {
+                builder
+                    .add_item("select all", ActionMenuItem(ActionId::SelectAll))
+                    .add_item("clear selection", ClearSelectionItem);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The visible items of the menu built for the context with their enabled and checked state.
+fn visible_items(menu: &ContextMenu, graph: &Graph, context: &ContextMenuContext) -> Vec<(String, bool, Option<bool>)> {
+    let mut items: Vec<_> = menu
+        .build_dynamic(graph, context)
+        .items()
+        .filter(|item| item.data().is_visible(graph, context))
+        .map(|item| {
+            let data = item.data();
+            (
+                item.name.clone(),
+                data.is_enabled(graph, context),
+                data.is_checked(graph, context),
+            )
+        })
+        .collect();
+    items.sort();
+    items
+}
+
+fn menu() -> ContextMenu {
+    let mut menu = ContextMenu::default();
+    menu.add_provider(TargetMenu);
+    menu
+}
+
+#[test]
+fn node_menu_depends_on_the_node_and_the_interaction() {
+    let (mut graph, source, _) = source_sink_graph();
+    graph.node_mut(source).unwrap().locked = true;
+    let menu = menu();
+
+    let mut context = ContextMenuContext {
+        target: ContextMenuTarget::Node(source),
+        ..Default::default()
+    };
+    assert_eq!(
+        visible_items(&menu, &graph, &context),
+        vec![
+            ("delete".to_string(), true, None),
+            ("locked".to_string(), true, Some(true))
+        ]
+    );
+
+    context.interaction = InteractionMode::WiringOnly;
+    assert_eq!(
+        visible_items(&menu, &graph, &context),
+        vec![
+            ("delete".to_string(), false, None),
+            ("locked".to_string(), false, Some(true))
+        ]
+    );
+}
+
+#[test]
+fn canvas_menu_depends_on_the_selection() {
+    let (graph, source, _) = source_sink_graph();
+    let menu = menu();
+
+    let mut context = ContextMenuContext {
+        interaction: InteractionMode::ReadOnly,
+        ..Default::default()
+    };
+    assert_eq!(
+        visible_items(&menu, &graph, &context),
+        vec![("select all".to_string(), true, None)]
+    );
+
+    context.selection = vec![source];
+    assert_eq!(
+        visible_items(&menu, &graph, &context),
+        vec![
+            ("clear selection".to_string(), true, None),
+            ("select all".to_string(), true, None)
+        ]
+    );
+
+    context.target = ContextMenuTarget::Connection(Default::default());
+    assert!(visible_items(&menu, &graph, &context).is_empty());
+}
+
+#[test]
+fn shortcut_labels_come_from_the_item_or_the_keymap() {
+    let (graph, source, _) = source_sink_graph();
+    let ctx = Context::default();
+    let mut keymap = KeyMap::default();
+    let menu = menu();
+    let shortcut = |keymap: &KeyMap, target: ContextMenuTarget, name: &str| {
+        let context = ContextMenuContext {
+            target,
+            selection: vec![source],
+            ..Default::default()
+        };
+        let dynamic = menu.build_dynamic(&graph, &context);
+        let item = dynamic.items().find(|item| item.name == name).unwrap();
+        item.shortcut_text(keymap, &ctx)
+    };
+
+    let delete = keymap.format(&ActionId::Delete, &ctx);
+    assert!(delete.is_some());
+    assert_eq!(shortcut(&keymap, ContextMenuTarget::Node(source), "delete"), delete);
+    assert_eq!(shortcut(&keymap, ContextMenuTarget::Node(source), "locked"), None);
+    assert_eq!(
+        shortcut(&keymap, ContextMenuTarget::Canvas, "clear selection"),
+        Some("Esc".to_string())
+    );
+
+    // the label follows the rebound chord
+    keymap.unbind(&ActionId::Delete);
+    assert_eq!(shortcut(&keymap, ContextMenuTarget::Node(source), "delete"), None);
+}
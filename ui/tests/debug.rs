@@ -0,0 +1,141 @@
+mod harness;
+
+use harness::{create_task, u32_graph};
+use shine_ui::{
+    build_graph,
+    node_graph::{ExecutionTrace, GraphEvaluator, NodeStatus, TraceEventKind},
+};
+
+#[test]
+fn breakpoint_pauses_until_resumed() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "inc" at (200., 0.), inputs [value: u32], outputs [value: u32];
+        a.value -> b.value;
+    }
+
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.set_breakpoint(b.id(), true);
+    evaluator.start(&graph, &create_task);
+    evaluator.poll();
+    assert_eq!(evaluator.status(a.id()), Some(&NodeStatus::Done));
+    assert_eq!(evaluator.status(b.id()), Some(&NodeStatus::Paused));
+    assert!(evaluator.is_paused());
+    assert!(evaluator.is_running());
+
+    evaluator.resume();
+    evaluator.poll();
+    assert_eq!(evaluator.status(b.id()), Some(&NodeStatus::Done));
+    assert_eq!(evaluator.value_text(b.output("value")), Some("2".to_string()));
+    assert!(!evaluator.is_running());
+}
+
+#[test]
+fn stepping_runs_one_node_at_a_time() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "inc" at (200., 0.), inputs [value: u32], outputs [value: u32];
+        c = "inc" at (400., 0.), inputs [value: u32], outputs [value: u32];
+        a.value -> b.value;
+        b.value -> c.value;
+    }
+
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.set_stepping(true);
+    evaluator.start(&graph, &create_task);
+    evaluator.poll();
+    assert_eq!(evaluator.status(a.id()), Some(&NodeStatus::Paused));
+
+    let expected = [
+        (NodeStatus::Done, NodeStatus::Paused, NodeStatus::Pending),
+        (NodeStatus::Done, NodeStatus::Done, NodeStatus::Paused),
+        (NodeStatus::Done, NodeStatus::Done, NodeStatus::Done),
+    ];
+    for (a_status, b_status, c_status) in expected {
+        evaluator.step();
+        evaluator.poll();
+        assert_eq!(evaluator.status(a.id()), Some(&a_status));
+        assert_eq!(evaluator.status(b.id()), Some(&b_status));
+        assert_eq!(evaluator.status(c.id()), Some(&c_status));
+    }
+    assert_eq!(evaluator.value_text(c.output("value")), Some("3".to_string()));
+}
+
+#[test]
+fn recorded_trace_is_replayed() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "inc" at (200., 0.), inputs [value: u32], outputs [value: u32];
+        a.value -> b.value;
+    }
+
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.set_recording(true);
+    evaluator.start(&graph, &create_task);
+    evaluator.poll();
+
+    let trace = evaluator.trace().unwrap();
+    assert_eq!(trace.nodes().len(), 2);
+    // running, output and done of each node
+    assert_eq!(trace.len(), 6);
+    let trace = ExecutionTrace::from_json(&trace.to_json().unwrap()).unwrap();
+    assert_eq!(
+        trace.events()[1].kind,
+        TraceEventKind::Output {
+            port_id: 0,
+            value: Some("1".to_string())
+        }
+    );
+
+    let mut player = GraphEvaluator::new(0);
+    player.replay(&graph, &trace, 3);
+    assert_eq!(player.status(a.id()), Some(&NodeStatus::Done));
+    assert_eq!(player.status(b.id()), None);
+    assert_eq!(player.value_text(a.output("value")), Some("1".to_string()));
+
+    player.replay(&graph, &trace, trace.len());
+    assert_eq!(player.status(b.id()), Some(&NodeStatus::Done));
+    assert_eq!(player.value_text(b.output("value")), Some("2".to_string()));
+}
+
+#[test]
+fn replay_skips_the_events_of_other_nodes() {
+    let build = |first: &str, second: &str| {
+        let mut graph = u32_graph();
+        let a = graph.builder().node(first).output::<u32>("value").build();
+        let b = graph
+            .builder()
+            .node(second)
+            .input::<u32>("value")
+            .output::<u32>("value")
+            .build();
+        graph.builder().connect(a.output("value"), b.input("value")).unwrap();
+        (graph, a, b)
+    };
+
+    let (graph, ..) = build("one", "inc");
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.set_recording(true);
+    evaluator.start(&graph, &create_task);
+    evaluator.poll();
+    let trace = evaluator.trace().unwrap().clone();
+
+    // the same document loaded in a new session
+    let (loaded, a, b) = build("one", "inc");
+    let mut player = GraphEvaluator::new(0);
+    assert_eq!(player.replay(&loaded, &trace, trace.len()), 0);
+    assert_eq!(player.status(b.id()), Some(&NodeStatus::Done));
+    assert_eq!(player.value_text(b.output("value")), Some("2".to_string()));
+
+    // a different document reusing the node ids
+    let (other, ..) = build("inc", "one");
+    assert_eq!(player.replay(&other, &trace, trace.len()), trace.len());
+    assert_eq!(player.status(a.id()), None);
+    assert_eq!(player.value_text(a.output("value")), None);
+}
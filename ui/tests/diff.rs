@@ -0,0 +1,192 @@
+use egui::pos2;
+use shine_ui::{
+    build_graph,
+    node_graph::{
+        ConnectionChange, Graph, GraphDiff, GraphMerge, HasProperties, Input, MergeConflict, NodeChange, NodeData,
+        NodeHandle,
+    },
+};
+
+/// Node data without a content hash.
+struct Opaque;
+
+impl HasProperties for Opaque {}
+
+impl NodeData for Opaque {}
+
+/// A chain of three nodes, the same sequence of insertions creates the same ids in each snapshot.
+fn snapshot() -> (Graph, NodeHandle, NodeHandle, NodeHandle) {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        a = "a" at (0., 0.), inputs [], outputs [value: u8];
+        b = "b" at (200., 0.), inputs [value: u8], outputs [value: u8];
+        c = "c" at (400., 0.), inputs [value: u8], outputs [];
+        a.value -> b.value;
+        b.value -> c.value;
+    }
+    (graph, a, b, c)
+}
+
+#[test]
+fn node_and_connection_changes_are_reported() {
+    let (old, ..) = snapshot();
+    let (mut new, a, b, c) = snapshot();
+    assert!(GraphDiff::new(&old, &new).is_empty());
+
+    new.node_mut(a.id()).unwrap().location = pos2(0., 100.);
+    new.node_mut(b.id()).unwrap().caption = "renamed".to_string();
+    new.remove_node(c.id());
+    let d = new.builder().node("d").input::<u8>("value").build();
+    new.builder().connect(a.output("value"), d.input("value")).unwrap();
+
+    let diff = GraphDiff::new(&old, &new);
+    assert_eq!(
+        diff.node_change(a.id()),
+        Some(NodeChange::Modified {
+            moved: true,
+            changed: false
+        })
+    );
+    assert!(diff.node_change(b.id()).unwrap().is_changed());
+    assert_eq!(diff.node_change(c.id()), Some(NodeChange::Removed));
+    assert_eq!(diff.node_change(d.id()), Some(NodeChange::Added));
+    assert_eq!(
+        diff.connection_change(c.input("value"), b.output("value")),
+        Some(ConnectionChange::Removed)
+    );
+    assert_eq!(
+        diff.connection_change(d.input("value"), a.output("value")),
+        Some(ConnectionChange::Added)
+    );
+    assert_eq!(diff.connection_change(b.input("value"), a.output("value")), None);
+}
+
+#[test]
+fn data_without_hash_is_unknown() {
+    let opaque = || {
+        let mut graph = Graph::default();
+        let node = graph
+            .builder()
+            .node("opaque")
+            .output::<u8>("value")
+            .with_data(Opaque)
+            .build();
+        (graph, node)
+    };
+    let (old, a) = opaque();
+    let (new, _) = opaque();
+
+    let diff = GraphDiff::new(&old, &new);
+    assert_eq!(diff.node_change(a.id()), Some(NodeChange::Unknown { moved: false }));
+    assert!(!diff.node_change(a.id()).unwrap().is_changed());
+    assert_eq!(diff.nodes().count(), 1);
+}
+
+#[test]
+fn concurrent_edits_are_conflicts() {
+    let (base, ..) = snapshot();
+    let (mut ours, a, b, c) = snapshot();
+    let (mut theirs, ..) = snapshot();
+
+    ours.node_mut(a.id()).unwrap().caption = "ours".to_string();
+    theirs.node_mut(a.id()).unwrap().caption = "theirs".to_string();
+    // the same change on both sides is not a conflict
+    ours.node_mut(b.id()).unwrap().location = pos2(200., 100.);
+    theirs.node_mut(b.id()).unwrap().location = pos2(200., 100.);
+    ours.remove_node(c.id());
+    let connection = theirs.find_connections(c.input("value"), b.output("value")).unwrap();
+    theirs.remove_connection(connection);
+    theirs.builder().connect(a.output("value"), c.input("value")).unwrap();
+
+    let merge = GraphMerge::new(&base, &ours, &theirs);
+    let mut conflicts = merge.conflicts().to_vec();
+    conflicts.sort_by_key(|conflict| matches!(conflict, MergeConflict::Connection(..)));
+    assert_eq!(
+        conflicts,
+        vec![
+            MergeConflict::Node(a.id()),
+            MergeConflict::Connection(c.input("value"), a.output("value"))
+        ]
+    );
+
+    merge.apply(&mut ours, &theirs);
+    assert_eq!(ours.node(a.id()).unwrap().caption, "ours");
+    assert!(ours.node(c.id()).is_none());
+    assert_eq!(ours.connections().count(), 1);
+}
+
+#[test]
+fn apply_merges_the_changes_of_theirs() {
+    let (base, ..) = snapshot();
+    let (mut ours, a, b, c) = snapshot();
+    let (mut theirs, ..) = snapshot();
+
+    ours.node_mut(c.id()).unwrap().location = pos2(400., 100.);
+    theirs.node_mut(a.id()).unwrap().location = pos2(0., 100.);
+    // the type of the input changes, the connection of ours to the input becomes invalid
+    theirs.node_mut(b.id()).unwrap().inputs[0] = Input::new::<u16>("value");
+    let d = theirs.builder().node("d").at((600., 0.)).input::<u8>("value").build();
+    theirs.builder().connect(a.output("value"), d.input("value")).unwrap();
+
+    let merge = GraphMerge::new(&base, &ours, &theirs);
+    assert!(!merge.has_conflicts());
+    let id_map = merge.apply(&mut ours, &theirs);
+
+    assert_eq!(ours.node(a.id()).unwrap().location, pos2(0., 100.));
+    assert_eq!(ours.node(c.id()).unwrap().location, pos2(400., 100.));
+    assert!(ours.find_connections(b.input("value"), a.output("value")).is_none());
+    // b -> c of ours and a -> d of theirs are kept
+    assert_eq!(ours.connections().count(), 2);
+
+    let new_d = id_map[&d.id()];
+    assert_eq!(ours.node(new_d).unwrap().caption, "d");
+    assert!(ours
+        .connections()
+        .any(|connection| connection.input_node_id() == new_d && connection.output_node_id() == a.id()));
+}
+
+#[test]
+fn nodes_added_on_both_sides_are_kept_apart() {
+    let (base, ..) = snapshot();
+    let (mut ours, a, ..) = snapshot();
+    let (mut theirs, ..) = snapshot();
+
+    let our_node = ours.builder().node("ours").input::<u8>("value").build();
+    let their_node = theirs.builder().node("theirs").input::<u8>("value").build();
+    let opaque = theirs
+        .builder()
+        .node("opaque")
+        .input::<u8>("value")
+        .with_data(Opaque)
+        .build();
+    // the two sides allocate the same id for their first new node
+    assert_eq!(our_node.id(), their_node.id());
+    theirs
+        .builder()
+        .connect(a.output("value"), their_node.input("value"))
+        .unwrap();
+    theirs
+        .builder()
+        .connect(a.output("value"), opaque.input("value"))
+        .unwrap();
+
+    let merge = GraphMerge::new(&base, &ours, &theirs);
+    assert_eq!(merge.conflicts(), &[MergeConflict::NotDuplicable(opaque.id())]);
+    let id_map = merge.apply(&mut ours, &theirs);
+
+    assert!(!id_map.contains_key(&opaque.id()));
+    let new_node = id_map[&their_node.id()];
+    assert_ne!(new_node, our_node.id());
+    assert_eq!(ours.node(new_node).unwrap().caption, "theirs");
+    assert_eq!(ours.nodes().count(), 5);
+    // only the connection of the copied node is added, nothing is attached to the node of "ours"
+    let added: Vec<_> = ours
+        .connections()
+        .filter(|connection| connection.output_node_id() == a.id() && connection.input_node_id() != a.id())
+        .map(|connection| connection.input_node_id())
+        .collect();
+    assert!(added.contains(&new_node));
+    assert!(!added.contains(&our_node.id()));
+    assert_eq!(ours.connections().count(), 3);
+}
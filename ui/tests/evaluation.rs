@@ -0,0 +1,157 @@
+mod harness;
+
+use egui::Color32;
+use harness::create_task;
+use shine_ui::{
+    build_graph,
+    node_graph::{Graph, GraphEvaluator, NodeStatus, PortStyle, PortStyles, PortValueSource, ValuePreview},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+fn wait_for(evaluator: &mut GraphEvaluator, condition: impl Fn(&GraphEvaluator) -> bool) {
+    let start = Instant::now();
+    loop {
+        evaluator.poll();
+        if condition(evaluator) {
+            return;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "Evaluation has not finished");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn independent_branches_are_merged() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "one" at (0., 100.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 50.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        total = "add" at (400., 50.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a.value -> sum.values;
+        b.value -> sum.values;
+        sum.sum -> total.values;
+        a.value -> total.offset;
+    }
+
+    let mut evaluator = GraphEvaluator::new(4);
+    evaluator.start(&graph, &create_task);
+    wait_for(&mut evaluator, |evaluator| !evaluator.is_running());
+
+    assert_eq!(evaluator.status(sum.id()), Some(&NodeStatus::Done));
+    assert_eq!(evaluator.output_value(sum.typed_output::<u32>("sum")), Some(&2));
+    assert_eq!(evaluator.output_value(total.typed_output::<u32>("sum")), Some(&3));
+}
+
+#[test]
+fn failure_skips_the_dependents() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        source = "fail" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        source.value -> sum.values;
+    }
+
+    let mut evaluator = GraphEvaluator::new(2);
+    evaluator.start(&graph, &create_task);
+    wait_for(&mut evaluator, |evaluator| !evaluator.is_running());
+
+    assert_eq!(
+        evaluator.status(source.id()),
+        Some(&NodeStatus::Failed("no value".to_string()))
+    );
+    assert_eq!(evaluator.status(sum.id()), Some(&NodeStatus::Skipped));
+    assert!(evaluator.value(sum.output("sum")).is_none());
+}
+
+#[test]
+fn restart_cancels_the_running_evaluation() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        source = "wait" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        source.value -> sum.values;
+    }
+
+    let mut evaluator = GraphEvaluator::new(2);
+    evaluator.start(&graph, &create_task);
+    wait_for(&mut evaluator, |evaluator| {
+        matches!(evaluator.status(source.id()), Some(NodeStatus::Running(_)))
+    });
+
+    evaluator.cancel();
+    assert_eq!(evaluator.status(source.id()), Some(&NodeStatus::Cancelled));
+    assert_eq!(evaluator.status(sum.id()), Some(&NodeStatus::Cancelled));
+    assert!(!evaluator.is_running());
+
+    // the events of the cancelled run are ignored
+    graph.remove_node(source.id());
+    evaluator.start(&graph, &create_task);
+    wait_for(&mut evaluator, |evaluator| !evaluator.is_running());
+    assert_eq!(evaluator.status(source.id()), None);
+    assert_eq!(evaluator.output_value(sum.typed_output::<u32>("sum")), Some(&0));
+}
+
+#[test]
+fn tasks_run_in_poll_without_threads() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a.value -> sum.values;
+        a.value -> sum.offset;
+    }
+
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.start(&graph, &create_task);
+    assert!(evaluator.is_running());
+    assert!(evaluator.poll());
+    assert!(!evaluator.is_running());
+    assert_eq!(evaluator.output_value(sum.typed_output::<u32>("sum")), Some(&2));
+}
+
+#[test]
+fn port_style_preview_is_shown_in_tooltips() {
+    let mut port_styles = PortStyles::default();
+    port_styles.set::<u32>(PortStyle::new("u32").with_preview(|value: &u32| ValuePreview::text(value)));
+    let mut graph = Graph::default();
+    graph.set_port_styles(port_styles);
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a.value -> sum.values;
+    }
+
+    let style = graph.get_port_styles().get::<u32>().unwrap();
+    assert_eq!(style.preview(&7u32), Some(ValuePreview::Text("7".to_string())));
+    assert_eq!(style.preview(&7u8), None);
+    assert_eq!(style.display(&7u32), Some("7".to_string()));
+
+    // the display takes precedence over the text preview
+    let style = PortStyle::new("u32")
+        .with_preview(|value: &u32| ValuePreview::Color(Color32::from_gray(*value as u8)))
+        .with_display::<u32>();
+    assert_eq!(style.display(&7u32), Some("7".to_string()));
+    assert_eq!(style.display(&7u8), None);
+
+    let mut evaluator = GraphEvaluator::new(0);
+    evaluator.start(&graph, &create_task);
+    evaluator.poll();
+    assert_eq!(
+        evaluator.port_value(&graph, sum.input("values").into()),
+        Some("1".to_string())
+    );
+    assert_eq!(
+        evaluator.port_value(&graph, sum.output("sum").into()),
+        Some("1".to_string())
+    );
+    assert_eq!(evaluator.port_value(&graph, sum.input("offset").into()), None);
+}
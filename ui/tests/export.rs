@@ -0,0 +1,81 @@
+use egui::Color32;
+use shine_ui::{
+    build_graph,
+    node_graph::{Graph, GraphExport, PortShape, PortStyle, PortStyles},
+};
+
+/// A source with an `u8` and an `u16` output and a sink with the matching inputs, only the `u8` ports are
+/// connected.
+fn graph() -> Graph {
+    let mut port_styles = PortStyles::default();
+    port_styles.set::<u8>(PortStyle::new("u8").with_color(Color32::from_rgb(255, 0, 0)));
+    port_styles.set::<u16>(
+        PortStyle::new("u16")
+            .with_color(Color32::from_rgb(0, 0, 255))
+            .with_shape(PortShape::Triangle)
+            .with_hollow_unconnected(true),
+    );
+    let mut graph = Graph::default();
+    graph.set_port_styles(port_styles);
+    build_graph! {
+        graph;
+        source = "source" at (0., 0.), inputs [], outputs [a: u8, b: u16];
+        sink = "sink" at (200., 50.), inputs [a: u8, b: u16], outputs [];
+        source.a -> sink.a;
+    }
+    graph
+}
+
+#[test]
+fn dot_golden() {
+    let graph = graph();
+    let expected = r##"digraph {
+    rankdir=LR;
+    bgcolor="#141414";
+    node [shape=plaintext, fontcolor="#c8c8c8"];
+    n0 [label=<<table border="1" cellborder="0" cellspacing="0" color="#3c3c3c" bgcolor="#232323"><tr><td colspan="2" bgcolor="#232323"><b>source</b></td></tr><tr><td></td><td port="o0" align="right">a<font color="#ff0000"> ●</font></td></tr><tr><td></td><td port="o1" align="right">b<font color="#0000ff"> ●</font></td></tr></table>>, kind="source", caption="source", pos="0,0"];
+    n1 [label=<<table border="1" cellborder="0" cellspacing="0" color="#3c3c3c" bgcolor="#232323"><tr><td colspan="2" bgcolor="#232323"><b>sink</b></td></tr><tr><td port="i0" align="left"><font color="#ff0000">● </font>a</td><td></td></tr><tr><td port="i1" align="left"><font color="#0000ff">● </font>b</td><td></td></tr></table>>, kind="sink", caption="sink", pos="200,50"];
+    n0:o0:e -> n1:i0:w [color="#ff0000"];
+}
+"##;
+    assert_eq!(GraphExport::new(&graph).to_dot(), expected);
+}
+
+#[test]
+fn mermaid_golden() {
+    let graph = graph();
+    let expected = r##"flowchart LR
+    n0["<b>source</b><br/>out: a, b"]
+    n1["<b>sink</b><br/>in: a, b"]
+    n0 -- "a → a" --> n1
+    style n0 fill:#232323,stroke:#3c3c3c,color:#c8c8c8
+    style n1 fill:#232323,stroke:#3c3c3c,color:#c8c8c8
+    linkStyle 0 stroke:#ff0000
+"##;
+    assert_eq!(GraphExport::new(&graph).to_mermaid(), expected);
+}
+
+#[test]
+fn svg_golden() {
+    let graph = graph();
+    let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-40 -40 360 202" width="360" height="202" font-family="sans-serif" font-size="14">
+<rect x="-40" y="-40" width="360" height="202" fill="#141414"/>
+<rect x="0" y="0" width="80" height="72" rx="4" fill="#232323" stroke="#3c3c3c" stroke-width="1"/>
+<line x1="0" y1="24" x2="80" y2="24" stroke="#3c3c3c"/>
+<text x="40" y="12" fill="#c8c8c8" font-weight="bold" text-anchor="middle" dominant-baseline="central">source</text>
+<text x="72" y="34" fill="#c8c8c8" text-anchor="end" dominant-baseline="central">a</text>
+<circle cx="80" cy="34" r="5" fill="#ff0000" fill-opacity="1"/>
+<text x="72" y="54" fill="#c8c8c8" text-anchor="end" dominant-baseline="central">b</text>
+<polygon points="84,54 78,57.48 78,50.52" fill="none" stroke="#0000ff" stroke-opacity="1" stroke-width="2"/>
+<rect x="200" y="50" width="80" height="72" rx="4" fill="#232323" stroke="#3c3c3c" stroke-width="1"/>
+<line x1="200" y1="74" x2="280" y2="74" stroke="#3c3c3c"/>
+<text x="240" y="62" fill="#c8c8c8" font-weight="bold" text-anchor="middle" dominant-baseline="central">sink</text>
+<text x="208" y="84" fill="#c8c8c8" dominant-baseline="central">a</text>
+<circle cx="200" cy="84" r="5" fill="#ff0000" fill-opacity="1"/>
+<text x="208" y="104" fill="#c8c8c8" dominant-baseline="central">b</text>
+<polygon points="204,104 198,107.48 198,100.52" fill="none" stroke="#0000ff" stroke-opacity="1" stroke-width="2"/>
+<path d="M 200 84 C 114.735985 84, 180.14206 34, 80 34" fill="none" stroke="#ff0000" stroke-opacity="1" stroke-width="3"/>
+</svg>
+"##;
+    assert_eq!(GraphExport::new(&graph).to_svg(), expected);
+}
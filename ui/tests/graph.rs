@@ -0,0 +1,46 @@
+mod harness;
+
+use egui::vec2;
+use harness::source_sink_graph;
+
+#[test]
+fn removed_connection_can_be_recreated() {
+    let (mut graph, source, sink) = source_sink_graph();
+    let output_id = graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = graph.node(sink).unwrap().input_id(0).unwrap();
+
+    let connection = graph.try_create_connection(input_id, output_id).unwrap();
+    let connection_id = graph.add_connection(connection);
+    assert!(graph.try_create_connection(input_id, output_id).is_none());
+
+    graph.remove_connection(connection_id);
+    assert_eq!(graph.find_connections(input_id, output_id), None);
+    let connection = graph.try_create_connection(input_id, output_id).unwrap();
+    graph.add_connection(connection);
+    assert_eq!(graph.connections().count(), 1);
+}
+
+#[test]
+fn removed_node_releases_the_connected_ports() {
+    let (mut graph, source, sink) = source_sink_graph();
+    let output_id = graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = graph.node(sink).unwrap().input_id(0).unwrap();
+    let connection = graph.try_create_connection(input_id, output_id).unwrap();
+    graph.add_connection(connection);
+
+    // removing the node on the input side shall clear the connection from the lookup too
+    let duplicate = graph.duplicate_nodes(&[sink], vec2(0., 100.))[0];
+    let duplicate_input_id = graph.node(duplicate).unwrap().input_id(0).unwrap();
+    let connection = graph.try_create_connection(duplicate_input_id, output_id).unwrap();
+    graph.add_connection(connection);
+    graph.remove_node(duplicate);
+    assert_eq!(graph.find_connections(duplicate_input_id, output_id), None);
+
+    // removing the node on the output side
+    graph.remove_node(source);
+    assert_eq!(graph.connections().count(), 0);
+    assert_eq!(graph.find_connections(input_id, output_id), None);
+
+    graph.clear();
+    assert_eq!(graph.find_connections(input_id, output_id), None);
+}
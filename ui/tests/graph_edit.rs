@@ -0,0 +1,232 @@
+mod harness;
+
+use egui::{pos2, vec2, Key, Modifiers, PointerButton, Ui};
+use harness::{source_sink_graph, Harness};
+use shine_ui::node_graph::{
+    EditorMode, HasProperties, Input, InputPortData, InteractionMode, Node, Output, OutputPortData, PortStyle,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Port data reporting a change in the first frame it is shown.
+struct ChangeOnce(bool);
+
+impl HasProperties for ChangeOnce {}
+
+impl InputPortData for ChangeOnce {
+    fn show(&mut self, _ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        !std::mem::replace(&mut self.0, true)
+    }
+}
+
+/// Port data recording if its editor was enabled in the last frame.
+struct EnabledProbe(Arc<AtomicBool>);
+
+impl HasProperties for EnabledProbe {}
+
+impl InputPortData for EnabledProbe {
+    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        self.0.store(ui.is_enabled(), Ordering::Relaxed);
+        false
+    }
+}
+
+impl OutputPortData for EnabledProbe {
+    fn show(&mut self, ui: &mut Ui, _port_id: usize, _style: &PortStyle) -> bool {
+        self.0.store(ui.is_enabled(), Ordering::Relaxed);
+        false
+    }
+}
+
+#[test]
+fn click_selects_node_and_canvas_clears_selection() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+
+    harness.click(harness.node_header(source));
+    assert!(harness.view().selection().contains(source));
+    assert!(!harness.view().selection().contains(sink));
+
+    harness.click(harness.node_header(sink));
+    assert_eq!(harness.view().selection().to_vec(), vec![sink]);
+
+    let empty = harness.view().area_to_screen(egui::pos2(20., 500.));
+    harness.click(empty);
+    assert!(harness.view().selection().is_empty());
+}
+
+#[test]
+fn views_with_a_shared_selection_follow_each_other() {
+    let (graph, source, _) = source_sink_graph();
+    let right = egui::Id::new("second view");
+    let mut harness = Harness::new(graph).with_second_view(right);
+    let left = harness.id();
+    assert!(harness.node_rect_of(left, source).right() < harness.node_rect_of(right, source).left());
+
+    // the other view picks up the selection in the next frame
+    harness.click(harness.node_header_of(left, source));
+    harness.step();
+    assert_eq!(harness.selection.to_vec(), vec![source]);
+    assert_eq!(harness.view_of(right).selection().to_vec(), vec![source]);
+
+    let empty = harness.view_of(right).area_to_screen(pos2(20., 500.));
+    harness.click(empty);
+    harness.step();
+    assert!(harness.selection.is_empty());
+    assert!(harness.view_of(left).selection().is_empty());
+}
+
+#[test]
+fn drag_moves_node() {
+    let (graph, source, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let start_location = harness.graph.node(source).unwrap().location;
+
+    let from = harness.node_header(source);
+    harness.drag_to(from, from + vec2(100., 50.), PointerButton::Primary);
+    assert_eq!(harness.view().mode(), EditorMode::NodeInteract);
+    harness.release(PointerButton::Primary);
+    harness.move_to(egui::pos2(790., 590.));
+    assert_eq!(harness.view().mode(), EditorMode::None);
+
+    let moved = harness.graph.node(source).unwrap().location - start_location;
+    assert!((moved - vec2(100., 50.)).length() < 1., "moved by {:?}", moved);
+}
+
+#[test]
+fn read_only_node_is_not_moved() {
+    let (graph, source, _) = source_sink_graph();
+    let mut harness = Harness::new(graph).with_interaction(InteractionMode::ReadOnly);
+    let start_location = harness.graph.node(source).unwrap().location;
+
+    let from = harness.node_header(source);
+    harness.drag(from, from + vec2(100., 50.), PointerButton::Primary);
+    assert_eq!(harness.graph.node(source).unwrap().location, start_location);
+}
+
+#[test]
+fn read_only_port_data_is_not_editable() {
+    for (interaction, enabled) in [(InteractionMode::Full, true), (InteractionMode::ReadOnly, false)] {
+        let (mut graph, _, _) = source_sink_graph();
+        let input_enabled = Arc::new(AtomicBool::new(!enabled));
+        let output_enabled = Arc::new(AtomicBool::new(!enabled));
+        graph.add_node(Node::new(
+            "probe",
+            pos2(100., 400.),
+            vec![Input::new::<u8>("value").with(EnabledProbe(input_enabled.clone()))],
+            vec![Output::new::<u8>("value").with(EnabledProbe(output_enabled.clone()))],
+        ));
+
+        let mut harness = Harness::new(graph).with_interaction(interaction);
+        harness.step();
+        assert_eq!(input_enabled.load(Ordering::Relaxed), enabled, "{:?}", interaction);
+        assert_eq!(output_enabled.load(Ordering::Relaxed), enabled, "{:?}", interaction);
+    }
+}
+
+#[test]
+fn edits_in_the_editor_change_the_revision() {
+    let (mut graph, source, _) = source_sink_graph();
+    let changed = graph.add_node(Node::new(
+        "changed",
+        pos2(100., 400.),
+        vec![Input::new::<u8>("value").with(ChangeOnce(true))],
+        vec![],
+    ));
+    let mut harness = Harness::new(graph);
+    let revision = harness.graph.revision();
+    harness.run_frames(3);
+    assert_eq!(harness.graph.revision(), revision);
+
+    harness.graph.node_mut(changed).unwrap().inputs[0]
+        .data_mut_as::<ChangeOnce>()
+        .0 = false;
+    harness.step();
+    assert!(harness.graph.revision() > revision);
+
+    let revision = harness.graph.revision();
+    let from = harness.node_header(source);
+    harness.drag(from, from + vec2(50., 0.), PointerButton::Primary);
+    assert!(harness.graph.revision() > revision);
+}
+
+#[test]
+fn drag_from_output_to_input_connects_ports() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let output_id = harness.graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = harness.graph.node(sink).unwrap().input_id(0).unwrap();
+
+    let from = harness.view().port_pos(output_id).unwrap();
+    let to = harness.view().port_pos(input_id).unwrap();
+    harness.drag_to(from, to, PointerButton::Primary);
+    assert_eq!(harness.view().mode(), EditorMode::EditConnection);
+    harness.release(PointerButton::Primary);
+    harness.step();
+
+    assert_eq!(harness.view().mode(), EditorMode::None);
+    assert!(harness.graph.find_connections(input_id, output_id).is_some());
+}
+
+#[test]
+fn connection_dropped_on_canvas_is_discarded() {
+    let (graph, source, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let output_id = harness.graph.node(source).unwrap().output_id(0).unwrap();
+
+    let from = harness.view().port_pos(output_id).unwrap();
+    harness.drag(from, from + vec2(150., 150.), PointerButton::Primary);
+    harness.step();
+
+    assert_eq!(harness.view().mode(), EditorMode::None);
+    assert_eq!(harness.graph.connections().count(), 0);
+}
+
+#[test]
+fn escape_cancels_connection_edit() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let output_id = harness.graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = harness.graph.node(sink).unwrap().input_id(0).unwrap();
+
+    let from = harness.view().port_pos(output_id).unwrap();
+    let to = harness.view().port_pos(input_id).unwrap();
+    harness.drag_to(from, to, PointerButton::Primary);
+    harness.key(Key::Escape, Modifiers::NONE);
+    assert_eq!(harness.view().mode(), EditorMode::None);
+    harness.release(PointerButton::Primary);
+    harness.step();
+
+    assert_eq!(harness.graph.connections().count(), 0);
+}
+
+#[test]
+fn wiring_is_disabled_in_layout_only_mode() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph).with_interaction(InteractionMode::LayoutOnly);
+    let output_id = harness.graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = harness.graph.node(sink).unwrap().input_id(0).unwrap();
+
+    let from = harness.view().port_pos(output_id).unwrap();
+    let to = harness.view().port_pos(input_id).unwrap();
+    harness.drag(from, to, PointerButton::Primary);
+    harness.step();
+
+    assert_ne!(harness.view().mode(), EditorMode::EditConnection);
+    assert_eq!(harness.graph.connections().count(), 0);
+}
+
+#[test]
+fn delete_removes_selected_nodes() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+
+    harness.click(harness.node_header(source));
+    harness.key(Key::Delete, Modifiers::NONE);
+
+    assert!(harness.graph.node(source).is_none());
+    assert!(harness.graph.node(sink).is_some());
+    assert!(harness.view().selection().is_empty());
+}
@@ -0,0 +1,291 @@
+//! Headless driver of the graph editor. The editor is shown in an `egui::Context` without a window, the input is
+//! injected as raw events and the frames are advanced by a fixed time step.
+#![allow(dead_code)]
+
+use egui::{
+    pos2, vec2, CentralPanel, Context, Event, Frame, Id, Key, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2,
+};
+use shine_ui::node_graph::{
+    ContextMenu, Graph, GraphEdit, GraphEditView, Input, InteractionMode, Node, NodeId, NodeTask, Output, PortStyle,
+    PortStyles, Selection, TaskContext,
+};
+use std::{thread, time::Duration};
+
+pub const SCREEN_SIZE: Vec2 = vec2(800., 600.);
+const FRAME_DT: f32 = 1. / 60.;
+/// Upper limit of the frames to wait for the animations to finish.
+const MAX_SETTLE_FRAMES: usize = 600;
+/// Length of a pointer move in a frame while dragging.
+const DRAG_STEP: f32 = 10.;
+
+pub struct Harness {
+    pub ctx: Context,
+    pub graph: Graph,
+    pub context_menu: ContextMenu,
+    pub interaction: InteractionMode,
+    /// The selection shared by the views when a second view is shown, see [`with_second_view`](Self::with_second_view).
+    pub selection: Selection,
+    id: Id,
+    second_id: Option<Id>,
+    time: f64,
+    events: Vec<Event>,
+    modifiers: Modifiers,
+    pointer: Pos2,
+    needs_repaint: bool,
+}
+
+impl Harness {
+    pub fn new(graph: Graph) -> Self {
+        let mut harness = Self {
+            ctx: Context::default(),
+            graph,
+            context_menu: ContextMenu::default(),
+            interaction: InteractionMode::Full,
+            selection: Selection::default(),
+            id: Id::new("graph edit"),
+            second_id: None,
+            time: 0.,
+            events: Vec::new(),
+            modifiers: Modifiers::NONE,
+            pointer: Pos2::ZERO,
+            needs_repaint: false,
+        };
+        // the areas of the nodes are sized in the first frames
+        harness.run_frames(2);
+        harness
+    }
+
+    pub fn with_interaction(mut self, interaction: InteractionMode) -> Self {
+        self.interaction = interaction;
+        self
+    }
+
+    /// Split the screen and show a second editor with the given id on the right half. The two views show the same
+    /// graph and share the [`selection`](Self::selection) of the harness.
+    pub fn with_second_view<I: Into<Id>>(mut self, id: I) -> Self {
+        self.second_id = Some(id.into());
+        self.run_frames(2);
+        self
+    }
+
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn view(&self) -> GraphEditView {
+        self.view_of(self.id)
+    }
+
+    pub fn view_of(&self, id: Id) -> GraphEditView {
+        GraphEditView::load(&self.ctx, id).expect("Editor shall be shown before inspecting its state")
+    }
+
+    pub fn node_rect(&self, node_id: NodeId) -> Rect {
+        self.node_rect_of(self.id, node_id)
+    }
+
+    pub fn node_rect_of(&self, id: Id, node_id: NodeId) -> Rect {
+        self.view_of(id).node_rect(node_id).expect("Node shall be drawn")
+    }
+
+    /// A point on the header of a node that is not covered by a port or a widget.
+    pub fn node_header(&self, node_id: NodeId) -> Pos2 {
+        self.node_header_of(self.id, node_id)
+    }
+
+    pub fn node_header_of(&self, id: Id, node_id: NodeId) -> Pos2 {
+        let rect = self.node_rect_of(id, node_id);
+        pos2(rect.center().x, rect.top() + 8.)
+    }
+
+    /// Run a frame with the queued input events.
+    pub fn step(&mut self) {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, SCREEN_SIZE)),
+            time: Some(self.time),
+            predicted_dt: FRAME_DT,
+            modifiers: self.modifiers,
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+
+        let Self {
+            ctx,
+            graph,
+            context_menu,
+            interaction,
+            selection,
+            id,
+            second_id,
+            ..
+        } = self;
+        let output = ctx.run(input, |ctx| {
+            CentralPanel::default()
+                .frame(Frame::none())
+                .show(ctx, |ui| match second_id {
+                    Some(second_id) => ui.columns(2, |columns| {
+                        for (column, id) in columns.iter_mut().zip([*id, *second_id]) {
+                            GraphEdit::new(id, graph, context_menu)
+                                .with_interaction(*interaction)
+                                .with_selection(selection)
+                                .show(column);
+                        }
+                    }),
+                    None => GraphEdit::new(*id, graph, context_menu)
+                        .with_interaction(*interaction)
+                        .show(ui),
+                });
+        });
+        self.needs_repaint = output.repaint_after == Duration::ZERO;
+        self.time += FRAME_DT as f64;
+    }
+
+    pub fn run_frames(&mut self, count: usize) {
+        for _ in 0..count {
+            self.step();
+        }
+    }
+
+    /// Run frames until the animations are finished.
+    pub fn settle(&mut self) {
+        for _ in 0..MAX_SETTLE_FRAMES {
+            self.step();
+            if !self.needs_repaint {
+                return;
+            }
+        }
+        panic!("Editor is still animating after {} frames", MAX_SETTLE_FRAMES);
+    }
+
+    pub fn move_to(&mut self, pos: Pos2) {
+        self.pointer = pos;
+        self.events.push(Event::PointerMoved(pos));
+        self.step();
+    }
+
+    pub fn press(&mut self, button: PointerButton) {
+        self.events.push(Event::PointerButton {
+            pos: self.pointer,
+            button,
+            pressed: true,
+            modifiers: self.modifiers,
+        });
+        self.step();
+    }
+
+    pub fn release(&mut self, button: PointerButton) {
+        self.events.push(Event::PointerButton {
+            pos: self.pointer,
+            button,
+            pressed: false,
+            modifiers: self.modifiers,
+        });
+        self.step();
+    }
+
+    pub fn click(&mut self, pos: Pos2) {
+        self.move_to(pos);
+        self.press(PointerButton::Primary);
+        self.release(PointerButton::Primary);
+    }
+
+    /// Press the button at `from` and move the pointer to `to` in small steps, one frame each.
+    /// The button is kept pressed, see [`drag`](Self::drag) for the complete gesture.
+    pub fn drag_to(&mut self, from: Pos2, to: Pos2, button: PointerButton) {
+        self.move_to(from);
+        self.press(button);
+        let steps = ((to - from).length() / DRAG_STEP).ceil().max(1.) as usize;
+        for i in 1..=steps {
+            self.move_to(from + (to - from) * (i as f32 / steps as f32));
+        }
+    }
+
+    pub fn drag(&mut self, from: Pos2, to: Pos2, button: PointerButton) {
+        self.drag_to(from, to, button);
+        self.release(button);
+    }
+
+    pub fn scroll(&mut self, pos: Pos2, delta: Vec2) {
+        self.move_to(pos);
+        self.events.push(Event::Scroll(delta));
+        self.step();
+    }
+
+    pub fn pinch(&mut self, pos: Pos2, zoom: f32) {
+        self.move_to(pos);
+        self.events.push(Event::Zoom(zoom));
+        self.step();
+    }
+
+    pub fn key(&mut self, key: Key, modifiers: Modifiers) {
+        for pressed in [true, false] {
+            self.events.push(Event::Key {
+                key,
+                pressed,
+                modifiers,
+            });
+        }
+        self.step();
+    }
+}
+
+/// A graph with a source node having an `u8` output and a sink node having an `u8` input.
+pub fn source_sink_graph() -> (Graph, NodeId, NodeId) {
+    let mut port_styles = PortStyles::default();
+    port_styles.set::<u8>(PortStyle::new("u8"));
+
+    let mut graph = Graph::default();
+    graph.set_port_styles(port_styles);
+    let source = graph.add_node(Node::new(
+        "source",
+        pos2(100., 100.),
+        vec![],
+        vec![Output::new::<u8>("value")],
+    ));
+    let sink = graph.add_node(Node::new(
+        "sink",
+        pos2(450., 300.),
+        vec![Input::new::<u8>("value")],
+        vec![],
+    ));
+    (graph, source, sink)
+}
+
+/// An empty graph with a style for the `u32` ports, the values are displayed as text.
+pub fn u32_graph() -> Graph {
+    let mut port_styles = PortStyles::default();
+    port_styles.set::<u32>(PortStyle::new("u32").with_display::<u32>());
+    let mut graph = Graph::default();
+    graph.set_port_styles(port_styles);
+    graph
+}
+
+/// Tasks of the test graphs: `one` outputs 1, `inc` adds one to its input, `add` sums its inputs, `fail` fails
+/// and `wait` runs until cancelled.
+pub fn create_task(node: &Node) -> Option<Box<dyn NodeTask>> {
+    match node.kind.as_str() {
+        "one" => Some(Box::new(|context: &mut TaskContext| {
+            context.set_output(0, 1u32);
+            Ok(())
+        })),
+        "inc" => Some(Box::new(|context: &mut TaskContext| {
+            let value = context.input::<u32>(0).cloned().unwrap_or(0);
+            context.set_output(0, value + 1);
+            Ok(())
+        })),
+        "add" => Some(Box::new(|context: &mut TaskContext| {
+            let sum = context.inputs::<u32>(0).sum::<u32>() + context.input::<u32>(1).cloned().unwrap_or(0);
+            context.set_progress(0.5);
+            context.set_output(0, sum);
+            Ok(())
+        })),
+        "fail" => Some(Box::new(|_: &mut TaskContext| Err("no value".to_string()))),
+        "wait" => Some(Box::new(|context: &mut TaskContext| {
+            while !context.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            Ok(())
+        })),
+        _ => None,
+    }
+}
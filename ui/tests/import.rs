@@ -0,0 +1,220 @@
+mod harness;
+
+use egui::pos2;
+use harness::source_sink_graph;
+use shine_ui::node_graph::{Graph, GraphExport, GraphImport, GraphImportError, Input, Node, Output};
+
+/// Create the nodes of the source-sink graph by their kind.
+fn create_node(kind: &str) -> Option<Node> {
+    match kind {
+        "source" => Some(Node::new(kind, pos2(0., 0.), vec![], vec![Output::new::<u8>("value")])),
+        "sink" => Some(Node::new(kind, pos2(0., 0.), vec![Input::new::<u8>("value")], vec![])),
+        "single" => Some(Node::new(
+            kind,
+            pos2(0., 0.),
+            vec![Input::new::<u8>("value").with_connection_limits(0, Some(1))],
+            vec![],
+        )),
+        "empty" => Some(Node::new(kind, pos2(0., 0.), vec![], vec![])),
+        _ => None,
+    }
+}
+
+/// An empty graph with the port styles of the source-sink graph.
+fn empty_graph() -> Graph {
+    let (source_graph, ..) = source_sink_graph();
+    let mut graph = Graph::default();
+    graph.set_port_styles(source_graph.get_port_styles().clone());
+    graph
+}
+
+#[test]
+fn dot_export_can_be_imported() {
+    let (mut graph, source, sink) = source_sink_graph();
+    let output_id = graph.node(source).unwrap().output_id(0).unwrap();
+    let input_id = graph.node(sink).unwrap().input_id(0).unwrap();
+    let connection = graph.try_create_connection(input_id, output_id).unwrap();
+    graph.add_connection(connection);
+    let dot = GraphExport::new(&graph).to_dot();
+
+    let mut imported = empty_graph();
+    let node_ids = GraphImport::new(&create_node).from_dot(&mut imported, &dot).unwrap();
+    assert_eq!(node_ids.len(), 2);
+    for node in graph.nodes() {
+        let copy = imported
+            .nodes()
+            .find(|copy| copy.kind == node.kind)
+            .expect("Node shall be imported");
+        assert_eq!(copy.caption, node.caption);
+        assert_eq!(copy.location, node.location);
+    }
+
+    let connection = imported.connections().next().expect("Connection shall be imported");
+    assert_eq!(imported.node(connection.output_node_id()).unwrap().kind, "source");
+    assert_eq!(imported.node(connection.input_node_id()).unwrap().kind, "sink");
+    assert_eq!(imported.connections().count(), 1);
+}
+
+#[test]
+fn dot_export_keeps_the_captions() {
+    let mut graph = empty_graph();
+    let source = graph
+        .builder()
+        .node("Renamed source")
+        .kind("source")
+        .at((10., 20.))
+        .output::<u8>("value")
+        .build();
+    let sink = graph.builder().node("sink").input::<u8>("value").build();
+    graph
+        .builder()
+        .connect(source.output("value"), sink.input("value"))
+        .unwrap();
+    let dot = GraphExport::new(&graph).to_dot();
+
+    let mut imported = empty_graph();
+    GraphImport::new(&create_node).from_dot(&mut imported, &dot).unwrap();
+    let copy = imported.nodes().find(|node| node.kind == "source").unwrap();
+    assert_eq!(copy.caption, "Renamed source");
+    assert_eq!(copy.location, pos2(10., 20.));
+    assert_eq!(imported.connections().count(), 1);
+}
+
+#[test]
+fn dot_export_escapes_the_strings() {
+    let caption = r#"a "quoted" \ caption\"#;
+    let mut graph = empty_graph();
+    graph.builder().node(caption).kind("empty").build();
+    let dot = GraphExport::new(&graph).to_dot();
+    assert!(dot.contains(r#"caption="a \"quoted\" \\ caption\\""#), "{}", dot);
+
+    let mut imported = empty_graph();
+    GraphImport::new(&create_node).from_dot(&mut imported, &dot).unwrap();
+    assert_eq!(imported.nodes().next().unwrap().caption, caption);
+}
+
+#[test]
+fn dot_ports_are_added_by_type_name() {
+    let dot = r#"
+        digraph {
+            // ports of the document extend the nodes of the factory
+            a [kind=empty, label="A", pos="10,20", outputs="out:u8"];
+            b [kind=sink];
+            a:out -> b:value;
+        }"#;
+    let mut graph = empty_graph();
+    let node_ids = GraphImport::new(&create_node).from_dot(&mut graph, dot).unwrap();
+
+    let a = graph.node(node_ids["a"]).unwrap();
+    assert_eq!(a.caption, "A");
+    assert_eq!(a.location, pos2(10., 20.));
+    assert_eq!(a.outputs[0].name, "out");
+    assert_eq!(graph.connections().count(), 1);
+}
+
+#[test]
+fn json_is_imported() {
+    let json = r#"{
+        "nodes": [
+            { "id": "a", "kind": "empty", "caption": "A", "location": [10, 20],
+              "outputs": [{ "name": "out", "type": "u8" }] },
+            { "id": "b", "kind": "sink" }
+        ],
+        "edges": [{ "from": "a", "output": "out", "to": "b", "input": "value" }]
+    }"#;
+    let mut graph = empty_graph();
+    let node_ids = GraphImport::new(&create_node).from_json(&mut graph, json).unwrap();
+
+    let a = graph.node(node_ids["a"]).unwrap();
+    assert_eq!(a.caption, "A");
+    assert_eq!(a.location, pos2(10., 20.));
+    assert_eq!(graph.node(node_ids["b"]).unwrap().kind, "sink");
+    assert_eq!(graph.connections().count(), 1);
+}
+
+#[test]
+fn port_without_type_is_a_syntax_error() {
+    let dot = "digraph {\n    a [kind=empty,\n       outputs=\"out\"];\n}";
+    let mut graph = empty_graph();
+    match GraphImport::new(&create_node).from_dot(&mut graph, dot) {
+        Err(GraphImportError::Syntax { line, message }) => {
+            assert_eq!(line, 3);
+            assert!(message.ends_with(": out"), "{}", message);
+        }
+        result => panic!("Syntax error expected, got {:?}", result),
+    }
+}
+
+#[test]
+fn malformed_dot_is_rejected() {
+    let import = |dot: &str| {
+        let mut graph = empty_graph();
+        let result = GraphImport::new(&create_node).from_dot(&mut graph, dot);
+        assert_eq!(graph.nodes().count(), 0);
+        result.unwrap_err()
+    };
+
+    assert!(matches!(import("graph"), GraphImportError::Syntax { line: 1, .. }));
+    assert!(matches!(
+        import("digraph { a [label=\"x]; }"),
+        GraphImportError::Syntax { line: 1, .. }
+    ));
+    assert!(matches!(
+        import("digraph {\n a -> b;\n}"),
+        GraphImportError::Syntax { line: 2, .. }
+    ));
+    assert!(matches!(
+        import("digraph { a [kind=source; }"),
+        GraphImportError::Syntax { .. }
+    ));
+    assert!(matches!(
+        import("digraph { a [kind=other]; }"),
+        GraphImportError::UnknownNodeKind { node, kind } if node == "a" && kind == "other"
+    ));
+    assert!(matches!(
+        import("digraph { a [kind=empty, inputs=\"in:u16\"]; }"),
+        GraphImportError::UnknownPortType { port_type, .. } if port_type == "u16"
+    ));
+    assert!(matches!(
+        import("digraph { a [kind=source]; b [kind=sink]; a:value -> b:other; }"),
+        GraphImportError::UnknownPort { node, port } if node == "b" && port == "other"
+    ));
+}
+
+#[test]
+fn rejected_connection_leaves_the_graph_unchanged() {
+    let dot = r#"
+        digraph {
+            a [kind=source];
+            b [kind=source];
+            c [kind=single];
+            a:value -> c:value;
+            b:value -> c:value;
+        }"#;
+    let (mut graph, ..) = source_sink_graph();
+    let result = GraphImport::new(&create_node).from_dot(&mut graph, dot);
+    assert!(matches!(
+        result,
+        Err(GraphImportError::RejectedConnection { from, .. }) if from == "b"
+    ));
+    assert_eq!(graph.nodes().count(), 2);
+    assert_eq!(graph.connections().count(), 0);
+}
+
+#[test]
+fn malformed_json_is_rejected() {
+    let import = |json: &str| {
+        let mut graph = empty_graph();
+        GraphImport::new(&create_node).from_json(&mut graph, json).unwrap_err()
+    };
+
+    assert!(matches!(import("{ \"nodes\": [ }"), GraphImportError::Json(_)));
+    assert!(matches!(
+        import(r#"{ "nodes": [{ "id": "a", "kind": "empty" }, { "id": "a", "kind": "empty" }] }"#),
+        GraphImportError::DuplicateNode { node } if node == "a"
+    ));
+    assert!(matches!(
+        import(r#"{ "edges": [{ "from": "a", "output": "value", "to": "b", "input": "value" }] }"#),
+        GraphImportError::UnknownNode { node } if node == "a"
+    ));
+}
@@ -0,0 +1,164 @@
+mod harness;
+
+use harness::u32_graph;
+use shine_ui::{
+    build_graph,
+    node_graph::{
+        CompileError, ExecutionError, ExecutionPlan, ExecutionState, Graph, Input, KernelContext, Node, NodeKernel,
+    },
+};
+
+/// Kernels of the test graphs: `one` outputs 1, `add` sums its inputs and `fail` fails.
+fn create_kernel(node: &Node) -> Option<Box<dyn NodeKernel>> {
+    match node.kind.as_str() {
+        "one" => Some(Box::new(|context: &mut KernelContext| {
+            context.set_output(0, 1u32);
+            Ok(())
+        })),
+        "add" => Some(Box::new(|context: &mut KernelContext| {
+            let sum = context.inputs::<u32>(0).sum::<u32>() + context.input::<u32>(1).cloned().unwrap_or(0);
+            context.set_output(0, sum);
+            Ok(())
+        })),
+        "fail" => Some(Box::new(|_: &mut KernelContext| Err("no value".to_string()))),
+        _ => None,
+    }
+}
+
+#[test]
+fn plan_is_executed_repeatedly() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "one" at (0., 100.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 50.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        total = "add" at (400., 50.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a.value -> sum.values;
+        b.value -> sum.values;
+        sum.sum -> total.values;
+        a.value -> total.offset;
+    }
+
+    let result = total.typed_output::<u32>("sum");
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[result.id()]).unwrap();
+    assert_eq!(plan.len(), 4);
+    let order: Vec<_> = plan.nodes().collect();
+    let position = |node_id| order.iter().position(|id| *id == node_id).unwrap();
+    assert!(position(a.id()) < position(sum.id()));
+    assert!(position(sum.id()) < position(total.id()));
+
+    let mut state = ExecutionState::default();
+    for _ in 0..3 {
+        plan.execute(&mut state).unwrap();
+        assert_eq!(plan.output_value(&state, result), Some(&3));
+    }
+    // only the retained outputs are kept
+    assert!(plan.value(&state, sum.output("sum")).is_none());
+}
+
+#[test]
+fn buffers_are_reused() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        b = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        c = "add" at (400., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        d = "add" at (600., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        a.value -> b.values;
+        b.sum -> c.values;
+        c.sum -> d.values;
+    }
+
+    let result = d.typed_output::<u32>("sum");
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[result.id()]).unwrap();
+    // a chain needs only the value of the previous node
+    assert_eq!(plan.buffer_count(), 2);
+
+    let mut state = ExecutionState::default();
+    plan.execute(&mut state).unwrap();
+    assert_eq!(plan.output_value(&state, result), Some(&1));
+}
+
+#[test]
+fn default_values_are_constants() {
+    let mut graph = u32_graph();
+    let sum = graph
+        .builder()
+        .node("sum")
+        .kind("add")
+        .with_input(Input::new::<u32>("values"))
+        .with_input(Input::new::<u32>("offset").with_default(5u32))
+        .output::<u32>("sum")
+        .build();
+
+    let result = sum.typed_output::<u32>("sum");
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[result.id()]).unwrap();
+    let mut state = ExecutionState::default();
+    plan.execute(&mut state).unwrap();
+    assert_eq!(plan.output_value(&state, result), Some(&5));
+}
+
+#[test]
+fn failure_stops_the_execution() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        source = "fail" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+        source.value -> sum.values;
+    }
+
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[sum.output("sum")]).unwrap();
+    let mut state = ExecutionState::default();
+    match plan.execute(&mut state) {
+        Err(ExecutionError::Failed { node_id, message }) => {
+            assert_eq!(node_id, source.id());
+            assert_eq!(message, "no value");
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+    assert!(plan.value(&state, sum.output("sum")).is_none());
+}
+
+#[test]
+fn invalid_graph_is_rejected() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+    }
+
+    // the port type has no style
+    let result = ExecutionPlan::compile(&graph, &create_kernel, &[a.output("value")]);
+    assert!(matches!(result, Err(CompileError::Invalid(report)) if report.has_errors()));
+}
+
+#[test]
+fn changes_outdate_the_plan() {
+    let mut graph = u32_graph();
+    build_graph! {
+        graph;
+        a = "one" at (0., 0.), inputs [], outputs [value: u32];
+        sum = "add" at (200., 0.), inputs [values: u32, offset: u32], outputs [sum: u32];
+    }
+
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[]).unwrap();
+    assert!(!plan.is_outdated(&graph));
+
+    // moving a node does not change the structure
+    graph.node_mut(a.id()).unwrap().location.x += 10.;
+    assert!(!plan.is_outdated(&graph));
+
+    let connection = graph.builder().connect(a.output("value"), sum.input("values")).unwrap();
+    assert!(plan.is_outdated(&graph));
+
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[]).unwrap();
+    graph.remove_connection(connection);
+    assert!(plan.is_outdated(&graph));
+
+    let plan = ExecutionPlan::compile(&graph, &create_kernel, &[]).unwrap();
+    graph.mark_changed();
+    assert!(plan.is_outdated(&graph));
+}
@@ -0,0 +1,127 @@
+mod harness;
+
+use egui::{pos2, CentralPanel, Context, RawInput};
+use harness::source_sink_graph;
+use shine_ui::node_graph::{DiagnosticLocation, Input, Node, Output, PortStyle, Severity};
+
+#[test]
+fn default_value_is_kept_on_duplicate() {
+    let input = Input::new::<u8>("value").with_default(7u8);
+    assert!(input.has_default());
+    assert_eq!(input.default_as::<u8>(), Some(&7));
+    assert_eq!(input.default_as::<u32>(), None);
+
+    let duplicate = input.duplicate().unwrap();
+    assert_eq!(duplicate.default_as::<u8>(), Some(&7));
+}
+
+#[test]
+#[should_panic]
+fn default_value_of_a_different_type_is_rejected() {
+    let _ = Input::new::<u8>("value").with_default(1f32);
+}
+
+#[test]
+fn default_value_is_edited_inline() {
+    // an editor that changes the value immediately
+    let style = PortStyle::new("u8").with_editor(|ui, value: &mut u8| {
+        *value += 1;
+        let mut response = ui.label(value.to_string());
+        response.mark_changed();
+        response
+    });
+    let mut input = Input::new::<u8>("value").with_default(7u8);
+    let duplicate = input.duplicate().unwrap();
+
+    let ctx = Context::default();
+    for is_connected in [true, false] {
+        let _ = ctx.run(RawInput::default(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| input.show(ui, 0, &style, is_connected));
+        });
+    }
+    assert_eq!(input.default_as::<u8>(), Some(&8));
+    assert_eq!(duplicate.default_as::<u8>(), Some(&7));
+}
+
+#[test]
+fn connection_above_the_maximum_is_rejected() {
+    let (mut graph, source, _) = source_sink_graph();
+    let other = graph.add_node(Node::new(
+        "other",
+        pos2(100., 300.),
+        vec![],
+        vec![Output::new::<u8>("value")],
+    ));
+    let limited = graph.add_node(Node::new(
+        "limited",
+        pos2(450., 100.),
+        vec![Input::new::<u8>("value").with_connection_limits(0, Some(1))],
+        vec![],
+    ));
+    let input_id = graph.node(limited).unwrap().input_id(0).unwrap();
+    let first = graph.node(source).unwrap().output_id(0).unwrap();
+    let second = graph.node(other).unwrap().output_id(0).unwrap();
+
+    let connection = graph.try_create_connection(input_id, first).unwrap();
+    graph.add_connection(connection);
+    assert!(graph.try_create_connection(input_id, first).is_none());
+    assert!(graph.try_create_connection(input_id, second).is_none());
+    assert_eq!(graph.input_connection_count(input_id), 1);
+}
+
+#[test]
+fn too_few_connections_are_reported() {
+    let (mut graph, source, _) = source_sink_graph();
+    let pair = graph.add_node(Node::new(
+        "pair",
+        pos2(450., 100.),
+        vec![Input::new::<u8>("value").with_connection_limits(2, None)],
+        vec![],
+    ));
+    let input_id = graph.node(pair).unwrap().input_id(0).unwrap();
+    let output_id = graph.node(source).unwrap().output_id(0).unwrap();
+    let connection = graph.try_create_connection(input_id, output_id).unwrap();
+    graph.add_connection(connection);
+
+    let report = graph.validate();
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+        report.severity(DiagnosticLocation::Port(input_id.into())),
+        Some(Severity::Error)
+    );
+}
+
+#[test]
+fn typed_ids_check_the_port_type() {
+    let (graph, source, sink) = source_sink_graph();
+    let source = graph.node(source).unwrap();
+    let sink = graph.node(sink).unwrap();
+    assert!(source.typed_output_id::<u8>(0).is_some());
+    assert!(source.typed_output_id::<u16>(0).is_none());
+    assert!(sink.typed_input_id::<u8>(0).is_some());
+    assert!(sink.typed_input_id::<u8>(1).is_none());
+}
+
+#[test]
+fn input_reads_the_value_of_the_connected_output() {
+    let (mut graph, source, _) = source_sink_graph();
+    let sink = graph.add_node(Node::new(
+        "defaulted",
+        pos2(450., 100.),
+        vec![Input::new::<u8>("value").with_default(3u8)],
+        vec![],
+    ));
+    let output_id = graph.node(source).unwrap().typed_output_id::<u8>(0).unwrap();
+    let input_id = graph.node(sink).unwrap().typed_input_id::<u8>(0).unwrap();
+
+    assert_eq!(graph.input_value(input_id), Some(&3));
+    graph.connect(output_id, input_id).unwrap();
+    graph.set_output_value(output_id, 5u8);
+    assert_eq!(graph.input_value(input_id), Some(&5));
+
+    *graph.output_value_mut(output_id).unwrap() += 1;
+    assert_eq!(graph.input_values(input_id).cloned().collect::<Vec<_>>(), vec![6]);
+
+    graph.remove_node(source);
+    assert_eq!(graph.input_value(input_id), Some(&3));
+}
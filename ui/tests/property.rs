@@ -0,0 +1,174 @@
+use egui::{CentralPanel, Color32, Context, Event, Key, Modifiers, RawInput};
+use shine_ui::node_graph::{
+    Graph, HasProperties, InteractionMode, Node, NodeData, Output, Properties, Property, PropertyPanel, PropertySet,
+    PropertyValue,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Material {
+    name: String,
+    roughness: f64,
+    color: Color32,
+    mode: usize,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            roughness: 0.5,
+            color: Color32::WHITE,
+            mode: 0,
+        }
+    }
+}
+
+impl NodeData for Material {}
+
+impl HasProperties for Material {
+    fn properties(&self) -> Option<&dyn Properties> {
+        Some(self)
+    }
+
+    fn properties_mut(&mut self) -> Option<&mut dyn Properties> {
+        Some(self)
+    }
+}
+
+impl Properties for Material {
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property::new("name", self.name.as_str()),
+            Property::new("roughness", self.roughness).with_range(0., 1.),
+            Property::new("color", self.color),
+            Property::new("mode", PropertyValue::Choice(self.mode)).with_choices(&["opaque", "blend"]),
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: PropertyValue) -> bool {
+        match (name, value) {
+            ("name", PropertyValue::Text(value)) => self.name = value,
+            ("roughness", PropertyValue::Float(value)) if (0. ..=1.).contains(&value) => self.roughness = value,
+            ("color", PropertyValue::Color(value)) => self.color = value,
+            ("mode", PropertyValue::Choice(value)) if value < 2 => self.mode = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[test]
+fn property_set_round_trip() {
+    let material = Material {
+        name: "metal".to_string(),
+        roughness: 0.2,
+        color: Color32::RED,
+        mode: 1,
+    };
+
+    let set = PropertySet::from_properties(&material);
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.get("roughness"), Some(&PropertyValue::Float(0.2)));
+
+    let json = serde_json::to_string(&set).unwrap();
+    let loaded: PropertySet = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, set);
+
+    let mut copy = Material::default();
+    assert!(loaded.apply(&mut copy).is_empty());
+    assert_eq!(copy, material);
+}
+
+#[test]
+fn rejected_properties_are_reported() {
+    let mut set = PropertySet::default();
+    set.set("name", "glass");
+    set.set("roughness", 2.);
+    set.set("removed", true);
+
+    let mut material = Material::default();
+    let mut rejected = set.apply(&mut material);
+    rejected.sort();
+    assert_eq!(rejected, vec!["removed".to_string(), "roughness".to_string()]);
+    assert_eq!(material.name, "glass");
+    assert_eq!(material.roughness, 0.5);
+}
+
+#[test]
+fn panel_shows_the_node() {
+    let mut graph = Graph::default();
+    let node_id = graph.add_node(
+        Node::new("material", (0., 0.).into(), vec![], vec![Output::new::<u32>("value")])
+            .with_data(Material::default()),
+    );
+    let revision = graph.revision();
+
+    let ctx = Context::default();
+    let mut changed = true;
+    let _ = ctx.run(RawInput::default(), |ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            changed = PropertyPanel::new(&mut graph).show(ui, node_id);
+        });
+    });
+
+    assert!(!changed);
+    assert_eq!(graph.revision(), revision);
+    let properties = graph.node(node_id).unwrap().data().properties().unwrap().properties();
+    assert_eq!(properties[0].value, PropertyValue::Text("default".to_string()));
+}
+
+/// Focus the first editable field of the panel by the keyboard and type into it.
+fn type_into_panel(interaction: InteractionMode) -> (bool, String) {
+    let mut graph = Graph::default();
+    let node_id = graph.add_node(Node::new("material", (0., 0.).into(), vec![], vec![]).with_data(Material::default()));
+
+    let ctx = Context::default();
+    let mut changed = false;
+    let events = [
+        vec![],
+        vec![Event::Key {
+            key: Key::Tab,
+            pressed: true,
+            modifiers: Modifiers::NONE,
+        }],
+        vec![Event::Text("x".to_string())],
+    ];
+    for events in events {
+        let input = RawInput {
+            events,
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                changed |= PropertyPanel::new(&mut graph)
+                    .with_interaction(interaction)
+                    .show(ui, node_id);
+            });
+        });
+    }
+    (changed, graph.node(node_id).unwrap().caption.clone())
+}
+
+#[test]
+fn read_only_panel_is_not_editable() {
+    assert_eq!(type_into_panel(InteractionMode::Full), (true, "materialx".to_string()));
+    assert_eq!(
+        type_into_panel(InteractionMode::ReadOnly),
+        (false, "material".to_string())
+    );
+}
+
+#[test]
+fn node_shows_the_properties_by_default() {
+    let ctx = Context::default();
+    let mut material = Material::default();
+    let mut shown = false;
+    let _ = ctx.run(RawInput::default(), |ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            let top = ui.cursor().top();
+            assert!(!material.show(ui, &mut vec![], &mut vec![]));
+            shown = ui.cursor().top() > top;
+        });
+    });
+    assert!(shown);
+}
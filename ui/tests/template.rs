@@ -0,0 +1,62 @@
+use egui::pos2;
+use shine_ui::{
+    build_graph,
+    node_graph::{Graph, GraphTemplate, TemplateLibrary},
+};
+
+#[test]
+fn template_keeps_the_internal_connections() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        source = "source" at (100., 100.), inputs [], outputs [value: u8];
+        filter = "filter" at (300., 150.), inputs [value: u8], outputs [value: u8];
+        sink = "sink" at (500., 100.), inputs [value: u8], outputs [];
+        source.value -> filter.value;
+        filter.value -> sink.value;
+    }
+
+    // the connection to the sink is not part of the template
+    let template = GraphTemplate::from_nodes("pair", &graph, &[source.id(), filter.id()]).unwrap();
+    assert_eq!(template.node_count(), 2);
+    assert_eq!(template.connection_count(), 1);
+
+    let new_nodes = template.instantiate(&mut graph, pos2(1000., 500.));
+    assert_eq!(new_nodes.len(), 2);
+    assert!(!new_nodes.contains(&source.id()) && !new_nodes.contains(&filter.id()));
+    assert_eq!(graph.nodes().count(), 5);
+    assert_eq!(graph.connections().count(), 3);
+
+    let new_source = graph.node(new_nodes[0]).unwrap();
+    let new_filter = graph.node(new_nodes[1]).unwrap();
+    assert_eq!(new_source.caption, "source");
+    assert_eq!(new_source.location, pos2(1000., 500.));
+    assert_eq!(new_filter.location, pos2(1200., 550.));
+    assert!(graph
+        .connections()
+        .any(|connection| connection.output_node_id() == new_nodes[0] && connection.input_node_id() == new_nodes[1]));
+}
+
+#[test]
+fn library_replaces_by_name() {
+    let mut graph = Graph::default();
+    build_graph! {
+        graph;
+        a = "a" at (0., 0.), inputs [], outputs [value: u8];
+        b = "b" at (100., 0.), inputs [value: u8], outputs [];
+        a.value -> b.value;
+    }
+
+    let mut library = TemplateLibrary::default();
+    assert!(!library.save_nodes("cluster", &graph, &[]));
+    assert!(library.save_nodes("cluster", &graph, &[a.id()]));
+    assert!(library.save_nodes("cluster", &graph, &[a.id(), b.id()]));
+    assert_eq!(library.len(), 1);
+    assert_eq!(library.get("cluster").unwrap().node_count(), 2);
+
+    assert_eq!(library.unique_name("cluster"), "cluster 1");
+    assert!(library.rename("cluster", "pair"));
+    assert!(library.get("cluster").is_none());
+    assert!(library.remove("pair").is_some());
+    assert!(library.is_empty());
+}
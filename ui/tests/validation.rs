@@ -0,0 +1,99 @@
+mod harness;
+
+use egui::{pos2, PointerButton};
+use harness::{source_sink_graph, Harness};
+use shine_ui::node_graph::{DiagnosticLocation, GraphEdit, Input, Node, Output, Severity};
+
+#[test]
+fn unconnected_required_input_is_reported() {
+    let (mut graph, _, _) = source_sink_graph();
+    let node_id = graph.add_node(Node::new(
+        "required",
+        pos2(0., 0.),
+        vec![Input::new::<u8>("value").with_required(true)],
+        vec![],
+    ));
+    let input_id = graph.node(node_id).unwrap().input_id(0).unwrap();
+
+    let report = graph.validate();
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+        report.severity(DiagnosticLocation::Port(input_id.into())),
+        Some(Severity::Error)
+    );
+}
+
+#[test]
+fn port_without_style_is_reported() {
+    let (mut graph, _, _) = source_sink_graph();
+    let node_id = graph.add_node(Node::new(
+        "unknown",
+        pos2(0., 0.),
+        vec![],
+        vec![Output::new::<u64>("value")],
+    ));
+    let output_id = graph.node(node_id).unwrap().output_id(0).unwrap();
+
+    let report = graph.validate();
+    assert!(report.has_errors());
+    assert_eq!(report.find(DiagnosticLocation::Port(output_id.into())).count(), 1);
+}
+
+#[test]
+fn connection_closing_a_cycle_is_reported() {
+    let (mut graph, _, _) = source_sink_graph();
+    let new_node = || {
+        Node::new(
+            "pass",
+            pos2(0., 0.),
+            vec![Input::new::<u8>("in")],
+            vec![Output::new::<u8>("out")],
+        )
+    };
+    let a = graph.add_node(new_node());
+    let b = graph.add_node(new_node());
+
+    for (from, to) in [(a, b), (b, a)] {
+        let output_id = graph.node(from).unwrap().output_id(0).unwrap();
+        let input_id = graph.node(to).unwrap().input_id(0).unwrap();
+        let connection = graph
+            .validator()
+            .try_create_connection(&graph, input_id, output_id)
+            .unwrap();
+        graph.add_connection(connection);
+    }
+
+    let report = graph.validate();
+    assert_eq!(report.len(), 1);
+    assert!(matches!(
+        report.diagnostics()[0].location,
+        DiagnosticLocation::Connection(_)
+    ));
+}
+
+#[test]
+fn valid_graph_has_no_diagnostics() {
+    let (graph, _, _) = source_sink_graph();
+    assert!(graph.validate().is_empty());
+}
+
+#[test]
+fn focus_centers_the_view_and_selects_the_node() {
+    let (graph, _, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    // move the sink out of the screen
+    harness.drag(pos2(400., 300.), pos2(-200., -100.), PointerButton::Middle);
+    harness.settle();
+
+    GraphEdit::focus(&harness.ctx, harness.id(), DiagnosticLocation::Node(sink));
+    harness.settle();
+
+    let view = harness.view();
+    let rect = harness.node_rect(sink);
+    assert!(
+        (rect.center() - view.screen_rect().center()).length() < 1.,
+        "{:?}",
+        rect
+    );
+    assert_eq!(view.selection().to_vec(), vec![sink]);
+}
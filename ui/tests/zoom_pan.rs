@@ -0,0 +1,107 @@
+mod harness;
+
+use egui::{pos2, vec2, Key, Modifiers, PointerButton};
+use harness::{source_sink_graph, Harness};
+
+#[test]
+fn screen_and_area_transforms_are_inverse() {
+    let (graph, _, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    harness.pinch(pos2(300., 200.), 2.5);
+    harness.settle();
+
+    let view = harness.view();
+    for pos in [pos2(0., 0.), pos2(123., -45.), pos2(-800., 600.)] {
+        let back = view.screen_to_area(view.area_to_screen(pos));
+        assert!((back - pos).length() < 1e-3, "{:?} != {:?}", back, pos);
+    }
+}
+
+#[test]
+fn wheel_zoom_keeps_the_point_under_the_pointer() {
+    let (graph, _, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let pointer = pos2(350., 250.);
+    let anchor = harness.view().screen_to_area(pointer);
+
+    harness.scroll(pointer, vec2(0., 200.));
+    harness.settle();
+
+    let view = harness.view();
+    assert!(view.zoom() > 1., "zoom: {}", view.zoom());
+    assert!((view.area_to_screen(anchor) - pointer).length() < 0.5);
+}
+
+#[test]
+fn pinch_zoom_is_applied_immediately() {
+    let (graph, _, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let pointer = pos2(200., 300.);
+    let anchor = harness.view().screen_to_area(pointer);
+
+    harness.pinch(pointer, 0.5);
+
+    let view = harness.view();
+    assert!((view.zoom() - 0.5).abs() < 1e-4, "zoom: {}", view.zoom());
+    assert!((view.area_to_screen(anchor) - pointer).length() < 0.5);
+}
+
+#[test]
+fn extreme_zoom_is_clamped() {
+    let (graph, _, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    let pointer = pos2(400., 300.);
+
+    for _ in 0..20 {
+        harness.pinch(pointer, 0.1);
+    }
+    harness.settle();
+    let min_zoom = harness.view().zoom();
+    assert!(min_zoom > 0. && min_zoom < 0.2, "zoom: {}", min_zoom);
+
+    for _ in 0..20 {
+        harness.scroll(pointer, vec2(0., 5000.));
+    }
+    harness.settle();
+    let max_zoom = harness.view().zoom();
+    assert!(
+        max_zoom.is_finite() && max_zoom > 5. && max_zoom <= 10.,
+        "zoom: {}",
+        max_zoom
+    );
+}
+
+#[test]
+fn canvas_drag_pans_the_view() {
+    let (graph, _, _) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    harness.pinch(pos2(400., 300.), 2.);
+    let pan = harness.view().pan();
+
+    // check before the release, as the momentum keeps panning after it
+    harness.drag_to(pos2(700., 100.), pos2(600., 150.), PointerButton::Middle);
+    let view = harness.view();
+    let delta = (view.pan() - pan) * view.zoom();
+    assert!((delta - vec2(-100., 50.)).length() < 1., "delta: {:?}", delta);
+
+    harness.release(PointerButton::Middle);
+    harness.settle();
+    let momentum = (harness.view().pan() - view.pan()) * view.zoom();
+    assert!(momentum.x < 0. && momentum.y > 0., "momentum: {:?}", momentum);
+}
+
+#[test]
+fn frame_shows_all_nodes() {
+    let (graph, source, sink) = source_sink_graph();
+    let mut harness = Harness::new(graph);
+    harness.pinch(pos2(0., 0.), 4.);
+    harness.move_to(pos2(400., 300.));
+    harness.key(Key::F, Modifiers::NONE);
+    harness.settle();
+
+    let view = harness.view();
+    for node_id in [source, sink] {
+        let rect = harness.node_rect(node_id);
+        assert!(view.screen_rect().contains_rect(rect), "{:?} is not visible", rect);
+    }
+}